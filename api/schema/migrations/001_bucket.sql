@@ -0,0 +1,21 @@
+-- -----------------------------------------------------
+-- Table `bucket`
+-- image containers owned by a business account (owner_type_id 0)
+-- or a community user (owner_type_id 1)
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `bucket` (
+  `id` INT NOT NULL AUTO_INCREMENT,
+  `owner_type_id` TINYINT NOT NULL,
+  `owner_id` INT NOT NULL,
+  `name` VARCHAR(63) NOT NULL,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE INDEX `owner_name_UNIQUE` (`owner_type_id` ASC, `owner_id` ASC, `name` ASC) VISIBLE,
+  INDEX `fk_bucket_owner_type_idx` (`owner_type_id` ASC) VISIBLE,
+  CONSTRAINT `fk_bucket_owner_type`
+    FOREIGN KEY (`owner_type_id`)
+    REFERENCES `user_type` (`id`)
+    ON DELETE RESTRICT
+    ON UPDATE CASCADE)
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
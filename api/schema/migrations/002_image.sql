@@ -0,0 +1,23 @@
+-- -----------------------------------------------------
+-- Table `image`
+-- image metadata, the file itself lives in the image
+-- store under storage_key
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `image` (
+  `id` INT NOT NULL AUTO_INCREMENT,
+  `bucket_id` INT NOT NULL,
+  `storage_key` VARCHAR(32) NOT NULL,
+  `filename` VARCHAR(255) NOT NULL,
+  `mime_type` VARCHAR(32) NOT NULL,
+  `size_bytes` BIGINT NOT NULL,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE INDEX `storage_key_UNIQUE` (`storage_key` ASC) VISIBLE,
+  INDEX `fk_image_bucket_idx` (`bucket_id` ASC) VISIBLE,
+  CONSTRAINT `fk_image_bucket`
+    FOREIGN KEY (`bucket_id`)
+    REFERENCES `bucket` (`id`)
+    ON DELETE RESTRICT
+    ON UPDATE CASCADE)
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
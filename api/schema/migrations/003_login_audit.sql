@@ -0,0 +1,27 @@
+-- -----------------------------------------------------
+-- Table `login_audit`
+-- logins refused because of the account status
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `login_audit` (
+  `id` INT NOT NULL AUTO_INCREMENT,
+  `user_id` INT NOT NULL,
+  `username` VARCHAR(16) NOT NULL,
+  `user_status_id` TINYINT NOT NULL,
+  `ip_address` VARCHAR(45) NULL,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  INDEX `created_at_idx` (`created_at` ASC) VISIBLE,
+  INDEX `fk_login_audit_user_idx` (`user_id` ASC) VISIBLE,
+  INDEX `fk_login_audit_user_status_idx` (`user_status_id` ASC) VISIBLE,
+  CONSTRAINT `fk_login_audit_user`
+    FOREIGN KEY (`user_id`)
+    REFERENCES `user` (`id`)
+    ON DELETE CASCADE
+    ON UPDATE CASCADE,
+  CONSTRAINT `fk_login_audit_user_status`
+    FOREIGN KEY (`user_status_id`)
+    REFERENCES `user_account_status` (`id`)
+    ON DELETE RESTRICT
+    ON UPDATE CASCADE)
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
@@ -0,0 +1,25 @@
+-- -----------------------------------------------------
+-- Table `user_session`
+-- persisted sessions keyed on the token's session key,
+-- restored on boot when SESSIONS_PERSIST is set. kept
+-- apart from `session`, which carts still hang off
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `user_session` (
+  `session_key` BINARY(16) NOT NULL,
+  `hash` BINARY(32) NOT NULL,
+  `user_id` INT NOT NULL,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `expires_at` DATETIME NOT NULL,
+  `last_used_at` DATETIME NOT NULL,
+  `ip_address` VARCHAR(45) NULL,
+  `user_agent` TEXT NULL,
+  PRIMARY KEY (`session_key`),
+  INDEX `expires_at_idx` (`expires_at` ASC) VISIBLE,
+  INDEX `fk_user_session_user_id_idx` (`user_id` ASC) VISIBLE,
+  CONSTRAINT `fk_user_session_user_id`
+    FOREIGN KEY (`user_id`)
+    REFERENCES `user` (`id`)
+    ON DELETE CASCADE
+    ON UPDATE CASCADE)
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
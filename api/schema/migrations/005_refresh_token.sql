@@ -0,0 +1,27 @@
+-- -----------------------------------------------------
+-- Table `refresh_token`
+-- single use refresh tokens, a family is every token
+-- rotated from the same login. session_key has no foreign
+-- key since sessions only reach the database when
+-- SESSIONS_PERSIST is set
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `refresh_token` (
+  `token_key` BINARY(16) NOT NULL,
+  `hash` BINARY(32) NOT NULL,
+  `family_key` BINARY(16) NOT NULL,
+  `user_id` INT NOT NULL,
+  `session_key` BINARY(16) NOT NULL,
+  `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `expires_at` DATETIME NOT NULL,
+  `used_at` DATETIME NULL,
+  PRIMARY KEY (`token_key`),
+  INDEX `family_key_idx` (`family_key` ASC) VISIBLE,
+  INDEX `session_key_idx` (`session_key` ASC) VISIBLE,
+  INDEX `fk_refresh_token_user_idx` (`user_id` ASC) VISIBLE,
+  CONSTRAINT `fk_refresh_token_user`
+    FOREIGN KEY (`user_id`)
+    REFERENCES `user` (`id`)
+    ON DELETE CASCADE
+    ON UPDATE CASCADE)
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
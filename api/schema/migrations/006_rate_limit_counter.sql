@@ -0,0 +1,14 @@
+-- -----------------------------------------------------
+-- Table `rate_limit_counter`
+-- hits per limiter, window and client address (4 or 16
+-- bytes) summed across every server instance when
+-- LIMITER_SHARED is set
+-- -----------------------------------------------------
+CREATE TABLE IF NOT EXISTS `rate_limit_counter` (
+  `limiter` VARCHAR(32) NOT NULL,
+  `rate_window` BIGINT UNSIGNED NOT NULL,
+  `rate_key` VARBINARY(16) NOT NULL,
+  `hits` INT UNSIGNED NOT NULL DEFAULT 0,
+  PRIMARY KEY (`limiter`, `rate_window`, `rate_key`))
+ENGINE = InnoDB
+DEFAULT CHARACTER SET = utf8mb4;
@@ -0,0 +1,50 @@
+use actix_web::{web,Responder};
+use serde::Serialize;
+
+use crate::types::{ApiResponse, AppState, LoginFailureSummary};
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    key: String,
+    kind: &'static str,
+    failures: u32,
+    retry_after: u64,
+    locked_out: bool
+}
+
+impl From<LoginFailureSummary> for DataContainer {
+    fn from(summary: LoginFailureSummary) -> Self {
+        DataContainer {
+            key: summary.key,
+            kind: summary.kind,
+            failures: summary.failures,
+            retry_after: summary.retry_after,
+            locked_out: summary.locked_out
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LoginFailuresList;
+
+impl LoginFailuresList {
+    /// endpoint entry
+    pub async fn logic(shared: web::Data<AppState>) -> impl Responder {
+        let summaries = match shared.login_throttle().failures() {
+            Ok(summaries) => summaries,
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        let response: Vec<DataContainer> = summaries
+            .into_iter()
+            .map(DataContainer::from)
+            .collect();
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
@@ -0,0 +1,11 @@
+mod login_failures_list;
+mod rate_limit_entries_delete;
+mod rate_limit_entries_list;
+mod rate_limit_entries_post;
+mod settings_changes_list;
+
+pub use login_failures_list::LoginFailuresList;
+pub use rate_limit_entries_delete::RateLimitEntriesDelete;
+pub use rate_limit_entries_list::RateLimitEntriesList;
+pub use rate_limit_entries_post::RateLimitEntriesPost;
+pub use settings_changes_list::SettingsChangesList;
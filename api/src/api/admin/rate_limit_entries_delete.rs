@@ -0,0 +1,58 @@
+use std::net::IpAddr;
+
+use actix_web::{web,Responder};
+use rate_limit::IpNetwork;
+use serde::Deserialize;
+
+use crate::{
+    enums::{RateLimitList, RateLimiterStatus},
+    types::{ApiResponse, AppState}
+};
+
+/// ranges name their prefix in the query, a slash can't sit in a path segment
+#[derive(Debug,Deserialize)]
+pub struct Query {
+    pub prefix: Option<u8>
+}
+
+#[derive(Debug)]
+pub struct RateLimitEntriesDelete;
+
+impl RateLimitEntriesDelete {
+    /// endpoint entry
+    pub async fn logic(path: web::Path<(RateLimitList,String)>, query: web::Query<Query>, shared: web::Data<AppState>) -> impl Responder {
+        let (list, ip_address) = path.into_inner();
+
+        let ip_address: IpAddr = match ip_address.parse() {
+            Ok(ip_address) => ip_address,
+            Err(_e) => return ApiResponse::bad_request().error()
+        };
+
+        let network = match query.prefix {
+            Some(prefix) => match IpNetwork::new(ip_address, prefix) {
+                Ok(network) => network,
+                Err(_e) => return ApiResponse::bad_request().error()
+            },
+            None => IpNetwork::host(ip_address)
+        };
+
+        let limiter = match shared.rate_limiter_instance() {
+            RateLimiterStatus::Enabled(limiter) => limiter,
+            RateLimiterStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        let result = match list {
+            RateLimitList::Blacklist => limiter.remove_network_from_blacklist(&network),
+            RateLimitList::Whitelist => limiter.remove_network_from_whitelist(&network)
+        };
+
+        match result {
+            Ok(true) => ApiResponse::success(),
+            Ok(false) => ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
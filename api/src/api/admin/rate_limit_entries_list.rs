@@ -0,0 +1,51 @@
+use actix_web::{web,Responder};
+use serde::Serialize;
+
+use crate::{
+    enums::{RateLimitList, RateLimiterStatus},
+    types::{ApiResponse, AppState}
+};
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    ip_address: String, // address or cidr range
+    ttl: u64    // seconds left on the entry
+}
+
+#[derive(Debug)]
+pub struct RateLimitEntriesList;
+
+impl RateLimitEntriesList {
+    /// endpoint entry
+    pub async fn logic(path: web::Path<RateLimitList>, shared: web::Data<AppState>) -> impl Responder {
+        // lists stay manageable while the limiter is switched off at runtime
+        let limiter = match shared.rate_limiter_instance() {
+            RateLimiterStatus::Enabled(limiter) => limiter,
+            RateLimiterStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        let entries = match path.into_inner() {
+            RateLimitList::Blacklist => limiter.network_blacklist(),
+            RateLimitList::Whitelist => limiter.network_whitelist()
+        };
+
+        let mut entries = match entries {
+            Ok(entries) => entries,
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        entries.sort_by_key(|(network, _)| *network);
+
+        let response: Vec<DataContainer> = entries
+            .into_iter()
+            .map(|(network, ttl)| DataContainer { ip_address: network.to_string(), ttl: ttl.as_secs() })
+            .collect();
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
@@ -0,0 +1,53 @@
+use actix_web::{web,Responder};
+use rate_limit::{enums::RateLimitError, IpNetwork};
+use serde::Deserialize;
+
+use crate::{
+    enums::{RateLimitList, RateLimiterStatus},
+    types::{ApiResponse, AppState}
+};
+
+#[derive(Debug,Deserialize)]
+pub struct Post {
+    pub ip_address: String,     // address or cidr range
+    pub ttl: u64    // seconds
+}
+
+#[derive(Debug)]
+pub struct RateLimitEntriesPost;
+
+impl RateLimitEntriesPost {
+    /// endpoint entry
+    pub async fn logic(path: web::Path<RateLimitList>, post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
+        let post = post.into_inner();
+
+        // input checks
+        let network: IpNetwork = match post.ip_address.parse() {
+            Ok(network) => network,
+            Err(_e) => return ApiResponse::bad_request().error()
+        };
+
+        if post.ttl == 0 {
+            return ApiResponse::bad_request().error();
+        }
+
+        let limiter = match shared.rate_limiter_instance() {
+            RateLimiterStatus::Enabled(limiter) => limiter,
+            RateLimiterStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        let result = match path.into_inner() {
+            RateLimitList::Blacklist => limiter.add_network_to_blacklist(network, post.ttl),
+            RateLimitList::Whitelist => limiter.add_network_to_whitelist(network, post.ttl)
+        };
+
+        match result {
+            Ok(()) => ApiResponse::success(),
+            Err(RateLimitError::DuplicateBlacklistEntry(_) | RateLimitError::DuplicateWhitelistEntry(_)) => ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
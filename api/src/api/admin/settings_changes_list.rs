@@ -0,0 +1,15 @@
+use actix_web::{web,Responder};
+
+use crate::types::{ApiResponse, AppState};
+
+#[derive(Debug)]
+pub struct SettingsChangesList;
+
+impl SettingsChangesList {
+    /// endpoint entry
+    pub async fn logic(shared: web::Data<AppState>) -> impl Responder {
+        ApiResponse::default()
+            .with_data(shared.settings_changes())
+            .ok()
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    enums::{Permission, RowsUpdated},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, Image, UserPermissions}
+};
+
+#[derive(Debug)]
+pub struct BucketsDelete;
+
+impl BucketsDelete {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let bucket_id = path.into_inner();
+        let database = shared.database();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let bucket = match Bucket::by_id(bucket_id, database).await {
+            Ok(Some(bucket)) => bucket,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // other owners' buckets are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_delete();
+        if bucket.access(&user, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        // buckets must be emptied before they can be deleted
+        match Image::count_by_bucket(bucket.id, database).await {
+            Ok(0) => {},
+            Ok(_) => return ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        }
+
+        match Bucket::delete_by_id(bucket.id, database).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => ApiResponse::success(),
+            Ok(RowsUpdated::NoRowsUpdated) => ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
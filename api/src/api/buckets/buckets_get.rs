@@ -0,0 +1,62 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::Serialize;
+
+use crate::{
+    enums::{BucketOwner, Permission},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, UserPermissions}
+};
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer<'a> {
+    id: i64,
+    owner: &'a BucketOwner,
+    name: &'a str,
+    created_at: String
+}
+
+impl<'a> From<&'a Bucket> for DataContainer<'a> {
+    fn from(bucket: &'a Bucket) -> Self {
+        DataContainer {
+            id: bucket.id,
+            owner: &bucket.owner,
+            name: &bucket.name,
+            created_at: bucket.created_at.to_rfc3339()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BucketsGet;
+
+impl BucketsGet {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let bucket_id = path.into_inner();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let bucket = match Bucket::by_id(bucket_id, shared.database()).await {
+            Ok(Some(bucket)) => bucket,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // other owners' buckets are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_read();
+        if bucket.access(&user, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        ApiResponse::default()
+            .with_data(DataContainer::from(&bucket))
+            .ok()
+    }
+}
@@ -0,0 +1,45 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    traits::{ToBucketOwner, ToSessionUser},
+    types::{ApiResponse, AppState, Bucket}
+};
+
+use super::buckets_get::DataContainer;
+
+#[derive(Debug)]
+pub struct BucketsList;
+
+impl BucketsList {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, shared: web::Data<AppState>) -> impl Responder {
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // system users do not own buckets
+        let owner = match user.to_bucket_owner() {
+            Ok(owner) => owner,
+            Err(_e) => return ApiResponse::forbidden().error()
+        };
+
+        let buckets = match Bucket::by_owner(&owner, shared.database()).await {
+            Ok(buckets) => buckets,
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        let response: Vec<DataContainer> = buckets
+            .iter()
+            .map(DataContainer::from)
+            .collect();
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
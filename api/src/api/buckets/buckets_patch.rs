@@ -0,0 +1,60 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::Deserialize;
+
+use crate::{
+    enums::{Permission, RowsUpdated},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, UserPermissions}
+};
+
+#[derive(Debug,Deserialize)]
+pub struct Patch {
+    pub name: String
+}
+
+#[derive(Debug)]
+pub struct BucketsPatch;
+
+impl BucketsPatch {
+    /// endpoint entry, renames a bucket
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, patch: web::Json<Patch>, shared: web::Data<AppState>) -> impl Responder {
+        let bucket_id = path.into_inner();
+        let database = shared.database();
+
+        // input checks
+        if Bucket::validate_name(&patch.name).is_err() {
+            return ApiResponse::bad_request().error();
+        }
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let bucket = match Bucket::by_id(bucket_id, database).await {
+            Ok(Some(bucket)) => bucket,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // other owners' buckets are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_write();
+        if bucket.access(&user, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        match Bucket::rename(bucket.id, &patch.name, database).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => ApiResponse::success(),
+            Ok(RowsUpdated::NoRowsUpdated) => ApiResponse::not_found().error(),
+            Err(e) if e.is_unique_violation() => ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    traits::{ToBucketOwner, ToSessionUser},
+    types::{ApiResponse, AppState, Bucket}
+};
+
+#[derive(Debug,Deserialize)]
+pub struct Post {
+    pub name: String
+}
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    id: i64
+}
+
+#[derive(Debug)]
+pub struct BucketsPost;
+
+impl BucketsPost {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
+        // input checks
+        if Bucket::validate_name(&post.name).is_err() {
+            return ApiResponse::bad_request().error();
+        }
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // buckets are created for the caller's own account
+        let owner = match user.to_bucket_owner() {
+            Ok(owner) => owner,
+            Err(_e) => return ApiResponse::forbidden().error()
+        };
+
+        let id = match Bucket::into_db(&owner, &post.name, shared.database()).await {
+            Ok(id) => id,
+            Err(e) if e.is_unique_violation() => return ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        ApiResponse::default()
+            .with_code(201)
+            .with_message("created".to_string())
+            .with_data(DataContainer { id })
+            .ok()
+    }
+}
@@ -0,0 +1,11 @@
+mod buckets_delete;
+mod buckets_get;
+mod buckets_list;
+mod buckets_patch;
+mod buckets_post;
+
+pub use buckets_delete::BucketsDelete;
+pub use buckets_get::BucketsGet;
+pub use buckets_list::BucketsList;
+pub use buckets_patch::BucketsPatch;
+pub use buckets_post::BucketsPost;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    enums::{ImageStoreStatus, Permission, RowsUpdated},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, Image, UserPermissions}
+};
+
+#[derive(Debug)]
+pub struct ImagesDelete;
+
+impl ImagesDelete {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let image_id = path.into_inner();
+        let database = shared.database();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let image_store = match shared.image_store() {
+            ImageStoreStatus::Enabled(store) => store,
+            ImageStoreStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        let image = match Image::by_id(image_id, database).await {
+            Ok(Some(image)) => image,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // ownership is inherited from the bucket
+        let access = match Bucket::by_id(image.bucket_id, database).await {
+            Ok(Some(bucket)) => bucket.access(&user, &UserPermissions::default().with_admin_delete()),
+            Ok(None) => Permission::None,
+            Err(_e) => return ApiResponse::server_error().error()
+        };
+
+        if access == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        // metadata first, an orphaned file is harmless but a dangling row is not
+        match Image::delete_by_id(image.id, database).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => {},
+            Ok(RowsUpdated::NoRowsUpdated) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        }
+
+        if image_store.delete(&image.storage_key).await.is_err() {
+            // log here
+        }
+
+        ApiResponse::success()
+    }
+}
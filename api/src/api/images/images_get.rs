@@ -0,0 +1,68 @@
+use actix_web::{web, http::header::{ContentDisposition, DispositionParam, DispositionType}, HttpRequest, HttpResponse};
+
+use crate::{
+    enums::{ImageStoreStatus, Permission},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, Image, UserPermissions}
+};
+
+#[derive(Debug)]
+pub struct ImagesGet;
+
+impl ImagesGet {
+    /// endpoint entry, streams the image body
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> HttpResponse {
+        let image_id = path.into_inner();
+        let database = shared.database();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let image_store = match shared.image_store() {
+            ImageStoreStatus::Enabled(store) => store,
+            ImageStoreStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        let image = match Image::by_id(image_id, database).await {
+            Ok(Some(image)) => image,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // ownership is inherited from the bucket
+        let access = match Bucket::by_id(image.bucket_id, database).await {
+            Ok(Some(bucket)) => bucket.access(&user, &UserPermissions::default().with_admin_read()),
+            Ok(None) => Permission::None,
+            Err(_e) => return ApiResponse::server_error().error()
+        };
+
+        if access == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        let image_stream = match image_store.get(&image.storage_key).await {
+            Ok(image_stream) => image_stream,
+            Err(_e) => {
+                // log here
+                return ApiResponse::not_found().error();
+            }
+        };
+
+        let content_disposition = ContentDisposition {
+            disposition: DispositionType::Inline,
+            parameters: vec![DispositionParam::Filename(image.filename.clone())]
+        };
+
+        HttpResponse::Ok()
+            .content_type(image.mime_type.as_str())
+            .insert_header(content_disposition)
+            .no_chunking(image.size_bytes as u64)
+            .streaming(image_stream)
+    }
+}
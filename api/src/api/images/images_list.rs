@@ -0,0 +1,84 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::Permission,
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, Bucket, Image, UserPermissions}
+};
+
+#[derive(Debug,Deserialize)]
+pub struct Query {
+    pub bucket_id: i64
+}
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer<'a> {
+    id: i64,
+    bucket_id: i64,
+    filename: &'a str,
+    mime_type: &'a str,
+    size_bytes: i64,
+    created_at: String
+}
+
+impl<'a> From<&'a Image> for DataContainer<'a> {
+    fn from(image: &'a Image) -> Self {
+        DataContainer {
+            id: image.id,
+            bucket_id: image.bucket_id,
+            filename: &image.filename,
+            mime_type: &image.mime_type,
+            size_bytes: image.size_bytes,
+            created_at: image.created_at.to_rfc3339()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImagesList;
+
+impl ImagesList {
+    /// endpoint entry, lists image metadata in a bucket
+    pub async fn logic(req: HttpRequest, query: web::Query<Query>, shared: web::Data<AppState>) -> impl Responder {
+        let database = shared.database();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let bucket = match Bucket::by_id(query.bucket_id, database).await {
+            Ok(Some(bucket)) => bucket,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // other owners' buckets are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_read();
+        if bucket.access(&user, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        let images = match Image::by_bucket(bucket.id, database).await {
+            Ok(images) => images,
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        let response: Vec<DataContainer> = images
+            .iter()
+            .map(DataContainer::from)
+            .collect();
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
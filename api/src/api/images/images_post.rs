@@ -0,0 +1,160 @@
+use actix_multipart::{Field, Multipart};
+use actix_web::{web::{self, Bytes, BytesMut}, HttpRequest, Responder};
+use futures::StreamExt;
+use serde::Serialize;
+use std::num::NonZeroU8;
+
+use crate::{
+    enums::{Error, ImageStoreStatus, Permission, Uuid},
+    traits::{ToImageFormat, ToSessionUser},
+    types::{ApiResponse, AppState, Bucket, Image, UserPermissions}
+};
+
+const MAX_IMAGE_BYTES:usize = 20 * 1024 * 1024;     // 20MiB
+const MAX_FIELD_BYTES:usize = 256;                  // text fields
+const STORAGE_KEY_LENGTH:u8 = 32;
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    id: i64
+}
+
+/// collected multipart form
+#[derive(Debug,Default)]
+struct Upload {
+    bucket_id: Option<i64>,
+    filename: Option<String>,
+    bytes: Option<Bytes>
+}
+
+#[derive(Debug)]
+pub struct ImagesPost;
+
+impl ImagesPost {
+
+    /// reads a multipart field into memory, failing once it grows past the limit
+    async fn read_field(field: &mut Field, limit: usize) -> Result<Bytes,Error> {
+        let mut buf = BytesMut::new();
+
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| Error::StdError(e.to_string()))?;
+
+            if buf.len() + chunk.len() > limit {
+                return Err(Error::ImageTooLarge);
+            }
+
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(buf.freeze())
+    }
+
+    /// collects the `bucket_id` and `file` fields, unknown fields are discarded
+    async fn read_upload(mut payload: Multipart) -> Result<Upload,Error> {
+        let mut upload = Upload::default();
+
+        while let Some(field) = payload.next().await {
+            let mut field = field.map_err(|e| Error::StdError(e.to_string()))?;
+
+            match field.name() {
+                Some("bucket_id") => {
+                    let bytes = ImagesPost::read_field(&mut field, MAX_FIELD_BYTES).await?;
+                    upload.bucket_id = std::str::from_utf8(&bytes)
+                        .ok()
+                        .and_then(|s| s.trim().parse().ok());
+                },
+                Some("file") => {
+                    upload.filename = field
+                        .content_disposition()
+                        .and_then(|cd| cd.get_filename())
+                        .map(|name| name.chars().take(255).collect());
+                    upload.bytes = Some(ImagesPost::read_field(&mut field, MAX_IMAGE_BYTES).await?);
+                },
+                _ => {
+                    let _ = ImagesPost::read_field(&mut field, MAX_IMAGE_BYTES).await?;
+                }
+            }
+        }
+
+        Ok(upload)
+    }
+
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, payload: Multipart, shared: web::Data<AppState>) -> impl Responder {
+        let database = shared.database();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        let image_store = match shared.image_store() {
+            ImageStoreStatus::Enabled(store) => store,
+            ImageStoreStatus::Disabled => return ApiResponse::service_unavailable().error()
+        };
+
+        // read form
+        let upload = match ImagesPost::read_upload(payload).await {
+            Ok(upload) => upload,
+            Err(Error::ImageTooLarge) => return ApiResponse::payload_too_large().error(),
+            Err(_e) => return ApiResponse::bad_request().error()
+        };
+
+        let (bucket_id, bytes) = match (upload.bucket_id, upload.bytes) {
+            (Some(bucket_id), Some(bytes)) => (bucket_id, bytes),
+            (_, _) => return ApiResponse::bad_request().error()
+        };
+
+        // content type is sniffed from the file itself
+        let format = match bytes.as_ref().to_image_format() {
+            Ok(format) => format,
+            Err(_e) => return ApiResponse::unsupported_media_type().error()
+        };
+
+        // bucket ownership check
+        let bucket = match Bucket::by_id(bucket_id, database).await {
+            Ok(Some(bucket)) => bucket,
+            Ok(None) => return ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        let admin_rights = UserPermissions::default().with_admin_write();
+        if bucket.access(&user, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        // store file
+        let storage_key = match Uuid::web_safe_with_nums(NonZeroU8::new(STORAGE_KEY_LENGTH)) {
+            Ok(Uuid::WebSafeNums(key)) => key,
+            _ => return ApiResponse::server_error().error()
+        };
+
+        let size_bytes = bytes.len();
+        let filename = upload.filename.unwrap_or_else(|| storage_key.clone());
+
+        if image_store.put(&storage_key, bytes).await.is_err() {
+            // log here
+            return ApiResponse::server_error().error();
+        }
+
+        // store metadata, remove the orphaned file on failure
+        let id = match Image::into_db(bucket.id, &storage_key, &filename, format, size_bytes, database).await {
+            Ok(id) => id,
+            Err(_e) => {
+                // log here
+                let _ = image_store.delete(&storage_key).await;
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        ApiResponse::default()
+            .with_code(201)
+            .with_message("created".to_string())
+            .with_data(DataContainer { id })
+            .ok()
+    }
+}
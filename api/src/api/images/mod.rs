@@ -0,0 +1,9 @@
+mod images_delete;
+mod images_get;
+mod images_list;
+mod images_post;
+
+pub use images_delete::ImagesDelete;
+pub use images_get::ImagesGet;
+pub use images_list::ImagesList;
+pub use images_post::ImagesPost;
\ No newline at end of file
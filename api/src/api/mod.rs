@@ -1,4 +1,8 @@
+pub mod admin;
+pub mod buckets;
 mod health;
+pub mod images;
 pub mod sessions;
+pub mod users;
 
 pub use health::HealthCheck;
\ No newline at end of file
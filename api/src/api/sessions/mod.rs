@@ -1,5 +1,13 @@
 mod sessions_delete;
+mod sessions_list;
 mod sessions_post;
+mod sessions_refresh;
+mod sessions_revoke;
+mod sessions_revoke_all;
 
 pub use sessions_delete::SessionsDelete;
-pub use sessions_post::SessionsPost;
\ No newline at end of file
+pub use sessions_list::SessionsList;
+pub use sessions_post::SessionsPost;
+pub use sessions_refresh::SessionsRefresh;
+pub use sessions_revoke::SessionsRevoke;
+pub use sessions_revoke_all::SessionsRevokeAll;
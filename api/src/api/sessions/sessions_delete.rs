@@ -2,8 +2,8 @@ use actix_web::{web,HttpRequest,Responder};
 
 use crate::{
     enums::SessionControllerStatus,
-    traits::ToHeaderAuthToken,
-    types::{ApiResponse, AppState}
+    traits::{FromBase64, ToHeaderAuthToken, ToKeySet},
+    types::{ApiResponse, AppState, RefreshToken}
 };
 
 #[derive(Debug)]
@@ -25,9 +25,16 @@ impl SessionsDelete {
         };
 
         // delete session
-        match session_controller.delete(&token) {
-            Ok(_) => ApiResponse::success(),
-            Err(_) => ApiResponse::server_error().error()
+        if session_controller.delete(&token).await.is_err() {
+            return ApiResponse::server_error().error();
         }
+
+        // signing out also ends the refresh token family behind the session
+        if let Ok(key) = token.as_str().vec_from_base64_url().and_then(|token| token.to_key())
+            && let Err(_e) = RefreshToken::revoke_by_session(&key, shared.database()).await {
+            // log here
+        }
+
+        ApiResponse::success()
     }
 }
\ No newline at end of file
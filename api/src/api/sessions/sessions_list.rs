@@ -0,0 +1,81 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::Serialize;
+
+use crate::{
+    enums::{Permission, SessionControllerStatus},
+    traits::{ToHeaderAuthToken, ToSessionUser},
+    types::{ApiResponse, AppState, SessionController, SessionSummary, UserPermissions}
+};
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    id: String,
+    created_at: String,
+    last_used: String,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    current: bool
+}
+
+impl DataContainer {
+    fn new(summary: SessionSummary, current_id: Option<&str>) -> Self {
+        DataContainer {
+            current: current_id == Some(summary.id.as_str()),
+            id: summary.id,
+            created_at: summary.created_at.to_rfc3339(),
+            last_used: summary.last_used.to_rfc3339(),
+            ip_address: summary.ip_address,
+            user_agent: summary.user_agent
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SessionsList;
+
+impl SessionsList {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let user_id = path.into_inner();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // other users' sessions are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_read();
+        if user.access_to_user(user_id, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(controller) => controller,
+            SessionControllerStatus::Disabled => return ApiResponse::server_error().error()
+        };
+
+        let summaries = match session_controller.sessions_by_user(user_id) {
+            Ok(summaries) => summaries,
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // flag the session making this request
+        let current_id = req
+            .to_auth()
+            .ok()
+            .and_then(|token| SessionController::session_id(&token).ok());
+
+        let response: Vec<DataContainer> = summaries
+            .into_iter()
+            .map(|summary| DataContainer::new(summary, current_id.as_deref()))
+            .collect();
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
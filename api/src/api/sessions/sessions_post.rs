@@ -1,7 +1,9 @@
-use actix_web::{web,Responder};
+use std::time::Duration;
+
+use actix_web::{http::header::{HeaderValue, RETRY_AFTER, USER_AGENT},web,HttpRequest,HttpResponse,Responder};
 use serde::{Deserialize, Serialize};
 
-use crate::{enums::{AuthorizationStatus, Error, SessionControllerStatus, User}, traits::VerifyPassword, types::{ApiResponse, AppState, DatabaseConnection, KeySet, Session}};
+use crate::{enums::{AuthorizationStatus, Error, LoginDecision, SessionControllerStatus, User, UserAccountStatus}, traits::{ToClientIp, VerifyPassword}, types::{ApiResponse, AppState, DatabaseConnection, KeySet, LoginAudit, RefreshToken, Session}};
 
 #[derive(Debug,Deserialize)]
 pub struct Post {
@@ -11,7 +13,9 @@ pub struct Post {
 
 #[derive(Debug,Serialize)]
 pub struct DataContainer<'a> {
-    token: &'a str
+    token: &'a str,
+    #[serde(skip_serializing_if="Option::is_none")]
+    refresh_token: Option<&'a str>
 }
 
 #[derive(Debug)]
@@ -31,11 +35,91 @@ impl SessionsPost {
         }
     }
 
+    /// records a login refused because of the account status
+    async fn audit(req: &HttpRequest, user: &User, shared: &AppState) {
+        let audit = LoginAudit {
+            user_id: user.id(),
+            username: user.username().to_string(),
+            status: user.status().clone(),
+            ip_address: req.to_client_ip(shared).map(|ip| ip.to_string())
+        };
+
+        if let Err(_e) = audit.into_db(shared.database()).await {
+            // log here
+        }
+    }
+
+    /// 429 telling the client how long to wait before the next attempt
+    fn throttled(wait: Duration) -> HttpResponse {
+        let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+
+        let mut response = ApiResponse::rate_limited().error();
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+
+        response
+    }
+
+    /// starts a session and issues its refresh token, continuing the family of an exchanged refresh token.
+    /// returns the session token and the refresh token, the session stands alone when the refresh token can't be stored
+    pub async fn issue(req: &HttpRequest, user: User, previous: Option<&RefreshToken>, shared: &AppState) -> Result<(String,Option<String>),Error> {
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(s) => s,
+            SessionControllerStatus::Disabled => return Err(Error::SessionControllerDisabled)
+        };
+
+        // create session with the client it was issued to
+        let key_set = KeySet::new()?;
+        let ip_address = req.to_client_ip(shared).map(|ip| ip.to_string());
+        let user_agent = req
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|agent| agent.to_str().ok())
+            .map(|agent| agent.to_string());
+
+        let user_id = user.id();
+        let policy = session_controller.policy(&user);
+        let session = Session::new(&key_set, user, session_controller.now())
+            .with_policy(policy)
+            .with_client(ip_address, user_agent);
+
+        // push to controller and accept base64 token
+        let token = session_controller.insert(session, &key_set).await?;
+
+        // refresh tokens are stored hashed, the secret only leaves in the response
+        let refresh_key_set = KeySet::new()?;
+        let refresh_token = match previous {
+            Some(previous) => RefreshToken::successor(&refresh_key_set, previous, key_set.key),
+            None => RefreshToken::new(&refresh_key_set, user_id, key_set.key)
+        };
+
+        if let Err(_e) = refresh_token.into_db(shared.database()).await {
+            // log here
+            return Ok((token, None));
+        }
+
+        Ok((token, Some(refresh_key_set.token())))
+    }
+
     /// endpoint entry
-    pub async fn logic(post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
+    pub async fn logic(req: HttpRequest, post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
         // get database connection
         let database = shared.database();
 
+        // throttle guessing per username and per ip before touching the database
+        let throttle = shared.login_throttle();
+        let ip_address = req.to_client_ip(&shared).map(|ip| ip.to_string());
+
+        match throttle.check(&post.username, ip_address.as_deref()) {
+            Ok(LoginDecision::Allowed) => {},
+            Ok(LoginDecision::Throttled(wait)) => return SessionsPost::throttled(wait),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        }
+
         // extract user from database
         let user =  {
             match SessionsPost::search_for_user(&post.username, database).await {
@@ -43,6 +127,7 @@ impl SessionsPost {
                     if let Some(user) = user_opt {
                         user
                     } else {
+                        let _ = throttle.record_failure(&post.username, ip_address.as_deref());
                         return ApiResponse::unauthorized().ok();
                     }
                 },
@@ -55,30 +140,28 @@ impl SessionsPost {
 
         // verify password against hash from database
         if user.verify_password(&post.password) == AuthorizationStatus::Unauthorized {
+            let _ = throttle.record_failure(&post.username, ip_address.as_deref());
             return ApiResponse::unauthorized().ok();
         }
 
-        // create a key set
-        let key_set = match  KeySet::new() {
-            Ok(set) => set,
-            Err(_e) => return ApiResponse::unauthorized().ok()
-        };
-
-        // get session controller
-        let session_controller = match shared.sessions() {
-            SessionControllerStatus::Enabled(s) => s,
-            SessionControllerStatus::Disabled => {
-                // log here
+        let _ = throttle.record_success(&post.username);
+
+        // account status is only revealed to callers holding the correct password
+        match user.status() {
+            UserAccountStatus::Enabled => {},
+            UserAccountStatus::Disabled | UserAccountStatus::Suspended => {
+                SessionsPost::audit(&req, &user, &shared).await;
+                return ApiResponse::account_unavailable().error();
+            },
+            UserAccountStatus::Banned => {
+                SessionsPost::audit(&req, &user, &shared).await;
                 return ApiResponse::unauthorized().ok();
             }
-        };
-
-        // create session
-        let session = Session::new(&key_set, user);
+        }
 
-        // push to controller and accept base64 token
-        let token = match session_controller.insert(session, &key_set) {
-            Ok(t) => t,
+        // start the session and its refresh token family
+        let (token, refresh_token) = match SessionsPost::issue(&req, user, None, &shared).await {
+            Ok(tokens) => tokens,
             Err(_e) => {
                 // log here
                 return ApiResponse::unauthorized().ok();
@@ -87,7 +170,8 @@ impl SessionsPost {
 
         // format and send response
         let response = DataContainer {
-            token: &token
+            token: &token,
+            refresh_token: refresh_token.as_deref()
         };
 
         ApiResponse::default()
@@ -0,0 +1,136 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::sessions::SessionsPost,
+    enums::{ExpiredStatus, RowsUpdated, SessionControllerStatus, User, UserAccountStatus, VerificationStatus},
+    traits::{FromBase64, ToBase64, ToKeySet},
+    types::{ApiResponse, AppState, KeySet, RefreshToken}
+};
+
+#[derive(Debug,Deserialize)]
+pub struct Post {
+    pub refresh_token: String
+}
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer<'a> {
+    token: &'a str,
+    #[serde(skip_serializing_if="Option::is_none")]
+    refresh_token: Option<&'a str>
+}
+
+#[derive(Debug)]
+pub struct SessionsRefresh;
+
+impl SessionsRefresh {
+
+    /// a replayed refresh token means the family leaked, so every token and session it issued is revoked
+    async fn revoke_family(refresh_token: &RefreshToken, shared: &AppState) {
+        let database = shared.database();
+
+        if let SessionControllerStatus::Enabled(session_controller) = shared.sessions() {
+            match refresh_token.family_sessions(database).await {
+                Ok(session_keys) => {
+                    for session_key in session_keys {
+                        let _ = session_controller.revoke(refresh_token.user_id, &session_key.to_base64_url()).await;
+                    }
+                },
+                Err(_e) => {
+                    // log here
+                }
+            }
+        }
+
+        if let Err(_e) = refresh_token.revoke_family(database).await {
+            // log here
+        }
+    }
+
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
+        let database = shared.database();
+
+        // decode from base64 to Vec<u8> and extract segments
+        let (key, secret) = match post.refresh_token.as_str().vec_from_base64_url() {
+            Ok(token) => match (token.to_key(), token.to_secret()) {
+                (Ok(key), Ok(secret)) => (key, secret),
+                _ => return ApiResponse::unauthorized().ok()
+            },
+            Err(_e) => return ApiResponse::unauthorized().ok()
+        };
+
+        let refresh_token = match RefreshToken::by_key(&key, database).await {
+            Ok(Some(refresh_token)) => refresh_token,
+            Ok(None) => return ApiResponse::unauthorized().ok(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::unauthorized().ok();
+            }
+        };
+
+        // constant time hash check, guessed keys never touch the family
+        if KeySet::verify(&key, &secret, &refresh_token.hash) == VerificationStatus::Unverified {
+            return ApiResponse::unauthorized().ok();
+        }
+
+        // reuse detection, an earlier exchange already consumed this token
+        if refresh_token.used_at.is_some() {
+            SessionsRefresh::revoke_family(&refresh_token, &shared).await;
+            return ApiResponse::unauthorized().ok();
+        }
+
+        if refresh_token.is_expired() == ExpiredStatus::Expired {
+            return ApiResponse::unauthorized().ok();
+        }
+
+        // a concurrent exchange won the race, treat it as a replay
+        match refresh_token.mark_used(database).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => {},
+            Ok(RowsUpdated::NoRowsUpdated) => {
+                SessionsRefresh::revoke_family(&refresh_token, &shared).await;
+                return ApiResponse::unauthorized().ok();
+            },
+            Err(_e) => {
+                // log here
+                return ApiResponse::unauthorized().ok();
+            }
+        }
+
+        // the exchanged token's session ends with it
+        if let SessionControllerStatus::Enabled(session_controller) = shared.sessions()
+            && let Err(_e) = session_controller.revoke(refresh_token.user_id, &refresh_token.session_key.to_base64_url()).await {
+            // log here
+        }
+
+        // accounts disabled since the last exchange lose the whole family
+        let user = match User::by_id(refresh_token.user_id, database).await {
+            Ok(Some(user)) if *user.status() == UserAccountStatus::Enabled => user,
+            Ok(_) => {
+                SessionsRefresh::revoke_family(&refresh_token, &shared).await;
+                return ApiResponse::unauthorized().ok();
+            },
+            Err(_e) => {
+                // log here
+                return ApiResponse::unauthorized().ok();
+            }
+        };
+
+        let (token, next_refresh_token) = match SessionsPost::issue(&req, user, Some(&refresh_token), &shared).await {
+            Ok(tokens) => tokens,
+            Err(_e) => {
+                // log here
+                return ApiResponse::unauthorized().ok();
+            }
+        };
+
+        let response = DataContainer {
+            token: &token,
+            refresh_token: next_refresh_token.as_deref()
+        };
+
+        ApiResponse::default()
+            .with_data(response)
+            .ok()
+    }
+}
@@ -0,0 +1,51 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    enums::{Permission, RowsUpdated, SessionControllerStatus},
+    traits::{FromBase64, ToSessionUser},
+    types::{ApiResponse, AppState, RefreshToken, UserPermissions}
+};
+
+#[derive(Debug)]
+pub struct SessionsRevoke;
+
+impl SessionsRevoke {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<(i64,String)>, shared: web::Data<AppState>) -> impl Responder {
+        let (user_id, session_id) = path.into_inner();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // other users' sessions are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_delete();
+        if user.access_to_user(user_id, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(controller) => controller,
+            SessionControllerStatus::Disabled => return ApiResponse::server_error().error()
+        };
+
+        match session_controller.revoke(user_id, &session_id).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => {
+                // a revoked session cannot be brought back with its refresh token
+                if let Ok(Ok(key)) = session_id.as_str().vec_from_base64_url().map(<[u8;16]>::try_from)
+                    && let Err(_e) = RefreshToken::revoke_by_session(&key, shared.database()).await {
+                    // log here
+                }
+
+                ApiResponse::success()
+            },
+            Ok(RowsUpdated::NoRowsUpdated) => ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::not_found().error()
+            }
+        }
+    }
+}
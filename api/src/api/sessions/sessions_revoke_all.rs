@@ -0,0 +1,48 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    enums::{Permission, SessionControllerStatus},
+    traits::ToSessionUser,
+    types::{ApiResponse, AppState, RefreshToken, UserPermissions}
+};
+
+#[derive(Debug)]
+pub struct SessionsRevokeAll;
+
+impl SessionsRevokeAll {
+    /// endpoint entry, logs a user out everywhere
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let user_id = path.into_inner();
+
+        // extract session user
+        let user = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // other users' sessions are reported as missing
+        let admin_rights = UserPermissions::default().with_admin_delete();
+        if user.access_to_user(user_id, &admin_rights) == Permission::None {
+            return ApiResponse::not_found().error();
+        }
+
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(controller) => controller,
+            SessionControllerStatus::Disabled => return ApiResponse::server_error().error()
+        };
+
+        // revoking zero sessions is still a success
+        if let Err(_e) = RefreshToken::revoke_by_user(user_id, shared.database()).await {
+            // log here
+            return ApiResponse::server_error().error();
+        }
+
+        match session_controller.revoke_all(user_id).await {
+            Ok(_) => ApiResponse::success(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod users_delete;
+mod users_get;
+mod users_patch;
+mod users_post;
+
+pub use users_delete::UsersDelete;
+pub use users_get::UsersGet;
+pub use users_patch::UsersPatch;
+pub use users_post::UsersPost;
\ No newline at end of file
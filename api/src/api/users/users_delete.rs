@@ -0,0 +1,40 @@
+use actix_web::{web,HttpRequest,Responder};
+
+use crate::{
+    enums::{Permission, RowsUpdated, User},
+    traits::HasPermission,
+    types::{ApiResponse, AppState, UserPermissions}
+};
+
+use super::users_get::UsersGet;
+
+#[derive(Debug)]
+pub struct UsersDelete;
+
+impl UsersDelete {
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let user_id = path.into_inner();
+
+        let admin_rights = UserPermissions::default().with_admin_delete();
+        let (caller, target) = match UsersGet::target(&req, &shared, user_id, &admin_rights).await {
+            Ok(users) => users,
+            Err(response) => return response
+        };
+
+        // accounts holding rights the caller doesn't hold are out of reach
+        if caller.permissions().has_permission(target.permissions()) == Permission::None {
+            return ApiResponse::forbidden().error();
+        }
+
+        // remove user and linked records
+        match User::delete_by_id(user_id, shared.database()).await {
+            Ok(RowsUpdated::RowsUpdated(_)) => ApiResponse::success(),
+            Ok(RowsUpdated::NoRowsUpdated) => ApiResponse::not_found().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
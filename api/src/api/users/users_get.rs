@@ -0,0 +1,85 @@
+use actix_web::{web,HttpRequest,HttpResponse,Responder};
+use serde::Serialize;
+
+use crate::{
+    enums::{Permission, User, UserAccountStatus, UserType},
+    traits::{ToSessionUser, ToUserType},
+    types::{ApiResponse, AppState, UserPermissions}
+};
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer<'a> {
+    id: i64,
+    user_type: UserType,
+    username: &'a str,
+    status: &'a UserAccountStatus,
+    #[serde(skip_serializing_if="Option::is_none")]
+    business_account_id: Option<i64>,
+    permissions: &'a UserPermissions
+}
+
+impl<'a> DataContainer<'a> {
+    /// public view of a user, the password hash is never included
+    fn from_user(user: &'a User) -> Option<Self> {
+        let container = DataContainer {
+            id: user.id(),
+            user_type: user.to_user_type().ok()?,
+            username: user.username(),
+            status: user.status(),
+            business_account_id: user.business_account_id(),
+            permissions: user.permissions()
+        };
+
+        Some(container)
+    }
+}
+
+#[derive(Debug)]
+pub struct UsersGet;
+
+impl UsersGet {
+    /// resolves the session user and the account a request acts on. callers reach their own account,
+    /// system users holding `admin_rights` reach any account, everything else is reported as missing.
+    /// returns the session user and the target
+    pub(super) async fn target(req: &HttpRequest, shared: &AppState, user_id: i64, admin_rights: &UserPermissions) -> Result<(User,User),HttpResponse> {
+        // extract session user
+        let caller = match req.to_session_user(shared) {
+            Ok(user) => user,
+            Err(_e) => return Err(ApiResponse::unauthorized().error())
+        };
+
+        // other users are reported as missing
+        if caller.access_to_user(user_id, admin_rights) == Permission::None {
+            return Err(ApiResponse::not_found().error());
+        }
+
+        // extract user from database
+        match User::by_id(user_id, shared.database()).await {
+            Ok(Some(target)) => Ok((caller, target)),
+            Ok(None) => Err(ApiResponse::not_found().error()),
+            Err(_e) => {
+                // log here
+                Err(ApiResponse::server_error().error())
+            }
+        }
+    }
+
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, shared: web::Data<AppState>) -> impl Responder {
+        let user_id = path.into_inner();
+
+        let admin_rights = UserPermissions::default().with_admin_read();
+        let user = match UsersGet::target(&req, &shared, user_id, &admin_rights).await {
+            Ok((_caller, target)) => target,
+            Err(response) => return response
+        };
+
+        // format and send response
+        match DataContainer::from_user(&user) {
+            Some(response) => ApiResponse::default()
+                .with_data(response)
+                .ok(),
+            None => ApiResponse::server_error().error()
+        }
+    }
+}
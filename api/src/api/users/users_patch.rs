@@ -0,0 +1,77 @@
+use actix_web::{web,HttpRequest,Responder};
+use serde::Deserialize;
+
+use crate::{
+    enums::{Permission, UserAccountStatus},
+    traits::HasPermission,
+    types::{ApiResponse, AppState, UserPermissions, users::UserUpdate}
+};
+
+use super::{users_get::UsersGet, users_post::{MIN_PASSWORD_LENGTH, UsersPost}};
+
+#[derive(Debug,Deserialize)]
+pub struct Patch {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub status: Option<UserAccountStatus>,
+    pub permissions: Option<UserPermissions>
+}
+
+#[derive(Debug)]
+pub struct UsersPatch;
+
+impl UsersPatch {
+
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, path: web::Path<i64>, patch: web::Json<Patch>, shared: web::Data<AppState>) -> impl Responder {
+        let user_id = path.into_inner();
+        let patch = patch.into_inner();
+
+        // input checks
+        if patch.username.as_ref().is_some_and(|u| !UsersPost::valid_username(u)) {
+            return ApiResponse::bad_request().error();
+        }
+
+        if patch.password.as_ref().is_some_and(|p| p.len() < MIN_PASSWORD_LENGTH) {
+            return ApiResponse::bad_request().error();
+        }
+
+        let admin_rights = UserPermissions::default().with_admin_write();
+        let (caller, target) = match UsersGet::target(&req, &shared, user_id, &admin_rights).await {
+            Ok(users) => users,
+            Err(response) => return response
+        };
+
+        // accounts holding rights the caller doesn't hold are out of reach
+        if caller.permissions().has_permission(target.permissions()) == Permission::None {
+            return ApiResponse::forbidden().error();
+        }
+
+        // privilege escalation check
+        if let Some(permissions) = &patch.permissions
+            && let Err(response) = UsersPost::can_grant(&req, &shared, permissions).await {
+            return response;
+        }
+
+        let update = UserUpdate {
+            username: patch.username.map(|u| u.trim().to_string()),
+            password: patch.password,
+            status: patch.status,
+            permissions: patch.permissions
+        };
+
+        if update.is_empty() {
+            return ApiResponse::bad_request().error();
+        }
+
+        // the user exists, values equal to the current ones update no rows but still succeed
+        match update.into_db(user_id, shared.database()).await {
+            Ok(_rows_updated) => ApiResponse::success(),
+            Err(e) if e.is_unique_violation() => ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                ApiResponse::server_error().error()
+            }
+        }
+    }
+}
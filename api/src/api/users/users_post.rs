@@ -0,0 +1,127 @@
+use actix_web::{web,HttpRequest,HttpResponse,Responder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::{Permission, SessionControllerStatus, UserType},
+    traits::{ToHeaderAuthToken, ToSessionUser},
+    types::{ApiResponse, AppState, UserPermissions, users::NewUser}
+};
+
+pub(super) const MIN_PASSWORD_LENGTH:usize = 8;
+const MAX_USERNAME_LENGTH:usize = 16;
+const MAX_EMAIL_LENGTH:usize = 255;
+
+#[derive(Debug,Deserialize)]
+pub struct Post {
+    pub email: String,
+    pub username: String,
+    pub password: String,
+    pub user_type: UserType,
+    pub business_account_id: Option<i64>,
+    #[serde(default)]
+    pub permissions: UserPermissions
+}
+
+#[derive(Debug,Serialize)]
+pub struct DataContainer {
+    id: i64
+}
+
+#[derive(Debug)]
+pub struct UsersPost;
+
+impl UsersPost {
+
+    /// usernames are non-empty once trimmed and fit the username column
+    pub(super) fn valid_username(username: &str) -> bool {
+        let username = username.trim();
+        !username.is_empty() && username.chars().count() <= MAX_USERNAME_LENGTH
+    }
+
+    /// callers may only grant permissions they hold themselves, returns the response to send when
+    /// they can't. nothing can be granted without a session controller
+    pub(super) async fn can_grant(req: &HttpRequest, shared: &AppState, requested: &UserPermissions) -> Result<(),HttpResponse> {
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(controller) => controller,
+            SessionControllerStatus::Disabled => return Err(ApiResponse::service_unavailable().error())
+        };
+
+        let token = match req.to_auth() {
+            Ok(t) => t,
+            Err(_e) => return Err(ApiResponse::forbidden().error())
+        };
+
+        match session_controller.permission_check(&token, requested, shared.database()).await {
+            Ok(Permission::Granted) => Ok(()),
+            _ => Err(ApiResponse::forbidden().error())
+        }
+    }
+
+    /// endpoint entry
+    pub async fn logic(req: HttpRequest, post: web::Json<Post>, shared: web::Data<AppState>) -> impl Responder {
+        let post = post.into_inner();
+
+        // input checks
+        if !UsersPost::valid_username(&post.username) || post.password.len() < MIN_PASSWORD_LENGTH {
+            return ApiResponse::bad_request().error();
+        }
+
+        let email = post.email.trim();
+        if !email.contains('@') || email.chars().count() > MAX_EMAIL_LENGTH {
+            return ApiResponse::bad_request().error();
+        }
+
+        // extract session user
+        let caller = match req.to_session_user(&shared) {
+            Ok(user) => user,
+            Err(_e) => return ApiResponse::unauthorized().error()
+        };
+
+        // business accounts default to the caller's own business account
+        let business_account_id = match post.user_type {
+            UserType::Business => post.business_account_id.or(caller.business_account_id()),
+            _ => post.business_account_id
+        };
+
+        if post.user_type == UserType::Business && business_account_id.is_none() {
+            return ApiResponse::bad_request().error();
+        }
+
+        // tenant check, only system admins create system accounts or accounts in other business accounts
+        let admin_rights = UserPermissions::default().with_admin_write();
+        if caller.access_to_create(&post.user_type, business_account_id, &admin_rights) == Permission::None {
+            return ApiResponse::forbidden().error();
+        }
+
+        // privilege escalation check
+        if let Err(response) = UsersPost::can_grant(&req, &shared, &post.permissions).await {
+            return response;
+        }
+
+        let new_user = NewUser {
+            email: email.to_string(),
+            username: post.username.trim().to_string(),
+            password: post.password,
+            user_type: post.user_type,
+            business_account_id,
+            permissions: post.permissions
+        };
+
+        // write person, user, username, user type link and permissions in one transaction
+        let id = match new_user.into_db(shared.database()).await {
+            Ok(id) => id,
+            Err(e) if e.is_unique_violation() => return ApiResponse::conflict().error(),
+            Err(_e) => {
+                // log here
+                return ApiResponse::server_error().error();
+            }
+        };
+
+        // format and send response
+        ApiResponse::default()
+            .with_code(201)
+            .with_message("created".to_string())
+            .with_data(DataContainer { id })
+            .ok()
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+/// owner of a bucket, stored as (owner_type_id, owner_id)
+#[derive(Copy,Clone,Debug,PartialEq,Serialize)]
+pub enum BucketOwner {
+    Business(i64),  // 0, business account id
+    Community(i64)  // 1, community user id
+}
+
+impl BucketOwner {
+    /// database owner type id, matches the user type id
+    pub fn type_id(&self) -> i8 {
+        match self {
+            BucketOwner::Business(_) => 0,
+            BucketOwner::Community(_) => 1
+        }
+    }
+
+    /// database owner id
+    pub fn id(&self) -> i64 {
+        match self {
+            BucketOwner::Business(id) => *id,
+            BucketOwner::Community(id) => *id
+        }
+    }
+}
\ No newline at end of file
@@ -22,6 +22,10 @@ pub enum Error {
     #[from]
     OsError(rand::rand_core::OsError),
 
+    /// derived from `rate_limit::enums::RateLimitError` for limiter errors
+    #[from]
+    RateLimit(rate_limit::enums::RateLimitError),
+
     /// derived from `sqlx::Error` for database errors
     #[from]
     Sqlx(sqlx::Error),
@@ -41,20 +45,36 @@ pub enum Error {
     /// Utf8 errors are generated during decryption when Vec<u8> is converted to plain text
     #[from]
     FromUtf8Error(FromUtf8Error),
+    BucketNameInvalid,                  // bucket names must be 3-63 characters of a-z, 0-9, '-', '_' or '.'
+    BucketOwnerNotSupported,            // system users cannot own buckets
+    BucketOwnerOutOfBounds,             // generated when a database owner type id cannot be parsed into a BucketOwner
+    BusinessAccountRequired,            // business users cannot be created without a business account id
     DatabaseConnection(String),         // failed database connection with the message passed back by the database itself
     DatabaseConnectionTestFailed,       // generated during a test of a new database connection
+    ForwardedHeaderUnknown,             // trusted proxy headers are X_FORWARDED_FOR or FORWARDED
+    ImageStorageKeyInvalid,             // image storage keys must be non-empty and alphanumeric
+    ImageTooLarge,                      // uploaded image exceeded the maximum upload size
     MalformedAuthorizationToken,        // authorization token did not 
     MissingAuthorizationBearerInHeader, // authorization bearer was not present during an authorization check
     PemCertFileReadSizeMismatch,        // generated when the buffer size does not match the size returned from the file read
+    PoisonedLoginThrottle,              // login throttle map could not be locked
     PoisonedSessionList,                // session shard could not be locked
+    RateLimitCounterInvalid,            // a shared rate limit counter key was not a 4 or 16 byte address
+    RefreshTokenRecordInvalid,          // a persisted refresh token key, family or hash had the wrong length
     ZeroLengthUUIDFound,                // uuids cannot be zero length, zero length found
     ServerCrash(String),                // generated if the HttpServer itself were to crash
     ServerModeOutOfRange,               // generated when the ToServerMode cannot match a database server mode value
+    SessionControllerDisabled,          // a session lookup was attempted while the session controller is disabled
+    SessionIdInvalid,                   // a public session id did not decode to a session key
+    SessionNotFound,                    // the session token did not match a live session
+    SessionPolicyInvalid,               // session policies are "idle_secs,lifetime_secs" with 0 < idle <= lifetime
+    SessionRecordInvalid,               // a persisted session key or hash had the wrong length
     SessionTokenLengthTooLong,          // client has provided a session token longer than required
     SessionTokenLengthTooShort,         // client has provided a session token shorter than required
     SystemSettingsNotSet,               // generated on startup when attempting to change a system while it's set to None
     SystemSettingsRecordNotReturned,    // a system settings record was not available in the database
     SystemFlagOutOfRange,               // generated when the ToSystemFlag trait cannot match a database system flag value 
+    UnsupportedImageFormat,             // uploaded file was not a gif, jpeg, png or webp image
     UserAccountStatusOutOfBounds,       // generated when ToUserAccountStatus cannot parse a value into a UserAccountStatus enum
     UserTypeOutOfBounds,                // generated when a user type id (database) cannot be parsed into a user type
     WrongUuidTypeForSessionHash,        // session hash requires a crypto uuid
@@ -63,6 +83,16 @@ pub enum Error {
     DevError(String),
 }
 
+impl Error {
+    /// true when the database rejected a write because of a unique key, e.g. a duplicate username
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            Error::Sqlx(e) => e.as_database_error().is_some_and(|db_error| db_error.is_unique_violation()),
+            _ => false
+        }
+    }
+}
+
 impl std::error::Error for Error {}
 
 impl Display for Error {
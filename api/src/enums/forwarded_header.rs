@@ -0,0 +1,7 @@
+/// header trusted proxies write the client address into, the other one is never read
+#[derive(Copy,Clone,Debug,Default,PartialEq)]
+pub enum ForwardedHeader {
+    #[default]
+    XForwardedFor,  // X-Forwarded-For, nginx and most load balancers
+    Forwarded       // standard Forwarded header, RFC 7239
+}
@@ -0,0 +1,20 @@
+/// supported image upload formats
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum ImageFormat {
+    Gif,
+    Jpeg,
+    Png,
+    Webp
+}
+
+impl ImageFormat {
+    /// mime type stored with the image metadata and sent on download
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Webp => "image/webp"
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+use crate::traits::ImageStore;
+
+#[derive(Debug)]
+pub enum ImageStoreStatus {
+    Disabled,
+    Enabled(Box<dyn ImageStore>)
+}
\ No newline at end of file
@@ -0,0 +1,8 @@
+use std::time::Duration;
+
+/// outcome of a login throttle check
+#[derive(Clone,Debug,PartialEq)]
+pub enum LoginDecision {
+    Allowed,
+    Throttled(Duration),    // time until the next attempt is accepted
+}
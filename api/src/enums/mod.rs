@@ -1,11 +1,17 @@
 mod api_result;
 mod authorization_status;
+mod bucket_owner;
 mod connection_status;
 mod error;
 mod expired_status;
+mod forwarded_header;
+mod image_format;
+mod image_store_status;
+mod login_decision;
 mod permission;
 mod master_password;
 mod primary_command;
+mod rate_limit_list;
 mod rate_limit_status;
 mod rows_updated;
 mod server_mode;
@@ -16,16 +22,23 @@ mod user_type;
 mod uuid;
 mod refresh_status;
 mod session_controller_status;
+mod session_store_status;
 mod verification_status;
 
 pub use api_result::ApiResult;
 pub use authorization_status::AuthorizationStatus;
+pub use bucket_owner::BucketOwner;
 pub use connection_status::ConnectionStatus;
 pub use error::Error;
 pub use expired_status::ExpiredStatus;
+pub use forwarded_header::ForwardedHeader;
+pub use image_format::ImageFormat;
+pub use image_store_status::ImageStoreStatus;
+pub use login_decision::LoginDecision;
 pub use master_password::MasterPassword;
 pub use permission::Permission;
 pub use primary_command::PrimaryCommand;
+pub use rate_limit_list::RateLimitList;
 pub use rate_limit_status::RateLimiterStatus;
 pub use rows_updated::RowsUpdated;
 pub use server_mode::ServerMode;
@@ -36,4 +49,5 @@ pub use user::User;
 pub use user_type::UserType;
 pub use refresh_status::RefreshStatus;
 pub use session_controller_status::SessionControllerStatus;
+pub use session_store_status::SessionStoreStatus;
 pub use verification_status::VerificationStatus;
\ No newline at end of file
@@ -1,4 +1,6 @@
-#[derive(Copy,Clone,Debug,PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy,Clone,Debug,PartialEq,Serialize,Deserialize)]
 pub enum Permission {
     None,       // default permissions state
     Granted,    // explicitly granted
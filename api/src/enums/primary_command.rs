@@ -1,17 +1,21 @@
 /// simple command line arguments to load the correct environment vars
+use std::collections::HashMap;
+
 use clap::Subcommand;
 use rate_limit::{
     enums::{TimeWindow,RefillRate},
-    types::RateLimitBuilder
+    types::{RateLimitBuilder,RateLimiter}
 };
 
 use crate::{
-    enums::{Error, RateLimiterStatus, SessionControllerStatus, SystemFlag},
-    types::{AppState,Env, SessionController}
+    enums::{Error, ImageStoreStatus, RateLimiterStatus, ServerMode, SessionControllerStatus, SessionStoreStatus, SystemFlag},
+    types::{AppState, DatabaseConnection, Env, LocalImageStore, RateLimitPolicy, RateLimitStore, SessionController, SessionStore}
 };
 
 type Result<T> = std::result::Result<T,Error>;
 
+const GLOBAL_LIMITER:&str = "global";
+
 #[derive(Clone,Debug,Subcommand)]
 pub enum PrimaryCommand {
     Dev,      // loads settings for use on a localhost
@@ -20,7 +24,7 @@ pub enum PrimaryCommand {
 
 impl PrimaryCommand {
 
-    fn build_rate_limiter(env: &Env) -> RateLimiterStatus {
+    fn build_rate_limiter(env: &Env, database: &DatabaseConnection) -> RateLimiterStatus {
         // settings
         let threads = env.server_threads;
         let rate = env.limiter_refill_rate;
@@ -32,22 +36,56 @@ impl PrimaryCommand {
         };
 
         // initialize
-        let limiter = RateLimitBuilder::default()
+        let builder = RateLimitBuilder::default()
             .with_initial_capacity(env.limiter_initial_capacity)
             .with_tokens_per_bucket(env.limiter_tokens_per_bucket)
             .with_refill_rate(refill_rate)
-            .shard_into(threads)
-            .build();
+            .with_ipv4_prefix(env.limiter_ipv4_prefix)
+            .with_ipv6_prefix(env.limiter_ipv6_prefix)
+            .with_algorithm(env.limiter_algorithm)
+            .shard_into(threads);
+
+        let limiter = RateLimitStore::attach(builder, env, database, GLOBAL_LIMITER).build();
 
         RateLimiterStatus::Enabled(Box::new(limiter))
     }
 
-    fn build_session_controller(env: &Env) -> SessionControllerStatus {
+    /// one limiter per named route policy
+    fn build_route_limiters(env: &Env, database: &DatabaseConnection) -> HashMap<&'static str,RateLimiter> {
+        RateLimitPolicy::all()
+            .into_iter()
+            .map(|policy| (policy.name, policy.build(env, database)))
+            .collect()
+    }
+
+    async fn build_session_controller(env: &Env, database: &DatabaseConnection) -> Result<SessionControllerStatus> {
         let capacity = env.sessions_initial_capacity;
         let threads = env.server_threads;
-        let session_controller = SessionController::new(capacity, threads);
+        let store = match env.sessions_persist {
+            SystemFlag::Enabled => SessionStoreStatus::Enabled(Box::new(SessionStore::new(database.clone()))),
+            SystemFlag::Disabled => SessionStoreStatus::Disabled
+        };
+
+        let session_controller = SessionController::new(capacity, threads)
+            .with_store_status(store)
+            .with_policies(env.sessions_policies)
+            .with_rotation(env.sessions_rotate);
+
+        // restore sessions written before the last shutdown
+        let _restored = session_controller.rehydrate(database).await?;
+
+        Ok(SessionControllerStatus::Enabled(Box::new(session_controller)))
+    }
 
-        SessionControllerStatus::Enabled(Box::new(session_controller))
+    /// image endpoints stay disabled without a store path
+    fn build_image_store(env: &Env) -> Result<ImageStoreStatus> {
+        match &env.image_store_path {
+            Some(path) => {
+                let image_store = LocalImageStore::new(path)?;
+                Ok(ImageStoreStatus::Enabled(Box::new(image_store)))
+            },
+            None => Ok(ImageStoreStatus::Disabled)
+        }
     }
 
     /// loads settings for local developement
@@ -55,14 +93,20 @@ impl PrimaryCommand {
 
         println!("\nwarning: server running in dev mode\n");
 
-        let limiter = PrimaryCommand::build_rate_limiter(env);
-        let sessions = PrimaryCommand::build_session_controller(env);
+        let images = PrimaryCommand::build_image_store(env)?;
+
+        // initialize app state, shared limiters count in the database
+        let app_state = AppState::new(env).await?;
+        let limiter = PrimaryCommand::build_rate_limiter(env, app_state.database());
+        let route_limiters = PrimaryCommand::build_route_limiters(env, app_state.database());
+        let sessions = PrimaryCommand::build_session_controller(env, app_state.database()).await?;
 
-        // initialize app state
-        let app_state = AppState::new(env)
-            .await?
+        let app_state = app_state
             .with_rate_limit_status(limiter)
-            .with_session_status(sessions);
+            .with_route_limiters(route_limiters)
+            .with_session_status(sessions)
+            .with_image_store_status(images)
+            .with_server_mode(ServerMode::Development);
 
         Ok(app_state)
     }
@@ -71,22 +115,21 @@ impl PrimaryCommand {
 
         println!("\nserver running in production mode\n");
         
-        let app_state = AppState::new(env)
-            .await?
+        let images = PrimaryCommand::build_image_store(env)?;
+
+        // limiter and sessions are always built, the rate limiter flag switches them on and off at runtime
+        let app_state = AppState::new(env).await?;
+        let limiter = PrimaryCommand::build_rate_limiter(env, app_state.database());
+        let route_limiters = PrimaryCommand::build_route_limiters(env, app_state.database());
+        let sessions = PrimaryCommand::build_session_controller(env, app_state.database()).await?;
+
+        let app_state = app_state
+            .with_rate_limit_status(limiter)
+            .with_route_limiters(route_limiters)
+            .with_session_status(sessions)
+            .with_image_store_status(images)
             .with_database_settings()
             .await?;
-        
-        // check flag and load limiter if enabled
-        let app_state = match app_state.settings().load_rate_limiter_service {
-            SystemFlag::Enabled => {
-                let limiter = PrimaryCommand::build_rate_limiter(env);
-                let sessions = PrimaryCommand::build_session_controller(env);
-                app_state
-                    .with_rate_limit_status(limiter)
-                    .with_session_status(sessions)
-            },
-            SystemFlag::Disabled => app_state
-        };
 
         Ok(app_state)
     }
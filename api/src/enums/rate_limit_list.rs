@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+/// rate limiter list an admin request targets, taken from the path
+#[derive(Clone,Copy,Debug,Deserialize,PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitList {
+    Blacklist,
+    Whitelist
+}
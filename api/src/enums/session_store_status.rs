@@ -0,0 +1,7 @@
+use crate::types::SessionStore;
+
+#[derive(Debug)]
+pub enum SessionStoreStatus {
+    Disabled,
+    Enabled(Box<SessionStore>)
+}
@@ -1,10 +1,11 @@
 use sqlx::prelude::FromRow;
 
 use crate::{
-    traits::ToUserType,
-    enums::{Error,UserType},
+    traits::{HasPermission, ToUpdatedResult, ToUserType},
+    enums::{Error,Permission,RowsUpdated,UserAccountStatus,UserType},
     types::{
         DatabaseConnection,
+        UserPermissions,
         users::{BusinessUser,CommunityUser,SystemUser}
     }
 };
@@ -26,6 +27,81 @@ pub enum User {
 
 impl User {
 
+    /// user id getter
+    pub fn id(&self) -> i64 {
+        match self {
+            User::Business(u) => u.id,
+            User::Community(u) => u.id,
+            User::System(u) => u.id
+        }
+    }
+
+    /// username getter
+    pub fn username(&self) -> &str {
+        match self {
+            User::Business(u) => &u.username,
+            User::Community(u) => &u.username,
+            User::System(u) => &u.username
+        }
+    }
+
+    /// account status getter
+    pub fn status(&self) -> &UserAccountStatus {
+        match self {
+            User::Business(u) => &u.status,
+            User::Community(u) => &u.status,
+            User::System(u) => &u.status
+        }
+    }
+
+    /// user type getter
+    pub fn user_type(&self) -> UserType {
+        match self {
+            User::Business(_) => UserType::Business,
+            User::Community(_) => UserType::Community,
+            User::System(_) => UserType::System
+        }
+    }
+
+    /// business account getter, only business users belong to one
+    pub fn business_account_id(&self) -> Option<i64> {
+        match self {
+            User::Business(u) => Some(u.business_account_id),
+            _ => None
+        }
+    }
+
+    /// permissions getter
+    pub fn permissions(&self) -> &UserPermissions {
+        match self {
+            User::Business(u) => &u.permissions,
+            User::Community(u) => &u.permissions,
+            User::System(u) => &u.permissions
+        }
+    }
+
+    /// granted when acting on the user's own account, or as a system user holding the admin rights
+    pub fn access_to_user(&self, user_id: i64, admin_rights: &UserPermissions) -> Permission {
+        match self {
+            _ if self.id() == user_id => Permission::Granted,
+            User::System(u) => u.permissions.has_permission(admin_rights),
+            _ => Permission::None
+        }
+    }
+
+    /// granted when the user may create an account of `user_type` inside `business_account_id`. system users
+    /// holding the admin rights create any account, other users never create system accounts and only
+    /// create business accounts inside their own business account
+    pub fn access_to_create(&self, user_type: &UserType, business_account_id: Option<i64>, admin_rights: &UserPermissions) -> Permission {
+        match (self, user_type) {
+            (User::System(u), _) => u.permissions.has_permission(admin_rights),
+            (_, UserType::System) => Permission::None,
+            (_, UserType::Business) if business_account_id.is_some() && business_account_id == self.business_account_id() => Permission::Granted,
+            (_, UserType::Community) => Permission::Granted,
+            _ => Permission::None
+        }
+    }
+
     /// builds a business user
     async fn business_user(user_id: i64, database: &DatabaseConnection) -> Result<Option<User>> {
         let user_opt = BusinessUser::by_id(user_id,database).await?;
@@ -99,4 +175,123 @@ impl User {
             Ok(None)
         }
     }
-}
\ No newline at end of file
+
+    /// try to get user by user id
+    pub async fn by_id(user_id: i64, database: &DatabaseConnection) -> Result<Option<User>> {
+        let sql = "SELECT id as user_id, user_type_id FROM `user` WHERE id = ?";
+        let helper_opt:Option<UserDatabaseHelper> = sqlx::query_as(sql)
+            .bind(user_id)
+            .fetch_optional(&database.pool)
+            .await?;
+
+        if let Some(record) = helper_opt {
+            Self::parse_user_type(&record,database).await
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// removes a user and all of its linked records in a single transaction
+    pub async fn delete_by_id(user_id: i64, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let mut tx = database.pool.begin().await?;
+
+        let _ = UserPermissions::delete_as_transaction(user_id, &mut tx).await?;
+
+        // user type link tables and username
+        for sql in [
+            "DELETE FROM `business_account_users` WHERE user_id = ?",
+            "DELETE FROM `community_users` WHERE user_id = ?",
+            "DELETE FROM `system_users` WHERE user_id = ?",
+            "DELETE FROM `username` WHERE user_id = ?"
+        ] {
+            sqlx::query(sql)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let sql = "DELETE FROM `user` WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // the person record shares the user id and holds the email
+        let sql = "DELETE FROM `person` WHERE id = ?";
+        sqlx::query(sql)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(rows_affected.to_updated_result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::users::{BusinessUser, SystemUser};
+
+    use super::*;
+
+    /// users reach their own account, only system admins reach other accounts
+    #[test]
+    fn user_access() {
+        let admin_rights = UserPermissions::default().with_admin_read();
+        let system_user = |id: i64, permissions: UserPermissions| User::System(SystemUser{
+            id,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions
+        });
+
+        let user = system_user(1, UserPermissions::default());
+        assert_eq!(user.access_to_user(1, &admin_rights), Permission::Granted);
+        assert_eq!(user.access_to_user(2, &admin_rights), Permission::None);
+
+        let admin = system_user(1, admin_rights);
+        assert_eq!(admin.access_to_user(2, &admin_rights), Permission::Granted);
+        assert_eq!(admin.access_to_user(2, &UserPermissions::default().with_admin_delete()), Permission::None);
+    }
+
+    /// business users create accounts inside their own business account only, system accounts need a system admin
+    #[test]
+    fn tenant_scoped_create() {
+        let admin_rights = UserPermissions::default().with_admin_write();
+        let business_user = User::Business(BusinessUser{
+            id: 1,
+            business_account_id: 10,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions: UserPermissions::default().with_users_write()
+        });
+
+        assert_eq!(business_user.access_to_create(&UserType::Business, Some(10), &admin_rights), Permission::Granted);
+        assert_eq!(business_user.access_to_create(&UserType::Community, None, &admin_rights), Permission::Granted);
+
+        // cross-tenant and system accounts are rejected
+        assert_eq!(business_user.access_to_create(&UserType::Business, Some(11), &admin_rights), Permission::None);
+        assert_eq!(business_user.access_to_create(&UserType::Business, None, &admin_rights), Permission::None);
+        assert_eq!(business_user.access_to_create(&UserType::System, None, &admin_rights), Permission::None);
+
+        let system_user = |permissions: UserPermissions| User::System(SystemUser{
+            id: 2,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions
+        });
+
+        let admin = system_user(admin_rights);
+        assert_eq!(admin.access_to_create(&UserType::Business, Some(11), &admin_rights), Permission::Granted);
+        assert_eq!(admin.access_to_create(&UserType::System, None, &admin_rights), Permission::Granted);
+
+        let user = system_user(UserPermissions::default().with_users_write());
+        assert_eq!(user.access_to_create(&UserType::Business, Some(11), &admin_rights), Permission::None);
+        assert_eq!(user.access_to_create(&UserType::System, None, &admin_rights), Permission::None);
+    }
+}
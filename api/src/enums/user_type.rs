@@ -1,4 +1,6 @@
-#[derive(Clone,Debug,PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
 pub enum UserType {
     Business,
     Community,
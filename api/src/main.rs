@@ -11,7 +11,7 @@ use clap::Parser;
 // internal types
 use {
     enums::{Error,PrimaryCommand},
-    types::{ApiServer,Cli,Env,RateLimitSweeper,RouteCollection,SessionSweeper}
+    types::{ApiServer,Cli,Env,LoginThrottleSweeper,RateLimitSnapshot,RateLimitSweeper,RouteCollection,SessionSweeper,SettingsReloader}
 };
 
 type Result<T> = std::result::Result<T,Error>;
@@ -33,6 +33,9 @@ async fn main() -> Result<()> {
     // move state into ARC ref
     let arc_state = actix_web::web::Data::new(initial_state);
 
+    // pick up rate limiter state from before the last shutdown
+    RateLimitSnapshot::restore(&arc_state, &env);
+
     // add api versions here ↴
     let collection = RouteCollection;
 
@@ -40,10 +43,21 @@ async fn main() -> Result<()> {
     {
         let () = SessionSweeper::run(&arc_state).await;
         let () = RateLimitSweeper::run(&arc_state).await;
+        let () = LoginThrottleSweeper::run(&arc_state).await;
+
+        // dev servers don't load settings from the database
+        if let PrimaryCommand::Prod = run_command {
+            let () = SettingsReloader::run(&arc_state).await;
+        }
     }
 
     // build and run server ↴
-    let server = ApiServer::run(run_command, arc_state, collection);
+    let server = ApiServer::run(run_command, arc_state.clone(), collection);
+    let result = server.await;
+
+    // graceful shutdown, send unsynced counts and keep rate limiter state for the next boot
+    RateLimitSweeper::flush(&arc_state).await;
+    RateLimitSnapshot::save(&arc_state, &env);
 
-    server.await // win
+    result // win
 }
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::rc::Rc;
+use actix_web::{
+    body::{EitherBody, BoxBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderValue, RETRY_AFTER},
+    web::Data,
+    Error
+};
+use futures::future::{ok, LocalBoxFuture, Ready};
+use std::task::{Context, Poll};
+
+use crate::{
+    enums::{Permission, ServerMode, SessionControllerStatus},
+    types::{ApiResponse, AppState, AuthorizationToken}
+};
+
+const RETRY_AFTER_SECS:u32 = 300;               // 5 minutes
+const EXEMPT_PATHS:[&str;1] = ["/v1/health"];
+
+/// target for the middleware service
+#[derive(Debug,Default)]
+pub struct MaintenanceMiddleware;
+
+impl<S,B> Transform<S, ServiceRequest> for MaintenanceMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = MaintenanceService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(MaintenanceService {
+            service: Rc::new(service),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct MaintenanceService<S> {
+    service: Rc<S>,
+}
+
+impl<S> MaintenanceService<S> {
+    /// health checks stay reachable so load balancers don't drop the node
+    fn is_exempt_path(path: &str) -> bool {
+        EXEMPT_PATHS.contains(&path)
+    }
+
+    /// admins keep working through a maintenance window
+    fn logic(shared: &Data<AppState>, req: &ServiceRequest) -> Permission {
+        if shared.settings().server_mode != ServerMode::Maintenance {
+            return Permission::Granted;
+        }
+
+        if MaintenanceService::<S>::is_exempt_path(req.path()) {
+            return Permission::Granted;
+        }
+
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(sessions) => sessions,
+            SessionControllerStatus::Disabled => return Permission::None
+        };
+
+        let token = match AuthorizationToken::extract(req) {
+            Ok(t) => t,
+            Err(_e) => return Permission::None
+        };
+
+        match session_controller.user(&token) {
+            Ok(Some(user)) => user.permissions().any_admin(),
+            _ => Permission::None
+        }
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for MaintenanceService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    // wraps ServiceResponse<B> in an EitherBody
+    // success: B, fail: BoxBody
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let permission_status = req
+            .app_data()
+            .map_or(Permission::Granted, |shared: &Data<AppState>| MaintenanceService::<S>::logic(shared, &req));
+
+        // return early with a Service Unavailable response
+        if permission_status == Permission::None {
+            let mut response = ApiResponse::service_unavailable().error();
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+
+            // map fail into BoxBody
+            let res = req
+                .into_response(response)
+                .map_into_right_body();
+
+            return Box::pin(async move { Ok(res) });
+        }
+
+        // return the result of the success branch
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            // map the success branch into the B
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// only the health check bypasses maintenance mode
+    #[test]
+    fn exempt_paths() {
+        assert!(MaintenanceService::<()>::is_exempt_path("/v1/health"));
+        assert!(!MaintenanceService::<()>::is_exempt_path("/v1/health/"));
+        assert!(!MaintenanceService::<()>::is_exempt_path("/v1/sessions"));
+        assert!(!MaintenanceService::<()>::is_exempt_path("/v1/images"));
+    }
+}
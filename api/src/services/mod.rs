@@ -1,5 +1,7 @@
+mod maintenance_service;
 mod rate_limit_service;
 mod route_lock_service;
 
+pub use maintenance_service::MaintenanceMiddleware;
 pub use rate_limit_service::RateLimitMiddleware;
 pub use route_lock_service::{RouteLock,RouteLockService};
\ No newline at end of file
@@ -1,20 +1,43 @@
-use std::rc::Rc;
+use std::{rc::Rc, time::Duration};
 use actix_web::{
     body::{EitherBody, BoxBody},
-    dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, RETRY_AFTER},
     web::Data,
-    HttpResponse,
-    Error
+    Error,
+    HttpRequest
 };
 use futures::future::{ok, LocalBoxFuture, Ready};
-use rate_limit::enums::Decision;
+use rate_limit::{enums::Decision, Quota};
 use std::task::{Context, Poll};
 
-use crate::{enums::RateLimiterStatus, types::AppState};
+use crate::{enums::RateLimiterStatus, traits::ToClientIp, types::{ApiResponse, AppState, RateLimitPolicy}};
 
-/// target for the middleware service
+/// target for the middleware service, the app-wide limiter unless a named policy is attached.
+/// routes carry one or the other so named budgets never draw from the app-wide one
 #[derive(Debug,Default)]
-pub struct RateLimitMiddleware;
+pub struct RateLimitMiddleware {
+    policy: Option<RateLimitPolicy>
+}
+
+impl RateLimitMiddleware {
+    pub const LIMIT_HEADER:&'static str = "ratelimit-limit";           // bucket capacity
+    pub const REMAINING_HEADER:&'static str = "ratelimit-remaining";   // tokens left
+    pub const RESET_HEADER:&'static str = "ratelimit-reset";           // seconds until the next token
+
+    /// headers browsers need exposed to read the budget, retry-after included for denials
+    pub const EXPOSED_HEADERS:[&'static str;4] = [
+        RateLimitMiddleware::LIMIT_HEADER,
+        RateLimitMiddleware::REMAINING_HEADER,
+        RateLimitMiddleware::RESET_HEADER,
+        "retry-after"
+    ];
+
+    /// limits a route with its own named budget
+    pub fn with_policy(policy: RateLimitPolicy) -> Self {
+        RateLimitMiddleware { policy: Some(policy) }
+    }
+}
 
 impl<S,B> Transform<S, ServiceRequest> for RateLimitMiddleware
 where
@@ -31,6 +54,7 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ok(RateLimitService {
             service: Rc::new(service),
+            policy: Rc::new(self.policy.clone())
         })
     }
 }
@@ -38,28 +62,51 @@ where
 #[derive(Debug)]
 pub struct RateLimitService<S> {
     service: Rc<S>,
+    policy: Rc<Option<RateLimitPolicy>>
 }
 
 impl<S> RateLimitService<S> {
-    fn logic(shared: &Data<AppState>,connection: &ConnectionInfo) -> Decision {
+    /// the decision for the connection and the budget behind it, no budget when the limiter is off or no ip was found
+    fn logic(shared: &Data<AppState>, req: &HttpRequest, policy: &Option<RateLimitPolicy>, content_length: Option<u64>) -> (Decision, Option<Quota>) {
 
         // extract rate limiter or return early if disabled
-        let rate_limit_handle = match shared.rate_limiter() {
-            RateLimiterStatus::Enabled(limiter) => limiter,
-            RateLimiterStatus::Disabled => return Decision::Approved
+        let (rate_limit_handle, cost) = match policy {
+            Some(policy) => match shared.route_limiter(policy.name) {
+                Some(limiter) => (limiter, policy.cost_for(content_length)),
+                None => return (Decision::Approved, None)
+            },
+            None => match shared.rate_limiter() {
+                RateLimiterStatus::Enabled(limiter) => (limiter.as_ref(), 1),
+                RateLimiterStatus::Disabled => return (Decision::Approved, None)
+            }
         };
 
-        // Decision or None
-        let decision_opt= connection
-            .realip_remote_addr()
-            .or(connection.peer_addr())
-            .and_then(|ip| rate_limit_handle.try_connect(ip).ok());
+        // Quota or None
+        let quota_opt = req
+            .to_client_ip(shared)
+            .and_then(|ip| rate_limit_handle.try_connect_addr(ip, cost).ok());
 
         // deny on None (no valid ip found)
-        if let Some(decision) = decision_opt {
-            decision
-        } else {
-            Decision::Denied
+        match quota_opt {
+            Some(quota) => (quota.decision.clone(), Some(quota)),
+            None => (Decision::Denied, None)
+        }
+    }
+
+    /// whole seconds, rounded up so clients never retry early
+    fn ceil_secs(duration: Duration) -> u64 {
+        duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+    }
+
+    /// adds the RateLimit headers
+    fn quota_headers(headers: &mut HeaderMap, quota: &Quota) {
+        headers.insert(HeaderName::from_static(RateLimitMiddleware::LIMIT_HEADER), HeaderValue::from(quota.limit));
+        headers.insert(HeaderName::from_static(RateLimitMiddleware::REMAINING_HEADER), HeaderValue::from(quota.remaining));
+        headers.insert(HeaderName::from_static(RateLimitMiddleware::RESET_HEADER), HeaderValue::from(RateLimitService::<S>::ceil_secs(quota.reset)));
+
+        if quota.decision == Decision::Denied {
+            let retry_after = RateLimitService::<S>::ceil_secs(quota.retry_after).max(1);
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
         }
     }
 }
@@ -81,19 +128,38 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let rate_limiter_status = req
+        let content_length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse().ok());
+
+        // return early with a Length Required response, the body size is part of the cost
+        if self.policy.as_ref().as_ref().is_some_and(|policy| policy.length_required(content_length)) {
+            let res = req
+                .into_response(ApiResponse::length_required().error())
+                .map_into_right_body();
+
+            return Box::pin(async move { Ok(res) });
+        }
+
+        let (rate_limiter_status, quota) = req
             .app_data()
-            .map_or(Decision::Denied, |shared: &Data<AppState>| {
-                let connection = req.connection_info().clone();
-                RateLimitService::<S>::logic(shared, &connection)
+            .map_or((Decision::Denied, None), |shared: &Data<AppState>| {
+                RateLimitService::<S>::logic(shared, req.request(), &self.policy, content_length)
             });
 
-        // return early with a Forbidden response
+        // return early with a Too Many Requests response
         if rate_limiter_status == Decision::Denied {
+            let mut response = ApiResponse::rate_limited().error();
+
+            if let Some(quota) = &quota {
+                RateLimitService::<S>::quota_headers(response.headers_mut(), quota);
+            }
+
             // map fail into BoxBody
             let res = req
-                .into_response(HttpResponse::TooManyRequests()
-                .body("Rate limited"))
+                .into_response(response)
                 .map_into_right_body();
 
             return Box::pin(async move { Ok(res) });
@@ -103,7 +169,11 @@ where
         let fut = self.service.call(req);
 
         Box::pin(async move {
-            let res = fut.await?;
+            let mut res = fut.await?;
+
+            if let Some(quota) = &quota {
+                RateLimitService::<S>::quota_headers(res.headers_mut(), quota);
+            }
 
             // map the success branch into the B
             Ok(res.map_into_left_body())
@@ -2,6 +2,7 @@ use std::rc::Rc;
 use actix_web::{
     body::{EitherBody, BoxBody},
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
     web::{Data},
     HttpResponse,
     Error
@@ -11,7 +12,7 @@ use std::task::{Context, Poll};
 
 use crate::{
     enums::{Permission,SessionControllerStatus},
-    types::{AppState, AuthorizationToken, UserPermissions}
+    types::{ApiResponse, AppState, AuthorizationToken, SessionCheck, UserPermissions}
 };
 
 /// target for the middleware service
@@ -28,7 +29,7 @@ impl RouteLock {
 
 impl<S,B> Transform<S, ServiceRequest> for RouteLock
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -53,24 +54,24 @@ pub struct RouteLockService<S> {
 }
 
 impl<S> RouteLockService<S> {
-    fn logic(shared: &Data<AppState>, token: &str, required_permissions: &UserPermissions) -> Permission {
+    async fn logic(shared: &Data<AppState>, token: &str, required_permissions: &UserPermissions) -> SessionCheck {
         
-        // extract rate limiter or return early if disabled
+        // extract session controller, nothing is granted without one
         let session_controller = match shared.sessions() {
             SessionControllerStatus::Enabled(sessions) => sessions,
-            SessionControllerStatus::Disabled => return Permission::Granted
+            SessionControllerStatus::Disabled => return SessionCheck { permission: Permission::None, rotated_token: None }
         };
 
-        match session_controller.permission_check(token, required_permissions) {
-            Ok(permission) => permission,
-            Err(_) => Permission::None
+        match session_controller.authorize(token, required_permissions, shared.database()).await {
+            Ok(session_check) => session_check,
+            Err(_) => SessionCheck { permission: Permission::None, rotated_token: None }
         }
     }
 }
 
 impl<S, B> Service<ServiceRequest> for RouteLockService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -85,36 +86,118 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let required_permissions = &self.required_permissions;
-
-        let permission_status = match AuthorizationToken::extract(&req) {
-            Ok(token) => {
-                req
-                .app_data()
-                .map_or(Permission::None, |shared: &Data<AppState>| RouteLockService::<S>::logic(shared, &token, required_permissions))
-            },
-            Err(_) => Permission::None
-        };
-
-        // return early with a Forbidden response
-        if permission_status == Permission::None {
-            // map fail into BoxBody
-            let res = req
-                .into_response(HttpResponse::Unauthorized()
-                .body("Unauthorized"))
-                .map_into_right_body();
-
-            return Box::pin(async move { Ok(res) });
-        }
-
-        // return the result of the success branch
-        let fut = self.service.call(req);
+        let service = Rc::clone(&self.service);
+        let required_permissions = Rc::clone(&self.required_permissions);
 
         Box::pin(async move {
-            let res = fut.await?;
+            let shared = req.app_data::<Data<AppState>>().cloned();
+
+            // locked routes stay shut with a Service Unavailable without a session controller
+            let sessions_enabled = shared
+                .as_ref()
+                .is_some_and(|shared| matches!(shared.sessions(), SessionControllerStatus::Enabled(_)));
+
+            if !sessions_enabled {
+                // map fail into BoxBody
+                let res = req
+                    .into_response(ApiResponse::service_unavailable().error())
+                    .map_into_right_body();
+
+                return Ok(res);
+            }
+
+            // stale sessions are refreshed from the database, so the check runs inside the future
+            let session_check = match (AuthorizationToken::extract(&req), shared) {
+                (Ok(token), Some(shared)) => RouteLockService::<S>::logic(&shared, &token, &required_permissions).await,
+                _ => SessionCheck { permission: Permission::None, rotated_token: None }
+            };
+
+            // return early with a Forbidden response
+            if session_check.permission == Permission::None {
+                // map fail into BoxBody
+                let res = req
+                    .into_response(HttpResponse::Unauthorized()
+                    .body("Unauthorized"))
+                    .map_into_right_body();
+
+                return Ok(res);
+            }
+
+            // return the result of the success branch
+            let mut res = service.call(req).await?;
+
+            // hand the rotated token back, the old one only lives through the grace window
+            if let Some(token) = session_check.rotated_token
+                && let Ok(value) = HeaderValue::from_str(&token) {
+                res.headers_mut().insert(HeaderName::from_static(AuthorizationToken::ROTATED_HEADER), value);
+            }
 
             // map the success branch into the B
             Ok(res.map_into_left_body())
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, web, App};
+
+    use crate::{
+        enums::{SystemFlag, User, UserAccountStatus},
+        types::{KeySet, Session, SessionController, users::SystemUser}
+    };
+
+    use super::*;
+
+    /// a locked route answers 503 without a session controller, switching the rate limiter off
+    /// leaves sessions alone
+    #[actix_rt::test]
+    async fn locked_route_without_sessions() {
+        let required_permissions = UserPermissions::default().with_sessions_read();
+        let controller = SessionController::new(16, 1);
+        let user = User::System(SystemUser{
+            id: 1,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions: required_permissions
+        });
+
+        let key_set = KeySet::new().unwrap();
+        let session = Session::new(&key_set, user, controller.now());
+        let token = controller.insert(session, &key_set).await.unwrap();
+        let shared = Data::new(AppState::offline(SessionControllerStatus::Enabled(Box::new(controller)), SystemFlag::Disabled));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(shared.clone())
+                .service(web::resource("/locked")
+                    .wrap(RouteLock::default(required_permissions))
+                    .route(web::get().to(HttpResponse::Ok)))
+        ).await;
+
+        let request = || test::TestRequest::get()
+            .uri("/locked")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+
+        let res = test::call_service(&app, request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        shared.switch_rate_limiter(SystemFlag::Enabled);
+        let res = test::call_service(&app, request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let shared = Data::new(AppState::offline(SessionControllerStatus::Disabled, SystemFlag::Enabled));
+        let app = test::init_service(
+            App::new()
+                .app_data(shared)
+                .service(web::resource("/locked")
+                    .wrap(RouteLock::default(required_permissions))
+                    .route(web::get().to(HttpResponse::Ok)))
+        ).await;
+
+        let res = test::call_service(&app, request()).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
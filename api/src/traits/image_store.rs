@@ -0,0 +1,22 @@
+use actix_web::web::Bytes;
+use futures::{future::LocalBoxFuture, stream::LocalBoxStream};
+use std::fmt::Debug;
+
+use crate::enums::Error;
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// chunked image body returned by an ImageStore
+pub type ImageStream = LocalBoxStream<'static, Result<Bytes>>;
+
+/// pluggable storage backend for image files, addressed by an opaque storage key
+pub trait ImageStore: Debug + Send + Sync {
+    /// writes the image, replacing anything stored under the same key
+    fn put(&self, key: &str, bytes: Bytes) -> LocalBoxFuture<'_, Result<()>>;
+
+    /// opens the image as a stream of chunks
+    fn get(&self, key: &str) -> LocalBoxFuture<'_, Result<ImageStream>>;
+
+    /// removes the image, missing keys are not an error
+    fn delete(&self, key: &str) -> LocalBoxFuture<'_, Result<()>>;
+}
\ No newline at end of file
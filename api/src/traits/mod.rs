@@ -2,12 +2,19 @@ mod has_permission;
 mod to_auth_token;
 mod to_authorization_status;
 mod to_base_64;
+mod to_bucket_owner;
+mod to_client_ip;
+mod to_forwarded_header;
 mod from_base_64;
+mod image_store;
+mod to_image_format;
 mod to_key_set;
 mod to_number;
 mod to_permission;
 mod to_updated_result;
 mod to_server_mode;
+mod to_session_policy;
+mod to_session_user;
 mod to_system_flag;
 mod to_user_type;
 mod to_user_account_status;
@@ -18,12 +25,19 @@ pub use has_permission::HasPermission;
 pub use to_auth_token::ToHeaderAuthToken;
 pub use to_authorization_status::ToAuthorizationStatus;
 pub use to_base_64::ToBase64;
+pub use to_bucket_owner::ToBucketOwner;
+pub use to_client_ip::ToClientIp;
+pub use to_forwarded_header::ToForwardedHeader;
 pub use from_base_64::FromBase64;
+pub use image_store::{ImageStore,ImageStream};
+pub use to_image_format::ToImageFormat;
 pub use to_key_set::ToKeySet;
 pub use to_number::ToNumber;
 pub use to_permission::ToPermission;
 pub use to_updated_result::ToUpdatedResult;
 pub use to_server_mode::ToServerMode;
+pub use to_session_policy::ToSessionPolicy;
+pub use to_session_user::ToSessionUser;
 pub use to_system_flag::ToSystemFlag;
 pub use to_user_type::ToUserType;
 pub use to_user_account_status::ToUserAccountStatus;
@@ -9,4 +9,10 @@ impl ToBase64 for [u8;32] {
     fn to_base64_url(&self) -> String {
         BASE64_URL_SAFE_NO_PAD.encode(self)
     }
+}
+
+impl ToBase64 for [u8;16] {
+    fn to_base64_url(&self) -> String {
+        BASE64_URL_SAFE_NO_PAD.encode(self)
+    }
 }
\ No newline at end of file
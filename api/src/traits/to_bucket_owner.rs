@@ -0,0 +1,45 @@
+use crate::enums::{BucketOwner, Error, User};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// buckets belong to a business account or to a single community user
+pub trait ToBucketOwner {
+    fn to_bucket_owner(&self) -> Result<BucketOwner>;
+}
+
+impl ToBucketOwner for User {
+    fn to_bucket_owner(&self) -> Result<BucketOwner> {
+        match self {
+            User::Business(u) => Ok(BucketOwner::Business(u.business_account_id)),
+            User::Community(u) => Ok(BucketOwner::Community(u.id)),
+            User::System(_) => Err(Error::BucketOwnerNotSupported)
+        }
+    }
+}
+
+/// (owner_type_id, owner_id) pair as stored in the database
+impl ToBucketOwner for (i8, i64) {
+    fn to_bucket_owner(&self) -> Result<BucketOwner> {
+        match self.0 {
+            0 => Ok(BucketOwner::Business(self.1)),
+            1 => Ok(BucketOwner::Community(self.1)),
+            _ => Err(Error::BucketOwnerOutOfBounds)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// database values round trip into owners
+    #[test]
+    fn owner_from_database_values() {
+        assert_eq!((0_i8, 7_i64).to_bucket_owner().unwrap(), BucketOwner::Business(7));
+        assert_eq!((1_i8, 9_i64).to_bucket_owner().unwrap(), BucketOwner::Community(9));
+        assert!((2_i8, 1_i64).to_bucket_owner().is_err());
+
+        let owner = BucketOwner::Community(3);
+        assert_eq!((owner.type_id(), owner.id()).to_bucket_owner().unwrap(), owner);
+    }
+}
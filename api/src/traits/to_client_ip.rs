@@ -0,0 +1,29 @@
+use std::net::IpAddr;
+
+use actix_web::{HttpMessage, HttpRequest};
+
+use crate::types::AppState;
+
+/// client address cached on the request so every reader sees the same one
+#[derive(Clone,Copy,Debug)]
+struct ClientIp(Option<IpAddr>);
+
+/// resolves the client address, forwarded headers only count when a trusted proxy sent them
+pub trait ToClientIp {
+    fn to_client_ip(&self, shared: &AppState) -> Option<IpAddr>;
+}
+
+impl ToClientIp for HttpRequest {
+    fn to_client_ip(&self, shared: &AppState) -> Option<IpAddr> {
+        if let Some(ClientIp(ip_address)) = self.extensions().get::<ClientIp>() {
+            return *ip_address;
+        }
+
+        let peer = self.peer_addr().map(|addr| addr.ip());
+        let ip_address = shared.trusted_proxies().client_ip(peer, self.headers());
+
+        self.extensions_mut().insert(ClientIp(ip_address));
+
+        ip_address
+    }
+}
@@ -0,0 +1,17 @@
+use crate::enums::{Error, ForwardedHeader};
+
+type Result<T> = std::result::Result<T,Error>;
+
+pub trait ToForwardedHeader {
+    fn to_forwarded_header(self) -> Result<ForwardedHeader>;
+}
+
+impl ToForwardedHeader for &String {
+    fn to_forwarded_header(self) -> Result<ForwardedHeader> {
+        match self.as_str() {
+            "X_FORWARDED_FOR" => Ok(ForwardedHeader::XForwardedFor),
+            "FORWARDED"       => Ok(ForwardedHeader::Forwarded),
+            _ => Err(Error::ForwardedHeaderUnknown)
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::enums::{Error, ImageFormat};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// sniffs an image format from the leading magic bytes, client supplied content types are not trusted
+pub trait ToImageFormat {
+    fn to_image_format(self) -> Result<ImageFormat>;
+}
+
+impl ToImageFormat for &[u8] {
+    fn to_image_format(self) -> Result<ImageFormat> {
+        match self {
+            [0xFF, 0xD8, 0xFF, ..] => Ok(ImageFormat::Jpeg),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Ok(ImageFormat::Png),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Ok(ImageFormat::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Ok(ImageFormat::Webp),
+            _ => Err(Error::UnsupportedImageFormat)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// tests magic byte detection for each supported format
+    #[test]
+    fn image_format_sniffing() {
+        let jpeg: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00];
+        let png: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+        let gif: &[u8] = b"GIF89a....";
+        let webp: &[u8] = b"RIFF\x10\x00\x00\x00WEBPVP8 ";
+        let text: &[u8] = b"<html></html>";
+        let empty: &[u8] = &[];
+
+        assert_eq!(jpeg.to_image_format().unwrap(), ImageFormat::Jpeg);
+        assert_eq!(png.to_image_format().unwrap(), ImageFormat::Png);
+        assert_eq!(gif.to_image_format().unwrap(), ImageFormat::Gif);
+        assert_eq!(webp.to_image_format().unwrap(), ImageFormat::Webp);
+        assert!(text.to_image_format().is_err());
+        assert!(empty.to_image_format().is_err());
+    }
+}
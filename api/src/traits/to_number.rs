@@ -1,4 +1,4 @@
-use crate::enums::Permission;
+use crate::enums::{Permission, UserAccountStatus, UserType};
 
 pub trait ToNumber {
     fn to_i8(self) -> i8;
@@ -60,4 +60,130 @@ impl ToNumber for Permission {
             Permission::None => 0
         }
     }
+}
+
+impl ToNumber for UserAccountStatus {
+    fn to_i8(self) -> i8 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_i16(self) -> i16 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_i32(self) -> i32 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_i64(self) -> i64 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_u8(self) -> u8 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_u16(self) -> u16 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_u32(self) -> u32 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+    fn to_u64(self) -> u64 {
+        match self {
+            UserAccountStatus::Disabled => 0,
+            UserAccountStatus::Enabled => 1,
+            UserAccountStatus::Suspended => 2,
+            UserAccountStatus::Banned => 3
+        }
+    }
+}
+
+impl ToNumber for UserType {
+    fn to_i8(self) -> i8 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_i16(self) -> i16 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_i32(self) -> i32 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_i64(self) -> i64 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_u8(self) -> u8 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_u16(self) -> u16 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_u32(self) -> u32 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
+    fn to_u64(self) -> u64 {
+        match self {
+            UserType::Business => 0,
+            UserType::Community => 1,
+            UserType::System => 2
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::{
+    enums::Error,
+    types::SessionPolicy
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// parses an "idle_timeout_secs,absolute_lifetime_secs" pair from the .env file
+pub trait ToSessionPolicy {
+    fn to_session_policy(self) -> Result<SessionPolicy>;
+}
+
+impl ToSessionPolicy for &str {
+    fn to_session_policy(self) -> Result<SessionPolicy> {
+        let (idle, lifetime) = self
+            .split_once(',')
+            .ok_or(Error::SessionPolicyInvalid)?;
+
+        let idle_secs:u64 = idle.trim().parse().map_err(|_e| Error::SessionPolicyInvalid)?;
+        let lifetime_secs:u64 = lifetime.trim().parse().map_err(|_e| Error::SessionPolicyInvalid)?;
+
+        // an idle timeout longer than the lifetime could never be reached
+        if idle_secs == 0 || lifetime_secs < idle_secs {
+            return Err(Error::SessionPolicyInvalid);
+        }
+
+        let policy = SessionPolicy {
+            idle_timeout: Duration::from_secs(idle_secs),
+            absolute_lifetime: Duration::from_secs(lifetime_secs)
+        };
+
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// valid pairs parse, malformed or inverted pairs are rejected
+    #[test]
+    fn parse_session_policy() {
+        let policy = "900, 86400".to_session_policy().unwrap();
+        assert_eq!(policy.idle_timeout, Duration::from_secs(900));
+        assert_eq!(policy.absolute_lifetime, Duration::from_secs(86400));
+
+        let policy = "60,60".to_session_policy().unwrap();
+        assert_eq!(policy.idle_timeout, policy.absolute_lifetime);
+
+        for invalid in ["", "900", "900,", ",86400", "a,b", "-1,60", "0,60", "120,60"] {
+            assert!(matches!(invalid.to_session_policy(), Err(Error::SessionPolicyInvalid)), "{invalid}");
+        }
+    }
+}
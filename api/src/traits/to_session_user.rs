@@ -0,0 +1,27 @@
+use actix_web::HttpRequest;
+
+use crate::{
+    enums::{Error, SessionControllerStatus, User},
+    traits::ToHeaderAuthToken,
+    types::AppState
+};
+
+/// resolves the user attached to the request's session token
+pub trait ToSessionUser {
+    fn to_session_user(&self, shared: &AppState) -> Result<User,Error>;
+}
+
+impl ToSessionUser for HttpRequest {
+    fn to_session_user(&self, shared: &AppState) -> Result<User,Error> {
+        let session_controller = match shared.sessions() {
+            SessionControllerStatus::Enabled(controller) => controller,
+            SessionControllerStatus::Disabled => return Err(Error::SessionControllerDisabled)
+        };
+
+        let token = self.to_auth()?;
+
+        session_controller
+            .user(&token)?
+            .ok_or(Error::SessionNotFound)
+    }
+}
\ No newline at end of file
@@ -99,6 +99,13 @@ impl ApiResponse<()> {
             .with_message("forbidden".to_string())
     }
 
+    /// login refused for a disabled or suspended account, only sent after the password verifies
+    pub fn account_unavailable() -> Self {
+        ApiResponse::default()
+            .with_code(403)
+            .with_message("account unavailable".to_string())
+    }
+
     /// standard 404 / not found response
     pub fn not_found() -> Self {
         ApiResponse::default()
@@ -106,6 +113,13 @@ impl ApiResponse<()> {
             .with_message("not found".to_string())
     }
 
+    /// standard 409 / conflict response
+    pub fn conflict() -> Self {
+        ApiResponse::default()
+            .with_code(409)
+            .with_message("conflict".to_string())
+    }
+
     /// standard no-content response
     pub fn no_content() -> Self {
         ApiResponse::default()
@@ -113,6 +127,27 @@ impl ApiResponse<()> {
             .with_message("no content".to_string())
     }
 
+    /// standard 411 / length required response
+    pub fn length_required() -> Self {
+        ApiResponse::default()
+            .with_code(411)
+            .with_message("length required".to_string())
+    }
+
+    /// standard 413 / payload too large response
+    pub fn payload_too_large() -> Self {
+        ApiResponse::default()
+            .with_code(413)
+            .with_message("payload too large".to_string())
+    }
+
+    /// standard 415 / unsupported media type response
+    pub fn unsupported_media_type() -> Self {
+        ApiResponse::default()
+            .with_code(415)
+            .with_message("unsupported media type".to_string())
+    }
+
     /// standard rate-limited response
     pub fn rate_limited() -> Self {
         ApiResponse::default()
@@ -126,4 +161,11 @@ impl ApiResponse<()> {
             .with_code(500)
             .with_message("internal server error".to_string())
     }
+
+    /// standard 503 / service unavailable response
+    pub fn service_unavailable() -> Self {
+        ApiResponse::default()
+            .with_code(503)
+            .with_message("service unavailable".to_string())
+    }
 }
\ No newline at end of file
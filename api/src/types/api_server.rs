@@ -10,7 +10,7 @@ use crate::{
         Error,
         PrimaryCommand
     },
-    services::{RateLimitMiddleware},
+    services::MaintenanceMiddleware,
     types::{
         AppState,
         HeaderSettings,
@@ -40,7 +40,7 @@ impl ApiServer {
             // load services into app
             actix_web::App::new()
                 .app_data(app_state.clone())
-                .wrap(RateLimitMiddleware)
+                .wrap(MaintenanceMiddleware)
                 .wrap(cors)
                 .service(routes_v1)
         };
@@ -1,12 +1,25 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, PoisonError, RwLock
+    }
+};
+
+use rate_limit::RateLimiter;
+
 use crate::{
     enums::{
         ConnectionStatus,
         Error,
+        ImageStoreStatus,
         RateLimiterStatus,
-        SessionControllerStatus
+        ServerMode,
+        SessionControllerStatus,
+        SystemFlag
     },
     types::{
-        DatabaseConnection, Env, Settings
+        DatabaseConnection, Env, LoginThrottle, Settings, TrustedProxies
     }
 };
 
@@ -17,9 +30,15 @@ const DATABASE_SETTINGS_ID:i64 = 1;
 #[derive(Debug)]
 pub struct AppState {
     database: DatabaseConnection,
-    settings: Settings,
+    settings: RwLock<Arc<Settings>>,
+    settings_changes: RwLock<Vec<String>>,  // last non-empty diff applied by reload_settings
+    limiter_switch: AtomicBool,
     limiter: RateLimiterStatus,
+    route_limiters: HashMap<&'static str,RateLimiter>,  // policy name -> limiter
     sessions: SessionControllerStatus,
+    images: ImageStoreStatus,
+    login_throttle: LoginThrottle,
+    trusted_proxies: TrustedProxies,
 }
 
 impl AppState {
@@ -41,28 +60,92 @@ impl AppState {
         // construct app state
         let app_state = AppState {
             database,
-            settings,
+            settings: RwLock::new(Arc::new(settings)),
+            settings_changes: RwLock::new(Vec::new()),
+            limiter_switch: AtomicBool::new(true),
             limiter: RateLimiterStatus::Disabled,
-            sessions: SessionControllerStatus::Disabled
+            route_limiters: HashMap::new(),
+            sessions: SessionControllerStatus::Disabled,
+            images: ImageStoreStatus::Disabled,
+            login_throttle: LoginThrottle::default(),
+            trusted_proxies: env.trusted_proxies.clone()
         };
 
         Ok(app_state)
     }
 
     // sync system settings with the database
-    pub async fn with_database_settings(mut self) -> Result<Self> {
-        let settings = Settings::by_id(DATABASE_SETTINGS_ID, &self.database).await?;
+    pub async fn with_database_settings(self) -> Result<Self> {
+        let _changes = self.reload_settings().await?;
+
+        Ok(self)
+    }
+
+    /// reloads system settings from the database, swaps them in and returns what changed
+    pub async fn reload_settings(&self) -> Result<Vec<String>> {
+        let record = Settings::by_id(DATABASE_SETTINGS_ID, &self.database).await?;
+        let current = self.settings();
 
         // apply database setting overrides
-        self.settings.load_email_queue_service = settings.load_email_queue_service;
-        self.settings.postmark_email_service = settings.postmark_email_service;
-        self.settings.load_rate_limiter_service = settings.load_rate_limiter_service;
-        self.settings.load_text_queue_service = settings.load_text_queue_service;
-        self.settings.ip_address = settings.ip_address;
-        self.settings.server_mode = settings.server_mode;
-        self.settings.timestamp = settings.timestamp;
+        let settings = current.merge(&record);
+        let changes = current.changes(&settings);
 
-        Ok(self)
+        self.switch_rate_limiter(settings.load_rate_limiter_service);
+
+        // readers keep the Arc they already hold, new readers see the new settings
+        *self.settings.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(settings);
+
+        if !changes.is_empty() {
+            *self.settings_changes.write().unwrap_or_else(PoisonError::into_inner) = changes.clone();
+        }
+
+        Ok(changes)
+    }
+
+    /// switches the rate limiters on or off at runtime, sessions are unaffected
+    pub fn switch_rate_limiter(&self, flag: SystemFlag) {
+        self.limiter_switch.store(flag == SystemFlag::Enabled, Ordering::Release);
+    }
+
+    /// the last settings changes picked up from the database
+    pub fn settings_changes(&self) -> Vec<String> {
+        self.settings_changes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// app state over a database that never connects, for middleware and handler tests
+    #[cfg(test)]
+    pub fn offline(sessions: SessionControllerStatus, rate_limiter: SystemFlag) -> AppState {
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .connect_lazy("mysql://localhost/idropr")
+            .expect("could not build lazy database pool");
+
+        let settings = Settings {
+            load_email_queue_service: SystemFlag::Disabled,
+            postmark_email_service: SystemFlag::Disabled,
+            load_rate_limiter_service: rate_limiter,
+            load_text_queue_service: SystemFlag::Disabled,
+            master_password: crate::enums::MasterPassword::None,
+            ip_address: String::from("127.0.0.1"),
+            server_mode: ServerMode::Development,
+            server_port: 0,
+            timestamp: chrono::Utc::now()
+        };
+
+        AppState {
+            database: DatabaseConnection { pool },
+            settings: RwLock::new(Arc::new(settings)),
+            settings_changes: RwLock::new(Vec::new()),
+            limiter_switch: AtomicBool::new(rate_limiter == SystemFlag::Enabled),
+            limiter: RateLimiterStatus::Disabled,
+            route_limiters: HashMap::new(),
+            sessions,
+            images: ImageStoreStatus::Disabled,
+            login_throttle: LoginThrottle::default(),
+            trusted_proxies: TrustedProxies::default()
+        }
     }
 
     /// database getter
@@ -70,9 +153,37 @@ impl AppState {
         &self.database
     }
 
-    /// rate limiter getter
+    /// image store getter
+    pub fn image_store(&self) -> &ImageStoreStatus {
+        &self.images
+    }
+
+    /// login throttle getter, always on so the runtime switch can't open a guessing window
+    pub fn login_throttle(&self) -> &LoginThrottle {
+        &self.login_throttle
+    }
+
+    /// proxies trusted to forward the client address
+    pub fn trusted_proxies(&self) -> &TrustedProxies {
+        &self.trusted_proxies
+    }
+
+    /// rate limiter getter, reports disabled while switched off at runtime
     pub fn rate_limiter(&self) -> &RateLimiterStatus {
-        &self.limiter
+        if self.limiter_switch.load(Ordering::Acquire) {
+            &self.limiter
+        } else {
+            &RateLimiterStatus::Disabled
+        }
+    }
+
+    /// limiter backing a named route policy, none while switched off at runtime
+    pub fn route_limiter(&self, policy: &str) -> Option<&RateLimiter> {
+        if self.limiter_switch.load(Ordering::Acquire) {
+            self.route_limiters.get(policy)
+        } else {
+            None
+        }
     }
 
     /// session controller getter
@@ -80,6 +191,16 @@ impl AppState {
         &self.sessions
     }
 
+    /// rate limiter getter that ignores the runtime switch, used by the sweeper
+    pub fn rate_limiter_instance(&self) -> &RateLimiterStatus {
+        &self.limiter
+    }
+
+    /// route policy limiters ignoring the runtime switch, used by the sweeper
+    pub fn route_limiters_instance(&self) -> &HashMap<&'static str,RateLimiter> {
+        &self.route_limiters
+    }
+
     /// accepts an instance of RateLimiter and moves it into the server
     pub fn with_rate_limit_status(mut self, status: RateLimiterStatus) -> Self {
         self.limiter = status;
@@ -87,25 +208,47 @@ impl AppState {
         self
     }
 
+    /// accepts the limiters backing the named route policies
+    pub fn with_route_limiters(mut self, route_limiters: HashMap<&'static str,RateLimiter>) -> Self {
+        self.route_limiters = route_limiters;
+        self
+    }
+
     // accepts an instance of the SessionController and moves it into the server
     pub fn with_session_status(mut self, status: SessionControllerStatus) -> Self {
         self.sessions = status;
         self
     }
 
-    /// settings getter
-    pub fn settings(&self) -> &Settings {
-        &self.settings
+    /// overrides the server mode, dev servers never load it from the database
+    pub fn with_server_mode(self, mode: ServerMode) -> Self {
+        let settings = Settings {
+            server_mode: mode,
+            ..(*self.settings()).clone()
+        };
+
+        *self.settings.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(settings);
+        self
+    }
+
+    /// accepts an image storage backend and moves it into the server
+    pub fn with_image_store_status(mut self, status: ImageStoreStatus) -> Self {
+        self.images = status;
+        self
+    }
+
+    /// settings getter, returns the settings in effect when called
+    pub fn settings(&self) -> Arc<Settings> {
+        self.settings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::enums::{
-        ServerMode,
-        SystemFlag
-    };
 
     /// constructor build test
     #[actix_rt::test]
@@ -134,9 +277,15 @@ mod tests {
         // manual build test
         let _manual_builder = AppState {
             database,
-            settings,
+            settings: RwLock::new(Arc::new(settings)),
+            settings_changes: RwLock::new(Vec::new()),
+            limiter_switch: AtomicBool::new(true),
             limiter: RateLimiterStatus::Disabled,
-            sessions: SessionControllerStatus::Disabled
+            route_limiters: HashMap::new(),
+            sessions: SessionControllerStatus::Disabled,
+            images: ImageStoreStatus::Disabled,
+            login_throttle: LoginThrottle::default(),
+            trusted_proxies: TrustedProxies::default()
         };
 
         // connection status is already checked in the AppState constructor()
@@ -7,7 +7,9 @@ type Result<T> = std::result::Result<T,Error>;
 pub struct AuthorizationToken;
 
 impl AuthorizationToken {
-    
+    /// response header carrying a rotated session token
+    pub const ROTATED_HEADER: &str = "x-session-token";
+
     pub fn extract(req: &ServiceRequest) -> Result<String> {
         let str = req
             .headers()
@@ -0,0 +1,203 @@
+use chrono::{DateTime,Utc};
+use sqlx::FromRow;
+
+use crate::{
+    enums::{BucketOwner, Error, Permission, RowsUpdated, User},
+    traits::{HasPermission, ToBucketOwner, ToUpdatedResult},
+    types::{DatabaseConnection, UserPermissions}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+const MIN_NAME_LENGTH:usize = 3;
+const MAX_NAME_LENGTH:usize = 63;
+
+#[derive(Debug,FromRow)]
+struct DatabaseHelper {
+    id: i64,
+    owner_type_id: i8,
+    owner_id: i64,
+    name: String,
+    created_at: DateTime<Utc>
+}
+
+impl DatabaseHelper {
+    /// consumes self and returns the Bucket
+    fn transform(self) -> Result<Bucket> {
+        let owner = (self.owner_type_id, self.owner_id).to_bucket_owner()?;
+
+        let bucket = Bucket {
+            id: self.id,
+            owner,
+            name: self.name,
+            created_at: self.created_at
+        };
+
+        Ok(bucket)
+    }
+}
+
+/// named image container owned by a business account or community user
+#[derive(Clone,Debug,PartialEq)]
+pub struct Bucket {
+    pub id: i64,
+    pub owner: BucketOwner,
+    pub name: String,
+    pub created_at: DateTime<Utc>
+}
+
+impl Bucket {
+    /// bucket names must be 3-63 characters of a-z, 0-9, '-', '_' or '.'
+    pub fn validate_name(name: &str) -> Result<()> {
+        let length_ok = (MIN_NAME_LENGTH..=MAX_NAME_LENGTH).contains(&name.len());
+        let chars_ok = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '.');
+
+        if length_ok && chars_ok {
+            Ok(())
+        } else {
+            Err(Error::BucketNameInvalid)
+        }
+    }
+
+    /// owners always have access, system users need the admin rights passed in
+    pub fn access(&self, user: &User, admin_rights: &UserPermissions) -> Permission {
+        match (user, user.to_bucket_owner()) {
+            (_, Ok(owner)) if owner == self.owner => Permission::Granted,
+            (User::System(u), _) => u.permissions.has_permission(admin_rights),
+            (_, _) => Permission::None
+        }
+    }
+
+    /// bucket by id
+    pub async fn by_id(bucket_id: i64, database: &DatabaseConnection) -> Result<Option<Bucket>> {
+        let sql = "SELECT id,owner_type_id,owner_id,name,created_at FROM `bucket` WHERE id = ?";
+        let helper_opt:Option<DatabaseHelper> = sqlx::query_as(sql)
+            .bind(bucket_id)
+            .fetch_optional(&database.pool)
+            .await?;
+
+        match helper_opt {
+            Some(helper) => Ok(Some(helper.transform()?)),
+            None => Ok(None)
+        }
+    }
+
+    /// all buckets belonging to an owner
+    pub async fn by_owner(owner: &BucketOwner, database: &DatabaseConnection) -> Result<Vec<Bucket>> {
+        let sql = "SELECT id,owner_type_id,owner_id,name,created_at FROM `bucket` WHERE owner_type_id = ? AND owner_id = ? ORDER BY name";
+        let helpers:Vec<DatabaseHelper> = sqlx::query_as(sql)
+            .bind(owner.type_id())
+            .bind(owner.id())
+            .fetch_all(&database.pool)
+            .await?;
+
+        helpers
+            .into_iter()
+            .map(|helper| helper.transform())
+            .collect()
+    }
+
+    /// creates a new bucket and returns its id
+    pub async fn into_db(owner: &BucketOwner, name: &str, database: &DatabaseConnection) -> Result<i64> {
+        Bucket::validate_name(name)?;
+
+        let sql = "INSERT INTO `bucket` (owner_type_id,owner_id,name,created_at) VALUES(?,?,?,?)";
+        let insert_id = sqlx::query(sql)
+            .bind(owner.type_id())
+            .bind(owner.id())
+            .bind(name)
+            .bind(Utc::now())
+            .execute(&database.pool)
+            .await?
+            .last_insert_id() as i64;
+
+        Ok(insert_id)
+    }
+
+    /// renames a bucket
+    pub async fn rename(bucket_id: i64, name: &str, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        Bucket::validate_name(name)?;
+
+        let sql = "UPDATE `bucket` SET name = ? WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(name)
+            .bind(bucket_id)
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+
+    /// deletes a bucket
+    pub async fn delete_by_id(bucket_id: i64, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "DELETE FROM `bucket` WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(bucket_id)
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::UserAccountStatus, types::users::{BusinessUser, CommunityUser, SystemUser}};
+
+    /// tests the bucket naming rules
+    #[test]
+    fn bucket_names() {
+        assert!(Bucket::validate_name("summer-2025").is_ok());
+        assert!(Bucket::validate_name("a.b_c").is_ok());
+        assert!(Bucket::validate_name("ab").is_err());
+        assert!(Bucket::validate_name("Uppercase").is_err());
+        assert!(Bucket::validate_name("white space").is_err());
+        assert!(Bucket::validate_name(&"a".repeat(64)).is_err());
+    }
+
+    /// one business account can never reach another account's buckets
+    #[test]
+    fn bucket_access() {
+        let bucket = Bucket {
+            id: 1,
+            owner: BucketOwner::Business(10),
+            name: String::from("bucket"),
+            created_at: Utc::now()
+        };
+        let admin_rights = UserPermissions::default().with_admin_read();
+
+        let business_user = |business_account_id| User::Business(BusinessUser {
+            id: 1,
+            business_account_id,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions: UserPermissions::default().with_admin_read()
+        });
+        let community_user = User::Community(CommunityUser {
+            id: 10,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions: UserPermissions::default()
+        });
+        let system_user = |permissions| User::System(SystemUser {
+            id: 2,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions
+        });
+
+        assert_eq!(bucket.access(&business_user(10), &admin_rights), Permission::Granted);
+        assert_eq!(bucket.access(&business_user(11), &admin_rights), Permission::None);
+        assert_eq!(bucket.access(&community_user, &admin_rights), Permission::None);
+        assert_eq!(bucket.access(&system_user(UserPermissions::default()), &admin_rights), Permission::None);
+        assert_eq!(bucket.access(&system_user(admin_rights), &admin_rights), Permission::Granted);
+    }
+}
@@ -1,10 +1,11 @@
 use std::{collections::HashMap, fmt::Debug};
 
 use dotenv;
-use rate_limit::{enums::TimeWindow,traits::ToTimeWindow};
+use rate_limit::{enums::{Algorithm,TimeWindow},traits::{ToAlgorithm,ToTimeWindow}};
 use crate::{
-    enums::ServerMode,
-    traits::ToServerMode
+    enums::{ForwardedHeader, ServerMode, SystemFlag},
+    traits::{ToForwardedHeader, ToServerMode, ToSessionPolicy, ToSystemFlag},
+    types::{SessionPolicies, SessionPolicy, TrustedProxies}
 };
 
 // manages importing and testing of the .env file
@@ -24,6 +25,7 @@ pub struct Env {
     pub server_mode: ServerMode,    // [DEVELOPMENT,PRODUCTION,MAINTENANCE]
     pub server_port: u16,           // port server will accept requests on
     pub server_threads: usize,      // maximum number of thread workers
+    pub trusted_proxies: TrustedProxies,    // optional, proxies allowed to forward the client address and the header they write
 
     // rate limiter settings
     pub limiter_initial_capacity: usize,
@@ -31,9 +33,21 @@ pub struct Env {
     pub limiter_initial_tokens_per_bucket: u32,
     pub limiter_refill_rate: f32,
     pub limiter_refill_window: TimeWindow,
+    pub limiter_ipv4_prefix: u8,        // optional, ipv4 addresses sharing this prefix share a bucket, 32 by default
+    pub limiter_ipv6_prefix: u8,        // optional, ipv6 addresses sharing this prefix share a bucket, 64 by default
+    pub limiter_algorithm: Algorithm,   // optional, token bucket by default
+    pub limiter_snapshot_dir: Option<String>, // optional, limiter state is written here on shutdown and loaded on boot
+    pub limiter_shared: SystemFlag,     // optional, counts limits in the database so every server instance shares them
+    pub limiter_sync_millis: u64,       // optional, time between syncs with the database, 1000 by default
 
     // session controller settings
-    pub sessions_initial_capacity: usize
+    pub sessions_initial_capacity: usize,
+    pub sessions_persist: SystemFlag,   // optional, writes sessions to the database so restarts keep them
+    pub sessions_policies: SessionPolicies, // optional, idle timeout and absolute lifetime per user type
+    pub sessions_rotate: SystemFlag,    // optional, rotates token secrets when a session refreshes
+
+    // image storage settings
+    pub image_store_path: Option<String>    // optional, root directory for the local image store, images are disabled without it
 }
 
 impl Default for Env {
@@ -88,6 +102,21 @@ impl Default for Env {
             .parse()
             .expect("could not parse SERVER_PORT in .env");
 
+        let trusted_proxy_header: ForwardedHeader = env.get("TRUSTED_PROXY_HEADER")
+            .map_or(Ok(ForwardedHeader::default()), |header| header.to_forwarded_header())
+            .expect("TRUSTED_PROXY_HEADER unknown in .env");
+
+        // comma separated addresses or cidr ranges, forwarded headers are ignored without any
+        let trusted_proxies = env.get("TRUSTED_PROXIES").map_or(TrustedProxies::default(), |proxies| {
+            let networks = proxies
+                .split(',')
+                .filter(|network| !network.trim().is_empty())
+                .map(|network| network.parse().expect("could not parse TRUSTED_PROXIES in .env"))
+                .collect();
+
+            TrustedProxies::new(networks).with_header(trusted_proxy_header)
+        });
+
         let limiter_initial_capacity = env.get("LIMITER_INITIAL_CAPACITY")
             .expect("LIMITER_INITIAL_CAPACITY not found in .env")
             .to_owned()
@@ -120,11 +149,75 @@ impl Default for Env {
             .parse()
             .expect("could not parse LIMITER_INITIAL_TOKENS_PER_BUCKET in .env");
 
+        let limiter_ipv4_prefix: u8 = env.get("LIMITER_IPV4_PREFIX")
+            .map_or(32, |prefix| prefix.parse().expect("could not parse LIMITER_IPV4_PREFIX in .env"));
+        assert!(limiter_ipv4_prefix <= 32, "LIMITER_IPV4_PREFIX in .env out-of-range");
+
+        let limiter_ipv6_prefix: u8 = env.get("LIMITER_IPV6_PREFIX")
+            .map_or(64, |prefix| prefix.parse().expect("could not parse LIMITER_IPV6_PREFIX in .env"));
+        assert!(limiter_ipv6_prefix <= 128, "LIMITER_IPV6_PREFIX in .env out-of-range");
+
+        let limiter_algorithm: Algorithm = env.get("LIMITER_ALGORITHM")
+            .map_or(Ok(Algorithm::default()), |algorithm| algorithm.to_algorithm())
+            .expect("LIMITER_ALGORITHM unknown in .env");
+
+        let limiter_snapshot_dir: Option<String> = env.get("LIMITER_SNAPSHOT_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| dir.to_owned());
+
+        let limiter_shared: SystemFlag = env.get("LIMITER_SHARED")
+            .map_or(Ok(SystemFlag::Disabled), |flag| {
+                flag.parse::<u8>()
+                    .expect("could not parse LIMITER_SHARED in .env")
+                    .to_system_flag()
+            })
+            .expect("LIMITER_SHARED in .env out-of-range");
+
+        let limiter_sync_millis: u64 = env.get("LIMITER_SYNC_MILLIS")
+            .map_or(1_000, |millis| millis.parse().expect("could not parse LIMITER_SYNC_MILLIS in .env"));
+        assert!(limiter_sync_millis > 0, "LIMITER_SYNC_MILLIS in .env out-of-range");
+
         let sessions_initial_capacity: usize = env.get("SESSIONS_INITIAL_CAPACITY")
             .expect("SESSIONS_INITIAL_CAPACITY not found in .env")
             .parse()
             .expect("could not parse SESSIONS_INITIAL_CAPACITY in .env");
 
+        let sessions_persist: SystemFlag = env.get("SESSIONS_PERSIST")
+            .map_or(Ok(SystemFlag::Disabled), |flag| {
+                flag.parse::<u8>()
+                    .expect("could not parse SESSIONS_PERSIST in .env")
+                    .to_system_flag()
+            })
+            .expect("SESSIONS_PERSIST in .env out-of-range");
+
+        // "idle_secs,lifetime_secs" per user type, defaults when missing
+        let session_policy = |var: &str| -> SessionPolicy {
+            env.get(var).map_or(SessionPolicy::default(), |policy| {
+                policy
+                    .as_str()
+                    .to_session_policy()
+                    .unwrap_or_else(|_e| panic!("{var} in .env must be \"idle_secs,lifetime_secs\" with 0 < idle <= lifetime"))
+            })
+        };
+
+        let sessions_policies = SessionPolicies {
+            business: session_policy("SESSIONS_POLICY_BUSINESS"),
+            community: session_policy("SESSIONS_POLICY_COMMUNITY"),
+            system: session_policy("SESSIONS_POLICY_SYSTEM")
+        };
+
+        let sessions_rotate: SystemFlag = env.get("SESSIONS_ROTATE")
+            .map_or(Ok(SystemFlag::Disabled), |flag| {
+                flag.parse::<u8>()
+                    .expect("could not parse SESSIONS_ROTATE in .env")
+                    .to_system_flag()
+            })
+            .expect("SESSIONS_ROTATE in .env out-of-range");
+
+        let image_store_path: Option<String> = env.get("IMAGE_STORE_PATH")
+            .filter(|path| !path.is_empty())
+            .map(|path| path.to_owned());
+
         Env {
             db_cert_path,
             db_user,
@@ -136,13 +229,24 @@ impl Default for Env {
             master_password,
             server_mode,
             server_port,
+            trusted_proxies,
             limiter_initial_capacity,
             limiter_initial_tokens_per_bucket,
             limiter_refill_rate,
             limiter_refill_window,
+            limiter_ipv4_prefix,
+            limiter_ipv6_prefix,
+            limiter_algorithm,
+            limiter_snapshot_dir,
+            limiter_shared,
+            limiter_sync_millis,
             limiter_tokens_per_bucket,
             server_threads,
-            sessions_initial_capacity
+            sessions_initial_capacity,
+            sessions_persist,
+            sessions_policies,
+            sessions_rotate,
+            image_store_path
         }
     }
 }
@@ -168,12 +272,26 @@ mod tests {
             server_port: String::from("3000").parse().unwrap(),
             server_mode: ServerMode::Production,
             server_threads: 2,
+            trusted_proxies: TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]).with_header(ForwardedHeader::Forwarded),
             limiter_initial_capacity: String::from("100").parse().unwrap(),
             limiter_initial_tokens_per_bucket: 1000,
             limiter_tokens_per_bucket: String::from("100").parse().unwrap(),
             limiter_refill_rate: String::from("100").parse().unwrap(),
             limiter_refill_window: String::from("HOUR").to_time_window().unwrap(),
-            sessions_initial_capacity: String::from("1000").parse().unwrap()
+            limiter_ipv4_prefix: 24,
+            limiter_ipv6_prefix: 64,
+            limiter_algorithm: String::from("GCRA").to_algorithm().unwrap(),
+            limiter_snapshot_dir: Some(String::from("snapshots")),
+            limiter_shared: 1_u8.to_system_flag().unwrap(),
+            limiter_sync_millis: 500,
+            sessions_initial_capacity: String::from("1000").parse().unwrap(),
+            sessions_persist: 1_u8.to_system_flag().unwrap(),
+            sessions_policies: SessionPolicies {
+                business: "900,86400".to_session_policy().unwrap(),
+                ..SessionPolicies::default()
+            },
+            sessions_rotate: 1_u8.to_system_flag().unwrap(),
+            image_store_path: Some(String::from("image_store_path"))
         };
 
         // test function calls return correct data
@@ -187,12 +305,25 @@ mod tests {
         assert_eq!(manual_env.master_password, String::from("master_password"));
         assert_eq!(manual_env.server_port, 3000);
         assert_eq!(manual_env.server_mode, ServerMode::Production);
+        assert!(manual_env.trusted_proxies.is_trusted(&"10.1.2.3".parse().unwrap()));
         assert_eq!(manual_env.limiter_initial_capacity, 100);
         assert_eq!(manual_env.limiter_initial_tokens_per_bucket, 1000);
         assert_eq!(manual_env.limiter_tokens_per_bucket, 100);
         assert_eq!(manual_env.limiter_refill_window,TimeWindow::Hour);
+        assert_eq!(manual_env.limiter_ipv4_prefix, 24);
+        assert_eq!(manual_env.limiter_ipv6_prefix, 64);
+        assert_eq!(manual_env.limiter_algorithm, Algorithm::Gcra);
+        assert_eq!(manual_env.limiter_snapshot_dir.as_deref(), Some("snapshots"));
+        assert_eq!(manual_env.limiter_shared, SystemFlag::Enabled);
+        assert_eq!(manual_env.limiter_sync_millis, 500);
         assert_eq!(manual_env.server_threads, 2);
         assert_eq!(manual_env.sessions_initial_capacity, 1000);
+        assert_eq!(manual_env.sessions_persist, SystemFlag::Enabled);
+        assert_eq!(manual_env.sessions_policies.business.idle_timeout.as_secs(), 900);
+        assert_eq!(manual_env.sessions_policies.business.absolute_lifetime.as_secs(), 86400);
+        assert_eq!(manual_env.sessions_policies.system, SessionPolicy::default());
+        assert_eq!(manual_env.sessions_rotate, SystemFlag::Enabled);
+        assert_eq!(manual_env.image_store_path.as_deref(), Some("image_store_path"));
 
         // test constructor generated properties contain some values
         let builder = Env::default();
@@ -1,16 +1,20 @@
 use actix_cors::Cors;
 use actix_web::{dev::RequestHead,http,http::header::{HeaderValue}};
+
+use crate::{services::RateLimitMiddleware, types::AuthorizationToken};
+
 pub struct HeaderSettings;
 
 impl HeaderSettings {
     pub fn dev_cors() -> Cors {
         let headers = vec![http::header::AUTHORIZATION, http::header::ACCEPT, http::header::CONTENT_TYPE];
-        let methods  = ["GET", "POST", "OPTIONS"];
+        let methods  = ["GET", "POST", "PATCH", "DELETE", "OPTIONS"];
 
         Cors::default()
             .allow_any_origin()
             .allowed_methods(methods)
             .allowed_headers(headers)
+            .expose_headers([AuthorizationToken::ROTATED_HEADER].into_iter().chain(RateLimitMiddleware::EXPOSED_HEADERS))
             .max_age(3600)
     }
 
@@ -31,12 +35,13 @@ impl HeaderSettings {
                 http::header::ACCESS_CONTROL_MAX_AGE,
                 http::header::CONTENT_TYPE,
         ];
-        let methods = ["GET", "POST", "OPTIONS"];
+        let methods = ["GET", "POST", "PATCH", "DELETE", "OPTIONS"];
 
         Cors::default()
             .allowed_methods(methods)
             .allowed_headers(headers)
             .allowed_origin_fn(HeaderSettings::filter_origin)
+            .expose_headers([AuthorizationToken::ROTATED_HEADER].into_iter().chain(RateLimitMiddleware::EXPOSED_HEADERS))
             .max_age(3600)
     }
 }
\ No newline at end of file
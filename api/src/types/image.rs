@@ -0,0 +1,86 @@
+use chrono::{DateTime,Utc};
+use sqlx::FromRow;
+
+use crate::{
+    enums::{Error, ImageFormat, RowsUpdated},
+    traits::ToUpdatedResult,
+    types::DatabaseConnection
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// image metadata, the image itself lives in the ImageStore under storage_key
+#[derive(Clone,Debug,FromRow,PartialEq)]
+pub struct Image {
+    pub id: i64,
+    pub bucket_id: i64,
+    pub storage_key: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>
+}
+
+impl Image {
+    /// image metadata by id
+    pub async fn by_id(image_id: i64, database: &DatabaseConnection) -> Result<Option<Image>> {
+        let sql = "SELECT id,bucket_id,storage_key,filename,mime_type,size_bytes,created_at FROM `image` WHERE id = ?";
+        let image_opt:Option<Image> = sqlx::query_as(sql)
+            .bind(image_id)
+            .fetch_optional(&database.pool)
+            .await?;
+
+        Ok(image_opt)
+    }
+
+    /// all image metadata in a bucket
+    pub async fn by_bucket(bucket_id: i64, database: &DatabaseConnection) -> Result<Vec<Image>> {
+        let sql = "SELECT id,bucket_id,storage_key,filename,mime_type,size_bytes,created_at FROM `image` WHERE bucket_id = ? ORDER BY id";
+        let images:Vec<Image> = sqlx::query_as(sql)
+            .bind(bucket_id)
+            .fetch_all(&database.pool)
+            .await?;
+
+        Ok(images)
+    }
+
+    /// number of images in a bucket
+    pub async fn count_by_bucket(bucket_id: i64, database: &DatabaseConnection) -> Result<i64> {
+        let sql = "SELECT COUNT(*) FROM `image` WHERE bucket_id = ?";
+        let (count,):(i64,) = sqlx::query_as(sql)
+            .bind(bucket_id)
+            .fetch_one(&database.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// writes image metadata and returns the new image id
+    pub async fn into_db(bucket_id: i64, storage_key: &str, filename: &str, format: ImageFormat, size_bytes: usize, database: &DatabaseConnection) -> Result<i64> {
+        let sql = "INSERT INTO `image` (bucket_id,storage_key,filename,mime_type,size_bytes,created_at) VALUES(?,?,?,?,?,?)";
+        let insert_id = sqlx::query(sql)
+            .bind(bucket_id)
+            .bind(storage_key)
+            .bind(filename)
+            .bind(format.mime_type())
+            .bind(size_bytes as i64)
+            .bind(Utc::now())
+            .execute(&database.pool)
+            .await?
+            .last_insert_id() as i64;
+
+        Ok(insert_id)
+    }
+
+    /// deletes image metadata
+    pub async fn delete_by_id(image_id: i64, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "DELETE FROM `image` WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(image_id)
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+}
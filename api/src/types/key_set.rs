@@ -1,6 +1,9 @@
 use blake3::{self, Hash};
 
-use crate::enums::{Error, Uuid, VerificationStatus};
+use crate::{
+    enums::{Error, Uuid, VerificationStatus},
+    traits::ToBase64
+};
 
 type Result<T> = std::result::Result<T,Error>;
 
@@ -43,6 +46,32 @@ impl KeySet {
         Ok(key_set)
     }
 
+    /// keeps the session key and draws a fresh secret, used to rotate a live session's token
+    pub fn rotate(key: &[u8;KEY_SIZE]) -> Result<KeySet> {
+        let secret = KeySet::new()?.secret;
+
+        let mut combined_set = [0;BUF_SIZE];
+        combined_set[..KEY_SIZE].copy_from_slice(key);
+        combined_set[KEY_SIZE..].copy_from_slice(&secret);
+
+        let key_set = KeySet {
+            key: *key,
+            secret,
+            hash: blake3::hash(&combined_set)
+        };
+
+        Ok(key_set)
+    }
+
+    /// base64url token of key||secret handed to the client
+    pub fn token(&self) -> String {
+        let mut token_buf:[u8;BUF_SIZE] = [0;BUF_SIZE];
+        token_buf[..KEY_SIZE].copy_from_slice(&self.key);
+        token_buf[KEY_SIZE..].copy_from_slice(&self.secret);
+
+        token_buf.to_base64_url()
+    }
+
     pub fn verify(key: &[u8;KEY_SIZE], secret: &[u8;KEY_SIZE], hash: &Hash) -> VerificationStatus {
         let mut combined_set= [0;BUF_SIZE];
         combined_set[..KEY_SIZE].copy_from_slice(key);
@@ -87,4 +116,17 @@ mod tests {
         
         println!("\nTime elapsed hashing session key-sets: {:?}\n", dur.as_millis());
     }
-}
\ No newline at end of file
+
+    /// rotation keeps the key, replaces the secret and invalidates the old secret against the new hash
+    #[test]
+    fn rotate_key_set() {
+        let key_set = KeySet::new().unwrap();
+        let rotated = KeySet::rotate(&key_set.key).unwrap();
+
+        assert_eq!(rotated.key, key_set.key);
+        assert_ne!(rotated.secret, key_set.secret);
+        assert_eq!(KeySet::verify(&rotated.key, &rotated.secret, &rotated.hash), VerificationStatus::Verified);
+        assert_eq!(KeySet::verify(&key_set.key, &key_set.secret, &rotated.hash), VerificationStatus::Unverified);
+        assert_ne!(rotated.token(), key_set.token());
+    }
+}
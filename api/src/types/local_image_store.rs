@@ -0,0 +1,147 @@
+use actix_web::web::Bytes;
+use futures::{future::LocalBoxFuture, stream};
+use std::{
+    fs::{self, File},
+    io::{self, ErrorKind, Read},
+    path::PathBuf
+};
+
+use crate::{
+    enums::Error,
+    traits::{ImageStore, ImageStream}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+const CHUNK_SIZE:usize = 64 * 1024;   // 64KiB per streamed chunk
+
+/// stores images as flat files under a root directory, blocking io is moved off the worker threads
+#[derive(Clone,Debug)]
+pub struct LocalImageStore {
+    root: PathBuf
+}
+
+impl LocalImageStore {
+    /// constructor, creates the root directory if it does not exist
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+
+        fs::create_dir_all(&root)
+            .map_err(|e| Error::StdError(e.to_string()))?;
+
+        Ok(LocalImageStore { root })
+    }
+
+    /// maps a storage key to a file path, keys cannot escape the root directory
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::ImageStorageKeyInvalid);
+        }
+
+        Ok(self.root.join(key))
+    }
+
+    /// reads the next chunk from an open file, None at end of file
+    fn read_chunk(mut file: File) -> io::Result<Option<(Bytes,File)>> {
+        let mut buf = vec![0_u8;CHUNK_SIZE];
+        let bytes_read = file.read(&mut buf)?;
+
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+
+        buf.truncate(bytes_read);
+        Ok(Some((Bytes::from(buf),file)))
+    }
+}
+
+impl ImageStore for LocalImageStore {
+    fn put(&self, key: &str, bytes: Bytes) -> LocalBoxFuture<'_, Result<()>> {
+        let path = self.path(key);
+
+        Box::pin(async move {
+            let path = path?;
+
+            actix_rt::task::spawn_blocking(move || {
+                // write to a temporary file first so readers never see a partial image
+                let tmp_path = path.with_extension("tmp");
+                fs::write(&tmp_path, &bytes)
+                    .and_then(|_| fs::rename(&tmp_path, &path))
+            })
+            .await?
+            .map_err(|e| Error::StdError(e.to_string()))
+        })
+    }
+
+    fn get(&self, key: &str) -> LocalBoxFuture<'_, Result<ImageStream>> {
+        let path = self.path(key);
+
+        Box::pin(async move {
+            let path = path?;
+
+            let file = actix_rt::task::spawn_blocking(move || File::open(path))
+                .await?
+                .map_err(|e| Error::StdError(e.to_string()))?;
+
+            // each chunk is read on the blocking pool
+            let image_stream = stream::try_unfold(file, |file| async move {
+                actix_rt::task::spawn_blocking(move || LocalImageStore::read_chunk(file))
+                    .await?
+                    .map_err(|e| Error::StdError(e.to_string()))
+            });
+
+            let boxed: ImageStream = Box::pin(image_stream);
+            Ok(boxed)
+        })
+    }
+
+    fn delete(&self, key: &str) -> LocalBoxFuture<'_, Result<()>> {
+        let path = self.path(key);
+
+        Box::pin(async move {
+            let path = path?;
+
+            actix_rt::task::spawn_blocking(move || {
+                match fs::remove_file(path) {
+                    Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                    result => result
+                }
+            })
+            .await?
+            .map_err(|e| Error::StdError(e.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    /// writes, streams back and deletes an image larger than a single chunk
+    #[actix_rt::test]
+    async fn put_get_delete() {
+        let root = std::env::temp_dir().join(format!("idropr-image-store-{}", rand::random::<u64>()));
+        let store = LocalImageStore::new(&root).unwrap();
+        let image: Vec<u8> = (0..(CHUNK_SIZE * 2 + 17)).map(|i| (i % 251) as u8).collect();
+
+        store.put("abc123", Bytes::from(image.clone())).await.unwrap();
+
+        let chunks: Vec<Bytes> = store.get("abc123").await.unwrap().try_collect().await.unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), image);
+
+        store.delete("abc123").await.unwrap();
+        assert!(store.get("abc123").await.is_err());
+
+        // deleting twice is not an error
+        store.delete("abc123").await.unwrap();
+
+        // keys cannot reach outside the root directory
+        assert!(store.put("../escape", Bytes::new()).await.is_err());
+        assert!(store.get("").await.is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
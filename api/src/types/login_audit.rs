@@ -0,0 +1,36 @@
+use chrono::Utc;
+
+use crate::{
+    enums::{Error, UserAccountStatus},
+    traits::ToNumber,
+    types::DatabaseConnection
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// a login refused because of the account status, written for later review
+#[derive(Clone,Debug,PartialEq)]
+pub struct LoginAudit {
+    pub user_id: i64,
+    pub username: String,
+    pub status: UserAccountStatus,
+    pub ip_address: Option<String>
+}
+
+impl LoginAudit {
+    /// writes the rejection and returns the new audit id
+    pub async fn into_db(self, database: &DatabaseConnection) -> Result<i64> {
+        let sql = "INSERT INTO `login_audit` (user_id,username,user_status_id,ip_address,created_at) VALUES(?,?,?,?,?)";
+        let insert_id = sqlx::query(sql)
+            .bind(self.user_id)
+            .bind(self.username)
+            .bind(self.status.to_i8())
+            .bind(self.ip_address)
+            .bind(Utc::now())
+            .execute(&database.pool)
+            .await?
+            .last_insert_id() as i64;
+
+        Ok(insert_id)
+    }
+}
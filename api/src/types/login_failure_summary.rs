@@ -0,0 +1,9 @@
+/// what an admin can see about a throttled username or ip address
+#[derive(Clone,Debug,PartialEq)]
+pub struct LoginFailureSummary {
+    pub key: String,
+    pub kind: &'static str,     // "username" or "ip_address"
+    pub failures: u32,
+    pub retry_after: u64,       // seconds until the next attempt is accepted, 0 when not blocked
+    pub locked_out: bool
+}
@@ -0,0 +1,360 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant}
+};
+
+use rate_limit::{enums::{Decision, RefillRate}, traits::Clock, types::SystemClock, RateLimitBuilder, RateLimiter};
+
+use crate::{
+    enums::{Error, LoginDecision},
+    types::LoginFailureSummary
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+const ATTEMPTS_PER_MINUTE:u32 = 10;     // raw attempts per username or ip, pass or fail
+const FREE_FAILURES:u32 = 3;            // failures allowed before backoff starts
+const BASE_BACKOFF:u64 = 1;             // 1 second, doubled per failure
+const MAX_BACKOFF:u64 = 300;            // 5 minutes
+const LOCKOUT_FAILURES:u32 = 10;        // failures before a lockout
+const LOCKOUT:u64 = 900;                // 15 minutes
+const FAILURE_TTL:u64 = 3600;           // failures are forgotten an hour after the last one
+const SWEEP_INTERVAL:u64 = 60;          // 60 seconds
+
+/// attempt buckets for usernames and ip addresses share one limiter
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+enum LoginKey {
+    Username(String),
+    IpAddress(String)
+}
+
+#[derive(Clone,Debug,Default)]
+struct LoginAttempts {
+    failures: u32,
+    last_failure: Option<Instant>,
+    blocked_until: Option<Instant>
+}
+
+impl LoginAttempts {
+    /// time left on a backoff or lockout
+    fn blocked_for(&self, now: Instant) -> Option<Duration> {
+        self.blocked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// exponential backoff after the free failures, a flat lockout once the limit is hit
+    fn backoff(failures: u32) -> Option<Duration> {
+        match failures {
+            0..=FREE_FAILURES => None,
+            LOCKOUT_FAILURES.. => Some(Duration::from_secs(LOCKOUT)),
+            _ => {
+                let exponent = failures - FREE_FAILURES - 1;
+                let secs = BASE_BACKOFF
+                    .checked_shl(exponent)
+                    .unwrap_or(MAX_BACKOFF)
+                    .min(MAX_BACKOFF);
+
+                Some(Duration::from_secs(secs))
+            }
+        }
+    }
+
+    /// entries with no recent failures and no block left can be dropped
+    fn is_stale(&self, now: Instant) -> bool {
+        let failures_forgotten = self.last_failure
+            .is_none_or(|last| now.duration_since(last) >= Duration::from_secs(FAILURE_TTL));
+
+        failures_forgotten && self.blocked_for(now).is_none()
+    }
+}
+
+/// throttles password guessing per username and per ip address, independent of the global rate limiter
+#[derive(Debug)]
+pub struct LoginThrottle {
+    limiter: RateLimiter<LoginKey>,
+    usernames: Mutex<HashMap<String,LoginAttempts>>,
+    ip_addresses: Mutex<HashMap<String,LoginAttempts>>,
+    clock: Arc<dyn Clock>                   // time source for the attempt buckets, backoffs and lockouts
+}
+
+impl LoginThrottle {
+
+    /// attempt buckets for usernames and ip addresses, refilled on the clock
+    fn limiter(clock: Arc<dyn Clock>) -> RateLimiter<LoginKey> {
+        RateLimitBuilder::default()
+            .with_bucket_capacity(ATTEMPTS_PER_MINUTE)
+            .with_tokens_per_bucket(ATTEMPTS_PER_MINUTE)
+            .with_refill_rate(RefillRate::PerMinute(ATTEMPTS_PER_MINUTE as f32))
+            .with_clock(clock)
+            .build()
+    }
+
+    /// set the time source, the system clock by default
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.limiter = LoginThrottle::limiter(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// garbage collector interval for the attempt buckets and the failure counts
+    pub async fn watch(&self) {
+        let failures = async {
+            let mut interval = actix_rt::time::interval(Duration::from_secs(SWEEP_INTERVAL));
+
+            loop {
+                interval.tick().await;
+                let _ = self.sweep();
+            }
+        };
+
+        futures::future::join(self.limiter.watch(), failures).await;
+    }
+
+    /// drops forgotten usernames and ip addresses
+    pub fn sweep(&self) -> Result<()> {
+        let now = self.clock.now();
+
+        for map in [&self.usernames, &self.ip_addresses] {
+            map.lock()
+                .map_err(|_e| Error::PoisonedLoginThrottle)?
+                .retain(|_, attempts| !attempts.is_stale(now));
+        }
+
+        Ok(())
+    }
+
+    /// usernames are matched case-insensitively so casing can't dodge the throttle
+    fn username_key(username: &str) -> String {
+        username.trim().to_lowercase()
+    }
+
+    /// checks a single key, spending an attempt token when it isn't blocked
+    fn check_key(&self, map: &Mutex<HashMap<String,LoginAttempts>>, key: LoginKey, now: Instant) -> Result<LoginDecision> {
+        let (LoginKey::Username(map_key) | LoginKey::IpAddress(map_key)) = &key;
+
+        let blocked_for = map
+            .lock()
+            .map_err(|_e| Error::PoisonedLoginThrottle)?
+            .get(map_key)
+            .and_then(|attempts| attempts.blocked_for(now));
+
+        if let Some(wait) = blocked_for {
+            return Ok(LoginDecision::Throttled(wait));
+        }
+
+        let quota = self.limiter.try_connect_key(&key)?;
+
+        match quota.decision {
+            Decision::Approved => Ok(LoginDecision::Allowed),
+            Decision::Denied => Ok(LoginDecision::Throttled(quota.retry_after))
+        }
+    }
+
+    /// called before a password is checked, the longer wait wins when both keys are blocked
+    pub fn check(&self, username: &str, ip_address: Option<&str>) -> Result<LoginDecision> {
+        let now = self.clock.now();
+        let by_username = self.check_key(&self.usernames, LoginKey::Username(LoginThrottle::username_key(username)), now)?;
+        let by_ip_address = match ip_address {
+            Some(ip_address) => self.check_key(&self.ip_addresses, LoginKey::IpAddress(ip_address.to_string()), now)?,
+            None => LoginDecision::Allowed
+        };
+
+        let decision = match (by_username, by_ip_address) {
+            (LoginDecision::Throttled(a), LoginDecision::Throttled(b)) => LoginDecision::Throttled(a.max(b)),
+            (LoginDecision::Throttled(wait), _) | (_, LoginDecision::Throttled(wait)) => LoginDecision::Throttled(wait),
+            _ => LoginDecision::Allowed
+        };
+
+        Ok(decision)
+    }
+
+    /// adds a failure to a single key and starts its backoff or lockout
+    fn fail_key(map: &Mutex<HashMap<String,LoginAttempts>>, key: &str, now: Instant) -> Result<()> {
+        let mut locked_map = map
+            .lock()
+            .map_err(|_e| Error::PoisonedLoginThrottle)?;
+
+        let attempts = locked_map
+            .entry(key.to_string())
+            .or_default();
+
+        // old failures are forgotten rather than stacking forever
+        if attempts.last_failure.is_some_and(|last| now.duration_since(last) >= Duration::from_secs(FAILURE_TTL)) {
+            attempts.failures = 0;
+        }
+
+        attempts.failures = attempts.failures.saturating_add(1);
+        attempts.last_failure = Some(now);
+        attempts.blocked_until = LoginAttempts::backoff(attempts.failures).map(|wait| now + wait);
+
+        Ok(())
+    }
+
+    /// records a failed login against the username and the ip address
+    pub fn record_failure(&self, username: &str, ip_address: Option<&str>) -> Result<()> {
+        let now = self.clock.now();
+        LoginThrottle::fail_key(&self.usernames, &LoginThrottle::username_key(username), now)?;
+
+        if let Some(ip_address) = ip_address {
+            LoginThrottle::fail_key(&self.ip_addresses, ip_address, now)?;
+        }
+
+        Ok(())
+    }
+
+    /// a successful login clears the username, the ip address keeps its count so one valid account can't reset a spray
+    pub fn record_success(&self, username: &str) -> Result<()> {
+        let _attempts = self.usernames
+            .lock()
+            .map_err(|_e| Error::PoisonedLoginThrottle)?
+            .remove(&LoginThrottle::username_key(username));
+
+        Ok(())
+    }
+
+    /// every username and ip address with failures on record, most failures first
+    pub fn failures(&self) -> Result<Vec<LoginFailureSummary>> {
+        let now = self.clock.now();
+        let mut summaries = Vec::new();
+
+        for (kind, map) in [("username", &self.usernames), ("ip_address", &self.ip_addresses)] {
+            let locked_map = map
+                .lock()
+                .map_err(|_e| Error::PoisonedLoginThrottle)?;
+
+            for (key, attempts) in locked_map.iter().filter(|(_, attempts)| attempts.failures > 0) {
+                let blocked_for = attempts.blocked_for(now);
+
+                summaries.push(LoginFailureSummary {
+                    key: key.clone(),
+                    kind,
+                    failures: attempts.failures,
+                    retry_after: blocked_for.map_or(0, |wait| wait.as_secs()),
+                    locked_out: blocked_for.is_some() && attempts.failures >= LOCKOUT_FAILURES
+                });
+            }
+        }
+
+        summaries.sort_by(|a, b| b.failures.cmp(&a.failures).then_with(|| a.key.cmp(&b.key)));
+
+        Ok(summaries)
+    }
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+
+        LoginThrottle {
+            limiter: LoginThrottle::limiter(clock.clone()),
+            usernames: Mutex::new(HashMap::new()),
+            ip_addresses: Mutex::new(HashMap::new()),
+            clock
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rate_limit::types::ManualClock;
+
+    use super::*;
+
+    /// the first failures are free, then the wait doubles up to the cap and ends in a lockout
+    #[test]
+    fn backoff_schedule() {
+        assert_eq!(LoginAttempts::backoff(FREE_FAILURES), None);
+        assert_eq!(LoginAttempts::backoff(FREE_FAILURES + 1), Some(Duration::from_secs(1)));
+        assert_eq!(LoginAttempts::backoff(FREE_FAILURES + 2), Some(Duration::from_secs(2)));
+        assert_eq!(LoginAttempts::backoff(FREE_FAILURES + 3), Some(Duration::from_secs(4)));
+        assert_eq!(LoginAttempts::backoff(LOCKOUT_FAILURES - 1), Some(Duration::from_secs(32)));
+        assert_eq!(LoginAttempts::backoff(LOCKOUT_FAILURES), Some(Duration::from_secs(LOCKOUT)));
+    }
+
+    /// failures block the username from any ip, and the ip for any username
+    #[test]
+    fn failures_block_username_and_ip() {
+        let throttle = LoginThrottle::default();
+
+        for _ in 0..=FREE_FAILURES {
+            assert_eq!(throttle.check("Alice", Some("10.0.0.1")).unwrap(), LoginDecision::Allowed);
+            throttle.record_failure("Alice", Some("10.0.0.1")).unwrap();
+        }
+
+        assert!(matches!(throttle.check("alice", Some("10.0.0.2")).unwrap(), LoginDecision::Throttled(_)));
+        assert!(matches!(throttle.check("bob", Some("10.0.0.1")).unwrap(), LoginDecision::Throttled(_)));
+        assert_eq!(throttle.check("bob", Some("10.0.0.2")).unwrap(), LoginDecision::Allowed);
+
+        // success clears the username but the ip keeps its failures
+        throttle.record_success("ALICE").unwrap();
+        assert_eq!(throttle.check("alice", Some("10.0.0.2")).unwrap(), LoginDecision::Allowed);
+        assert!(matches!(throttle.check("carol", Some("10.0.0.1")).unwrap(), LoginDecision::Throttled(_)));
+    }
+
+    /// hitting the failure limit locks the key out and is reported to admins
+    #[test]
+    fn lockout_is_reported() {
+        let clock = Arc::new(ManualClock::new());
+        let throttle = LoginThrottle::default().with_clock(clock.clone());
+
+        for _ in 0..LOCKOUT_FAILURES {
+            throttle.record_failure("alice", None).unwrap();
+        }
+
+        assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Throttled(Duration::from_secs(LOCKOUT)));
+
+        clock.advance(Duration::from_secs(LOCKOUT - 1));
+        assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Throttled(Duration::from_secs(1)));
+
+        throttle.record_failure("bob", Some("10.0.0.1")).unwrap();
+
+        let summaries = throttle.failures().unwrap();
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].key, "alice");
+        assert_eq!(summaries[0].failures, LOCKOUT_FAILURES);
+        assert!(summaries[0].locked_out);
+        assert!(!summaries[1].locked_out);
+    }
+
+    /// raw attempts are capped by the token bucket even without failures, the first attempt opens the bucket
+    #[test]
+    fn attempt_rate() {
+        let clock = Arc::new(ManualClock::new());
+        let throttle = LoginThrottle::default().with_clock(clock.clone());
+
+        for _ in 0..=ATTEMPTS_PER_MINUTE {
+            assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Allowed);
+        }
+
+        assert!(matches!(throttle.check("alice", None).unwrap(), LoginDecision::Throttled(_)));
+
+        // a quiet minute tops the bucket back up
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Allowed);
+    }
+
+    /// backoffs run out on the clock and forgotten keys are swept
+    #[test]
+    fn backoff_expires_on_the_clock() {
+        let clock = Arc::new(ManualClock::new());
+        let throttle = LoginThrottle::default().with_clock(clock.clone());
+
+        for _ in 0..=FREE_FAILURES {
+            throttle.record_failure("alice", None).unwrap();
+        }
+
+        assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Throttled(Duration::from_secs(BASE_BACKOFF)));
+
+        clock.advance(Duration::from_secs(BASE_BACKOFF));
+        assert_eq!(throttle.check("alice", None).unwrap(), LoginDecision::Allowed);
+
+        throttle.sweep().unwrap();
+        assert_eq!(throttle.failures().unwrap().len(), 1);
+
+        clock.advance(Duration::from_secs(FAILURE_TTL));
+        throttle.sweep().unwrap();
+        assert!(throttle.failures().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+use actix_web::web::Data;
+
+use crate::types::AppState;
+
+pub struct LoginThrottleSweeper;
+
+impl LoginThrottleSweeper {
+    pub async fn run(arc_state: &Data<AppState>) {
+        let app_state = arc_state.clone();
+
+        let _garbage_collector = actix_web::rt::spawn(async move {
+            app_state.login_throttle().watch().await;
+        });
+    }
+}
@@ -2,18 +2,36 @@ mod api_response;
 mod api_server;
 mod app_state;
 mod authorization_token;
+mod bucket;
 mod cli;
 mod database_connection;
 mod env;
 mod session_sweeper;
 mod header_settings;
+mod image;
+mod local_image_store;
+mod login_audit;
+mod login_failure_summary;
+mod login_throttle;
+mod login_throttle_sweeper;
 mod permission_check;
+mod rate_limit_policy;
+mod rate_limit_snapshot;
+mod rate_limit_store;
 mod rate_limit_sweeper;
+mod refresh_token;
 mod route_collection;
 mod session;
+mod session_check;
 mod session_controller;
+mod session_policies;
+mod session_policy;
+mod session_store;
+mod session_summary;
 mod key_set;
 mod settings;
+mod settings_reloader;
+mod trusted_proxies;
 mod user_permissions;
 
 pub mod users;
@@ -21,17 +39,35 @@ pub mod users;
 pub use authorization_token::AuthorizationToken;
 pub use api_response::ApiResponse;
 pub use api_server::ApiServer;
+pub use bucket::Bucket;
 pub use app_state::AppState;
 pub use cli::Cli;
 pub use database_connection::DatabaseConnection;
 pub use env::Env;
 pub use session_sweeper::SessionSweeper;
 pub use header_settings::HeaderSettings;
+pub use image::Image;
+pub use local_image_store::LocalImageStore;
+pub use login_audit::LoginAudit;
+pub use login_failure_summary::LoginFailureSummary;
+pub use login_throttle::LoginThrottle;
+pub use login_throttle_sweeper::LoginThrottleSweeper;
 pub use permission_check::PermissionCheck;
+pub use rate_limit_policy::RateLimitPolicy;
+pub use rate_limit_snapshot::RateLimitSnapshot;
+pub use rate_limit_store::RateLimitStore;
 pub use rate_limit_sweeper::RateLimitSweeper;
+pub use refresh_token::RefreshToken;
 pub use route_collection::RouteCollection;
 pub use session::Session;
+pub use session_check::SessionCheck;
 pub use session_controller::SessionController;
+pub use session_policies::SessionPolicies;
+pub use session_policy::SessionPolicy;
+pub use session_store::{SessionRecord, SessionStore};
+pub use session_summary::SessionSummary;
 pub use key_set::KeySet;
 pub use settings::Settings;
+pub use settings_reloader::SettingsReloader;
+pub use trusted_proxies::TrustedProxies;
 pub use user_permissions::UserPermissions;
\ No newline at end of file
@@ -2,5 +2,6 @@ use crate::enums::{Permission,RefreshStatus};
 
 pub struct PermissionCheck {
     pub permission: Permission,
-    pub refresh_status: RefreshStatus
+    pub refresh_status: RefreshStatus,
+    pub user_id: i64
 }
\ No newline at end of file
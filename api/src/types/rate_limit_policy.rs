@@ -0,0 +1,140 @@
+use rate_limit::{enums::RefillRate, RateLimitBuilder, RateLimiter};
+
+use crate::types::{DatabaseConnection, Env, RateLimitStore};
+
+/// a named rate limit budget attached to individual routes, each policy gets its own limiter
+#[derive(Clone,Debug,PartialEq)]
+pub struct RateLimitPolicy {
+    pub name: &'static str,
+    pub capacity: u32,              // most tokens a bucket can hold
+    pub initial_tokens: u32,        // tokens a new bucket starts with
+    pub refill_rate: RefillRate,
+    pub cost: u32,                  // tokens spent per request
+    pub bytes_per_token: Option<u64>    // extra token per this many bytes of Content-Length
+}
+
+impl RateLimitPolicy {
+    /// constructor, one token per request
+    pub fn new(name: &'static str, capacity: u32, refill_rate: RefillRate) -> Self {
+        RateLimitPolicy {
+            name,
+            capacity,
+            initial_tokens: capacity,
+            refill_rate,
+            cost: 1,
+            bytes_per_token: None
+        }
+    }
+
+    /// sets the starting number of tokens in each bucket
+    pub fn with_initial_tokens(mut self, initial_tokens: u32) -> Self {
+        self.initial_tokens = initial_tokens;
+        self
+    }
+
+    /// sets the tokens spent per request
+    pub fn with_cost(mut self, cost: u32) -> Self {
+        self.cost = cost.max(1);
+        self
+    }
+
+    /// charges large request bodies an extra token per `bytes` of Content-Length
+    pub fn with_size_cost(mut self, bytes: u64) -> Self {
+        self.bytes_per_token = Some(bytes.max(1));
+        self
+    }
+
+    /// size costed routes can't price a body without a Content-Length, chunked uploads would only pay the base cost
+    pub fn length_required(&self, content_length: Option<u64>) -> bool {
+        self.bytes_per_token.is_some() && content_length.is_none()
+    }
+
+    /// tokens a request spends, never more than a full bucket so any request can eventually pass
+    pub fn cost_for(&self, content_length: Option<u64>) -> u32 {
+        let size_cost = match (self.bytes_per_token, content_length) {
+            (Some(bytes), Some(length)) => length / bytes,
+            _ => 0
+        };
+
+        let cost = (self.cost as u64).saturating_add(size_cost);
+
+        cost.min(self.capacity.max(1) as u64) as u32
+    }
+
+    /// password and refresh token exchanges
+    pub fn login() -> Self {
+        RateLimitPolicy::new("login", 20, RefillRate::PerMinute(20.0))
+            .with_initial_tokens(10)
+    }
+
+    /// image uploads
+    pub fn uploads() -> Self {
+        RateLimitPolicy::new("uploads", 50, RefillRate::PerHour(100.0))
+            .with_cost(5)
+            .with_size_cost(1024 * 1024)
+    }
+
+    /// health checks from monitors and load balancers
+    pub fn health() -> Self {
+        RateLimitPolicy::new("health", 120, RefillRate::PerMinute(120.0))
+    }
+
+    /// every named policy attached in the RouteCollection
+    pub fn all() -> Vec<RateLimitPolicy> {
+        vec![
+            RateLimitPolicy::health(),
+            RateLimitPolicy::login(),
+            RateLimitPolicy::uploads()
+        ]
+    }
+
+    /// builds the limiter backing this policy, shared with the other server instances when LIMITER_SHARED is set
+    pub fn build(&self, env: &Env, database: &DatabaseConnection) -> RateLimiter {
+        let builder = RateLimitBuilder::default()
+            .with_bucket_capacity(self.capacity)
+            .with_tokens_per_bucket(self.initial_tokens)
+            .with_refill_rate(self.refill_rate.clone())
+            .with_ipv4_prefix(env.limiter_ipv4_prefix)
+            .with_ipv6_prefix(env.limiter_ipv6_prefix)
+            .with_algorithm(env.limiter_algorithm)
+            .shard_into(env.server_threads);
+
+        RateLimitStore::attach(builder, env, database, self.name).build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// policy names key the limiters in the app state, so they must be unique
+    #[test]
+    fn unique_policy_names() {
+        let policies = RateLimitPolicy::all();
+        let mut names: Vec<&str> = policies.iter().map(|policy| policy.name).collect();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(names.len(), policies.len());
+        assert_eq!(RateLimitPolicy::login().with_cost(0).cost, 1);
+    }
+
+    /// body size adds to the base cost up to a full bucket
+    #[test]
+    fn size_cost() {
+        let policy = RateLimitPolicy::new("test", 20, RefillRate::PerMinute(20.0))
+            .with_cost(2)
+            .with_size_cost(100);
+
+        assert_eq!(policy.cost_for(None), 2);
+        assert_eq!(policy.cost_for(Some(99)), 2);
+        assert_eq!(policy.cost_for(Some(250)), 4);
+        assert_eq!(policy.cost_for(Some(u64::MAX)), 20);
+        assert_eq!(RateLimitPolicy::health().cost_for(Some(10_000)), 1);
+
+        // chunked bodies are turned away on size costed routes only
+        assert!(policy.length_required(None));
+        assert!(!policy.length_required(Some(0)));
+        assert!(!RateLimitPolicy::health().length_required(None));
+    }
+}
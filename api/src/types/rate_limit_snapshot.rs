@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use actix_web::web::Data;
+use rate_limit::RateLimiter;
+
+use crate::{
+    enums::RateLimiterStatus,
+    types::{AppState, Env}
+};
+
+const GLOBAL_SNAPSHOT:&str = "global";
+
+/// carries rate limiter buckets and black and whitelists across restarts, one file per limiter
+pub struct RateLimitSnapshot;
+
+impl RateLimitSnapshot {
+    /// every limiter with the file its snapshot lives in
+    fn limiters<'a>(app_state: &'a AppState, dir: &Path) -> Vec<(PathBuf,&'a RateLimiter)> {
+        let global = match app_state.rate_limiter_instance() {
+            RateLimiterStatus::Enabled(limiter) => Some((dir.join(format!("{GLOBAL_SNAPSHOT}.snapshot")), limiter.as_ref())),
+            RateLimiterStatus::Disabled => None
+        };
+
+        let routes = app_state
+            .route_limiters_instance()
+            .iter()
+            .map(|(policy, limiter)| (dir.join(format!("{policy}.snapshot")), limiter));
+
+        global.into_iter().chain(routes).collect()
+    }
+
+    /// loads the snapshots written at the last shutdown, a bad snapshot is skipped rather than stopping the boot
+    pub fn restore(arc_state: &Data<AppState>, env: &Env) {
+        let Some(dir) = &env.limiter_snapshot_dir else {
+            return;
+        };
+
+        for (path, limiter) in RateLimitSnapshot::limiters(arc_state, Path::new(dir)) {
+            if let Err(_e) = limiter.load(&path) {
+                // log here
+            }
+        }
+    }
+
+    /// writes every limiter's state, called once the server has shut down gracefully
+    pub fn save(arc_state: &Data<AppState>, env: &Env) {
+        let Some(dir) = &env.limiter_snapshot_dir else {
+            return;
+        };
+
+        if let Err(_e) = std::fs::create_dir_all(dir) {
+            // log here
+            return;
+        }
+
+        for (path, limiter) in RateLimitSnapshot::limiters(arc_state, Path::new(dir)) {
+            if let Err(_e) = limiter.save(&path) {
+                // log here
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use rate_limit::{
+    enums::RateLimitError,
+    traits::{SharedStore, StoreFuture},
+    RateLimitBuilder
+};
+use sqlx::{MySql, QueryBuilder};
+
+use crate::{
+    enums::{Error, SystemFlag},
+    types::{DatabaseConnection, Env}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+const BATCH_ROWS:usize = 1_000;    // rows per statement, well under the placeholder limit
+
+/// counts a limiter's keys in the `rate_limit_counter` table so every server instance shares its budget.
+/// rows are keyed on the limiter name, the window and the address as 4 or 16 bytes
+#[derive(Clone,Debug)]
+pub struct RateLimitStore {
+    database: DatabaseConnection,
+    limiter: &'static str
+}
+
+impl RateLimitStore {
+    pub fn new(database: DatabaseConnection, limiter: &'static str) -> Self {
+        RateLimitStore { database, limiter }
+    }
+
+    /// points a limiter at the shared table when LIMITER_SHARED is set
+    pub fn attach(builder: RateLimitBuilder, env: &Env, database: &DatabaseConnection, limiter: &'static str) -> RateLimitBuilder {
+        match env.limiter_shared {
+            SystemFlag::Enabled => builder
+                .with_shared_store(Arc::new(RateLimitStore::new(database.clone(), limiter)))
+                .with_sync_interval(Duration::from_millis(env.limiter_sync_millis)),
+            SystemFlag::Disabled => builder
+        }
+    }
+
+    /// the stored form of a key
+    fn key_bytes(key: &IpAddr) -> Vec<u8> {
+        match key {
+            IpAddr::V4(ip_address) => ip_address.octets().to_vec(),
+            IpAddr::V6(ip_address) => ip_address.octets().to_vec()
+        }
+    }
+
+    /// reads a stored key back
+    fn key_from_bytes(bytes: Vec<u8>) -> Result<IpAddr> {
+        match bytes.len() {
+            4 => Ok(IpAddr::from(<[u8;4]>::try_from(bytes).map_err(|_e| Error::RateLimitCounterInvalid)?)),
+            16 => Ok(IpAddr::from(<[u8;16]>::try_from(bytes).map_err(|_e| Error::RateLimitCounterInvalid)?)),
+            _ => Err(Error::RateLimitCounterInvalid)
+        }
+    }
+
+    /// adds the counts to the window and reads back the totals
+    async fn add_counts(&self, window: u64, counts: Vec<(IpAddr,u32)>) -> Result<Vec<(IpAddr,u32)>> {
+        let adding: Vec<&(IpAddr,u32)> = counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        for batch in adding.chunks(BATCH_ROWS) {
+            let mut query: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO `rate_limit_counter` (limiter,rate_window,rate_key,hits) ");
+            query.push_values(batch, |mut row, (key, count)| {
+                row.push_bind(self.limiter)
+                    .push_bind(window)
+                    .push_bind(RateLimitStore::key_bytes(key))
+                    .push_bind(*count);
+            });
+            query.push(" ON DUPLICATE KEY UPDATE hits = hits + VALUES(hits)");
+
+            query.build()
+                .execute(&self.database.pool)
+                .await?;
+        }
+
+        let mut totals: Vec<(IpAddr,u32)> = Vec::with_capacity(counts.len());
+
+        for batch in counts.chunks(BATCH_ROWS) {
+            let mut query: QueryBuilder<MySql> = QueryBuilder::new("SELECT rate_key,hits FROM `rate_limit_counter` WHERE limiter = ");
+            query.push_bind(self.limiter)
+                .push(" AND rate_window = ")
+                .push_bind(window)
+                .push(" AND rate_key IN (");
+
+            let mut keys = query.separated(",");
+            for (key, _) in batch {
+                keys.push_bind(RateLimitStore::key_bytes(key));
+            }
+            keys.push_unseparated(")");
+
+            let rows: Vec<(Vec<u8>,u32)> = query.build_query_as()
+                .fetch_all(&self.database.pool)
+                .await?;
+
+            for (key, hits) in rows {
+                totals.push((RateLimitStore::key_from_bytes(key)?, hits));
+            }
+        }
+
+        Ok(totals)
+    }
+
+    /// removes the windows before `window`
+    async fn delete_before(&self, window: u64) -> Result<()> {
+        let sql = "DELETE FROM `rate_limit_counter` WHERE limiter = ? AND rate_window < ?";
+        sqlx::query(sql)
+            .bind(self.limiter)
+            .bind(window)
+            .execute(&self.database.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl SharedStore<IpAddr> for RateLimitStore {
+    fn add(&self, window: u64, counts: Vec<(IpAddr,u32)>) -> StoreFuture<'_, Vec<(IpAddr,u32)>> {
+        Box::pin(async move {
+            self.add_counts(window, counts)
+                .await
+                .map_err(|e| RateLimitError::SharedStore(e.to_string()))
+        })
+    }
+
+    fn expire(&self, window: u64) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            self.delete_before(window)
+                .await
+                .map_err(|e| RateLimitError::SharedStore(e.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// keys go in as 4 or 16 bytes and come back as the address they were, anything else is rejected
+    #[test]
+    fn key_bytes_round_trip() {
+        for ip_address in ["10.0.0.1", "2001:db8::"] {
+            let ip_address: IpAddr = ip_address.parse().unwrap();
+            let bytes = RateLimitStore::key_bytes(&ip_address);
+
+            assert_eq!(RateLimitStore::key_from_bytes(bytes).unwrap(), ip_address);
+        }
+
+        assert_eq!(RateLimitStore::key_bytes(&"10.0.0.1".parse().unwrap()).len(), 4);
+        assert!(matches!(RateLimitStore::key_from_bytes(vec![1;5]), Err(Error::RateLimitCounterInvalid)));
+        assert!(matches!(RateLimitStore::key_from_bytes(Vec::new()), Err(Error::RateLimitCounterInvalid)));
+    }
+}
@@ -12,10 +12,39 @@ impl RateLimitSweeper {
         let app_state = arc_state.clone();
 
         let _garbage_collector = actix_web::rt::spawn(async move {
-            match app_state.rate_limiter() {
+            match app_state.rate_limiter_instance() {
                 RateLimiterStatus::Enabled(limiter) => limiter.watch().await,
                 RateLimiterStatus::Disabled => {}
             }
         });
+
+        let app_state = arc_state.clone();
+
+        let _route_garbage_collector = actix_web::rt::spawn(async move {
+            let watchers = app_state
+                .route_limiters_instance()
+                .values()
+                .map(|limiter| limiter.watch());
+
+            futures::future::join_all(watchers).await;
+        });
+    }
+
+    /// sends counts admitted since the last sync to the shared store, called once the server has shut down
+    pub async fn flush(arc_state: &Data<AppState>) {
+        let global = match arc_state.rate_limiter_instance() {
+            RateLimiterStatus::Enabled(limiter) => Some(limiter.as_ref()),
+            RateLimiterStatus::Disabled => None
+        };
+
+        let limiters = global
+            .into_iter()
+            .chain(arc_state.route_limiters_instance().values());
+
+        for limiter in limiters {
+            if let Err(_e) = limiter.sync().await {
+                // log here
+            }
+        }
     }
-}
\ No newline at end of file
+}
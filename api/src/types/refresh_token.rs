@@ -0,0 +1,267 @@
+use blake3::Hash;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::FromRow;
+
+use crate::{
+    enums::{Error, ExpiredStatus, RowsUpdated},
+    traits::ToUpdatedResult,
+    types::{DatabaseConnection, KeySet}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+const REFRESH_TOKEN_LIFETIME:i64 = 30;     // 30 days
+
+/// a long-lived token exchanged for new sessions, only its hash is stored.
+/// every exchange issues a successor in the same family and marks the old token used,
+/// the whole family expires with the token issued at login
+#[derive(Clone,Debug,PartialEq)]
+pub struct RefreshToken {
+    pub key: [u8;16],
+    pub hash: Hash,
+    pub family: [u8;16],            // key of the first token issued at login
+    pub user_id: i64,
+    pub session_key: [u8;16],       // session issued alongside this token
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>
+}
+
+/// database record transformer
+#[derive(Debug,FromRow)]
+struct DatabaseHelper {
+    token_key: Vec<u8>,
+    hash: Vec<u8>,
+    family_key: Vec<u8>,
+    user_id: i64,
+    session_key: Vec<u8>,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>
+}
+
+impl DatabaseHelper {
+    /// transforms the raw database record into a RefreshToken
+    fn transform(self) -> Result<RefreshToken> {
+        let key: [u8;16] = self.token_key
+            .try_into()
+            .map_err(|_e| Error::RefreshTokenRecordInvalid)?;
+
+        let hash: [u8;32] = self.hash
+            .try_into()
+            .map_err(|_e| Error::RefreshTokenRecordInvalid)?;
+
+        let family: [u8;16] = self.family_key
+            .try_into()
+            .map_err(|_e| Error::RefreshTokenRecordInvalid)?;
+
+        let session_key: [u8;16] = self.session_key
+            .try_into()
+            .map_err(|_e| Error::RefreshTokenRecordInvalid)?;
+
+        let refresh_token = RefreshToken {
+            key,
+            hash: Hash::from(hash),
+            family,
+            user_id: self.user_id,
+            session_key,
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            used_at: self.used_at
+        };
+
+        Ok(refresh_token)
+    }
+}
+
+impl RefreshToken {
+    /// first token of a new family, issued at login
+    pub fn new(key_set: &KeySet, user_id: i64, session_key: [u8;16]) -> Self {
+        let created_at = Utc::now();
+
+        RefreshToken {
+            key: key_set.key,
+            hash: key_set.hash,
+            family: key_set.key,
+            user_id,
+            session_key,
+            created_at,
+            expires_at: created_at + Duration::days(REFRESH_TOKEN_LIFETIME),
+            used_at: None
+        }
+    }
+
+    /// next token of an exchanged token's family, expiring with it
+    pub fn successor(key_set: &KeySet, previous: &RefreshToken, session_key: [u8;16]) -> Self {
+        RefreshToken {
+            key: key_set.key,
+            hash: key_set.hash,
+            family: previous.family,
+            user_id: previous.user_id,
+            session_key,
+            created_at: Utc::now(),
+            expires_at: previous.expires_at,
+            used_at: None
+        }
+    }
+
+    /// returns the expired status
+    pub fn is_expired(&self) -> ExpiredStatus {
+        match Utc::now() < self.expires_at {
+            true => ExpiredStatus::NotExpired,
+            false => ExpiredStatus::Expired
+        }
+    }
+
+    /// persists the token
+    pub async fn into_db(&self, database: &DatabaseConnection) -> Result<()> {
+        let sql = "INSERT INTO `refresh_token` (token_key,hash,family_key,user_id,session_key,created_at,expires_at) VALUES(?,?,?,?,?,?,?)";
+        sqlx::query(sql)
+            .bind(self.key.as_slice())
+            .bind(self.hash.as_bytes().as_slice())
+            .bind(self.family.as_slice())
+            .bind(self.user_id)
+            .bind(self.session_key.as_slice())
+            .bind(self.created_at)
+            .bind(self.expires_at)
+            .execute(&database.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// try to get a token by its key
+    pub async fn by_key(key: &[u8;16], database: &DatabaseConnection) -> Result<Option<RefreshToken>> {
+        let sql = "SELECT token_key,hash,family_key,user_id,session_key,created_at,expires_at,used_at FROM `refresh_token` WHERE token_key = ?";
+        let helper_opt:Option<DatabaseHelper> = sqlx::query_as(sql)
+            .bind(key.as_slice())
+            .fetch_optional(&database.pool)
+            .await?;
+
+        helper_opt.map(DatabaseHelper::transform).transpose()
+    }
+
+    /// marks a token used, no rows are updated when it was already used so concurrent replays are caught
+    pub async fn mark_used(&self, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "UPDATE `refresh_token` SET used_at = ? WHERE token_key = ? AND used_at IS NULL";
+        let rows_affected = sqlx::query(sql)
+            .bind(Utc::now())
+            .bind(self.key.as_slice())
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+
+    /// sessions issued anywhere in the token's family
+    pub async fn family_sessions(&self, database: &DatabaseConnection) -> Result<Vec<[u8;16]>> {
+        let sql = "SELECT session_key FROM `refresh_token` WHERE family_key = ?";
+        let session_keys:Vec<(Vec<u8>,)> = sqlx::query_as(sql)
+            .bind(self.family.as_slice())
+            .fetch_all(&database.pool)
+            .await?;
+
+        session_keys
+            .into_iter()
+            .map(|(session_key,)| session_key.try_into().map_err(|_e| Error::RefreshTokenRecordInvalid))
+            .collect()
+    }
+
+    /// revokes every token in the token's family
+    pub async fn revoke_family(&self, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "DELETE FROM `refresh_token` WHERE family_key = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(self.family.as_slice())
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+
+    /// revokes the family whose latest session is being signed out
+    pub async fn revoke_by_session(session_key: &[u8;16], database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "DELETE FROM `refresh_token` WHERE family_key IN (SELECT family_key FROM (SELECT family_key FROM `refresh_token` WHERE session_key = ?) AS family)";
+        let rows_affected = sqlx::query(sql)
+            .bind(session_key.as_slice())
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+
+    /// removes every expired token, used tokens stay until then so replays are still caught
+    pub async fn delete_expired(database: &DatabaseConnection) -> Result<u64> {
+        let sql = "DELETE FROM `refresh_token` WHERE expires_at <= ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(Utc::now())
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    /// revokes every token a user holds
+    pub async fn revoke_by_user(user_id: i64, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        let sql = "DELETE FROM `refresh_token` WHERE user_id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(user_id)
+            .execute(&database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected.to_updated_result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// refresh token records must carry exact length keys and hashes
+    #[test]
+    fn database_helper_transform() {
+        let key_set = KeySet::new().unwrap();
+        let helper = |token_key: Vec<u8>| DatabaseHelper {
+            token_key,
+            hash: key_set.hash.as_bytes().to_vec(),
+            family_key: key_set.key.to_vec(),
+            user_id: 7,
+            session_key: key_set.secret.to_vec(),
+            created_at: Utc::now(),
+            expires_at: Utc::now(),
+            used_at: None
+        };
+
+        let refresh_token = helper(key_set.key.to_vec()).transform().unwrap();
+        assert_eq!(refresh_token.key, key_set.key);
+        assert_eq!(refresh_token.hash, key_set.hash);
+        assert_eq!(refresh_token.family, key_set.key);
+        assert_eq!(refresh_token.session_key, key_set.secret);
+
+        assert!(matches!(helper(vec![0;15]).transform(), Err(Error::RefreshTokenRecordInvalid)));
+    }
+
+    /// a family starts at the first token, successors inherit it along with its expiry
+    #[test]
+    fn family_and_expiry() {
+        let first = KeySet::new().unwrap();
+        let second = KeySet::new().unwrap();
+
+        let token = RefreshToken::new(&first, 7, [1;16]);
+        assert_eq!(token.family, first.key);
+        assert_eq!(token.is_expired(), ExpiredStatus::NotExpired);
+
+        let mut successor = RefreshToken::successor(&second, &token, [2;16]);
+        assert_eq!(successor.family, first.key);
+        assert_eq!(successor.key, second.key);
+        assert_eq!(successor.user_id, 7);
+        assert_eq!(successor.expires_at, token.expires_at);
+
+        successor.expires_at = Utc::now() - Duration::seconds(1);
+        assert_eq!(successor.is_expired(), ExpiredStatus::Expired);
+    }
+}
@@ -3,11 +3,15 @@ use actix_web::{web,Scope};
 
 use crate::{
     api::{
+        admin,
+        buckets,
         HealthCheck,
-        sessions
+        images,
+        sessions,
+        users
     },
-    services::RouteLock,
-    types::UserPermissions
+    services::{RateLimitMiddleware, RouteLock},
+    types::{RateLimitPolicy, UserPermissions}
 };
 
 #[derive(Clone,Debug)]
@@ -20,6 +24,10 @@ impl RouteCollection {
         Scope::new("/v1")
             .configure(RouteCollection::health)
             .configure(RouteCollection::sessions)
+            .configure(RouteCollection::users)
+            .configure(RouteCollection::buckets)
+            .configure(RouteCollection::images)
+            .configure(RouteCollection::admin)
     }
 }
 
@@ -27,30 +35,91 @@ impl RouteCollection {
 impl RouteCollection {
     /// returns server health
     pub fn health(cfg: &mut web::ServiceConfig) {
-        cfg.route("/health", web::get().to(HealthCheck::logic));
+        cfg.route("/health", web::get().to(HealthCheck::logic).wrap(RateLimitMiddleware::with_policy(RateLimitPolicy::health())));
         //.wrap(RouteLock::default(UserPermissions::default()))
     }
 
     /// sessions resource and endpoints
     pub fn sessions(cfg: &mut web::ServiceConfig) {
-        cfg.route("/sessions", web::post().to(sessions::SessionsPost::logic));
+        cfg.route("/sessions", web::post().to(sessions::SessionsPost::logic).wrap(RateLimitMiddleware::with_policy(RateLimitPolicy::login())));
+        cfg.route("/sessions/refresh", web::post().to(sessions::SessionsRefresh::logic).wrap(RateLimitMiddleware::with_policy(RateLimitPolicy::login())));
         
         let permissions = UserPermissions::default().with_sessions_delete();
-        cfg.route("/sessions", web::delete().to(sessions::SessionsDelete::logic).wrap(RouteLock::default(permissions)));
+        cfg.route("/sessions", web::delete().to(sessions::SessionsDelete::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_sessions_read();
+        cfg.route("/users/{user_id}/sessions", web::get().to(sessions::SessionsList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_sessions_delete();
+        cfg.route("/users/{user_id}/sessions", web::delete().to(sessions::SessionsRevokeAll::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_sessions_delete();
+        cfg.route("/users/{user_id}/sessions/{session_id}", web::delete().to(sessions::SessionsRevoke::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
     }
     
     /// users resource and endpoints
-    pub fn users(_cfg: &mut web::ServiceConfig) {
-        todo!()
+    pub fn users(cfg: &mut web::ServiceConfig) {
+        let permissions = UserPermissions::default().with_users_write();
+        cfg.route("/users", web::post().to(users::UsersPost::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_users_read();
+        cfg.route("/users/{user_id}", web::get().to(users::UsersGet::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_users_write();
+        cfg.route("/users/{user_id}", web::patch().to(users::UsersPatch::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_users_delete();
+        cfg.route("/users/{user_id}", web::delete().to(users::UsersDelete::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
     }
 
     /// buckets resource and endpoints
-    pub fn buckets(_cfg: &mut web::ServiceConfig) {
-        todo!()
+    pub fn buckets(cfg: &mut web::ServiceConfig) {
+        let permissions = UserPermissions::default().with_buckets_read();
+        cfg.route("/buckets", web::get().to(buckets::BucketsList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_buckets_write();
+        cfg.route("/buckets", web::post().to(buckets::BucketsPost::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_buckets_read();
+        cfg.route("/buckets/{bucket_id}", web::get().to(buckets::BucketsGet::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_buckets_write();
+        cfg.route("/buckets/{bucket_id}", web::patch().to(buckets::BucketsPatch::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_buckets_delete();
+        cfg.route("/buckets/{bucket_id}", web::delete().to(buckets::BucketsDelete::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+    }
+
+    /// admin resources and endpoints
+    pub fn admin(cfg: &mut web::ServiceConfig) {
+        let permissions = UserPermissions::default().with_admin_read();
+        cfg.route("/admin/login-failures", web::get().to(admin::LoginFailuresList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_admin_read();
+        cfg.route("/admin/ratelimit/{list}", web::get().to(admin::RateLimitEntriesList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_admin_write();
+        cfg.route("/admin/ratelimit/{list}", web::post().to(admin::RateLimitEntriesPost::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_admin_read();
+        cfg.route("/admin/settings/changes", web::get().to(admin::SettingsChangesList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_admin_delete();
+        cfg.route("/admin/ratelimit/{list}/{ip_address}", web::delete().to(admin::RateLimitEntriesDelete::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
     }
 
     /// images resource and endpoints
-    pub fn images(_cfg: &mut web::ServiceConfig) {
-        todo!()
+    pub fn images(cfg: &mut web::ServiceConfig) {
+        let permissions = UserPermissions::default().with_images_read();
+        cfg.route("/images", web::get().to(images::ImagesList::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_images_write();
+        cfg.route("/images", web::post().to(images::ImagesPost::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::with_policy(RateLimitPolicy::uploads())));
+
+        let permissions = UserPermissions::default().with_images_read();
+        cfg.route("/images/{image_id}", web::get().to(images::ImagesGet::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
+
+        let permissions = UserPermissions::default().with_images_delete();
+        cfg.route("/images/{image_id}", web::delete().to(images::ImagesDelete::logic).wrap(RouteLock::default(permissions)).wrap(RateLimitMiddleware::default()));
     }
 }
\ No newline at end of file
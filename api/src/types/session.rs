@@ -1,45 +1,186 @@
 use blake3::Hash;
-use std::time::{Duration, Instant};
+use chrono::{DateTime,Utc};
+use std::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::{Duration, Instant}
+};
 use rand::random_range;
 
 use crate::{
-    enums::{ExpiredStatus, RefreshStatus, User},
-    types::KeySet
+    enums::{ExpiredStatus, RefreshStatus, User, VerificationStatus},
+    types::{KeySet, SessionPolicy, SessionRecord}
 };
 
 const BASE_REFRESH_TIME:u64 = 60 * 10;      // 10 minutes
-const MAX_SESSION_AGE:u64 = 60 * 60 * 24;   // 1 day
 
-#[derive(Clone,Debug)]
+#[derive(Debug)]
 pub struct Session {
     pub hash: Hash,
     pub next_refresh: Instant,
-    pub user: User
+    pub user: User,
+    pub created_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub absolute_deadline: Instant,     // activity never moves this
+    pub idle_timeout: Duration,
+    pub previous_hash: Option<(Hash,Instant)>,  // hash before the last rotation and when it stops being accepted
+    anchor: Instant,                    // monotonic reference point for last_active
+    anchor_at: DateTime<Utc>,           // wall clock time of the anchor
+    last_active: AtomicI64              // millis since anchor, atomic so permission checks can stamp it under a read lock
+}
+
+impl Clone for Session {
+    fn clone(&self) -> Self {
+        Session {
+            hash: self.hash,
+            next_refresh: self.next_refresh,
+            user: self.user.clone(),
+            created_at: self.created_at,
+            ip_address: self.ip_address.clone(),
+            user_agent: self.user_agent.clone(),
+            absolute_deadline: self.absolute_deadline,
+            idle_timeout: self.idle_timeout,
+            previous_hash: self.previous_hash,
+            anchor: self.anchor,
+            anchor_at: self.anchor_at,
+            last_active: AtomicI64::new(self.last_active.load(Ordering::Relaxed))
+        }
+    }
 }
 
 impl Session {
 
-    /// creates a new session container
-    pub fn new(key_set: &KeySet, user: User) -> Self {
-        let now = Instant::now();
+    /// jittered refresh deadline so sessions created together don't all go stale together
+    fn next_refresh_time(now: Instant) -> Instant {
         let jitter = random_range(0.8..1.2);
         let duration_secs = (BASE_REFRESH_TIME as f32 * jitter).trunc() as u64;
-        let next_refresh = now
+
+        now
             .checked_add(Duration::from_secs(duration_secs))
             .or(Some(now))
-            .expect("unreachable after .or()");
+            .expect("unreachable after .or()")
+    }
+
+    /// monotonic deadline a duration after the anchor, saturating far in the future
+    fn deadline(anchor: Instant, duration: Duration) -> Instant {
+        anchor
+            .checked_add(duration)
+            .or(anchor.checked_add(Duration::from_secs(u32::MAX as u64)))
+            .unwrap_or(anchor)
+    }
+
+    /// creates a new session container under the default policy, starting at `now`
+    pub fn new(key_set: &KeySet, user: User, now: Instant) -> Self {
+        let anchor = now;
+        let anchor_at = Utc::now();
+        let policy = SessionPolicy::default();
 
         Session {
             hash: key_set.hash,
-            next_refresh,
-            user
+            next_refresh: Session::next_refresh_time(anchor),
+            user,
+            created_at: anchor_at,
+            ip_address: None,
+            user_agent: None,
+            absolute_deadline: Session::deadline(anchor, policy.absolute_lifetime),
+            idle_timeout: policy.idle_timeout,
+            previous_hash: None,
+            anchor,
+            anchor_at,
+            last_active: AtomicI64::new(0)
         }
     }
 
-    /// returns a refresh status
-    pub fn is_stale(&self) -> RefreshStatus {
-        let now = Instant::now();
+    /// applies an idle timeout and absolute lifetime, measured from when the session was created
+    pub fn with_policy(mut self, policy: SessionPolicy) -> Self {
+        self.absolute_deadline = Session::deadline(self.anchor, policy.absolute_lifetime);
+        self.idle_timeout = policy.idle_timeout;
+        self
+    }
+
+    /// attaches the client address and user agent the session was created from
+    pub fn with_client(mut self, ip_address: Option<String>, user_agent: Option<String>) -> Self {
+        self.ip_address = ip_address;
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// restores a persisted session, it is stale right away so the first request reloads the user
+    pub fn rehydrate(record: SessionRecord, user: User, policy: SessionPolicy, now: Instant) -> Self {
+        let anchor = now;
+        let anchor_at = Utc::now();
+
+        // a shortened policy applies to restored sessions, a lengthened one never extends them
+        let policy_cap = record.created_at + chrono::Duration::from_std(policy.absolute_lifetime).unwrap_or(chrono::TimeDelta::MAX);
+        let expires_at = record.expires_at.min(policy_cap);
+        let remaining = (expires_at - anchor_at).to_std().unwrap_or_default();
+        let last_active = (record.last_used_at - anchor_at).num_milliseconds();
+
+        Session {
+            hash: record.hash,
+            next_refresh: anchor,
+            user,
+            created_at: record.created_at,
+            ip_address: record.ip_address,
+            user_agent: record.user_agent,
+            absolute_deadline: Session::deadline(anchor, remaining),
+            idle_timeout: policy.idle_timeout,
+            previous_hash: None,
+            anchor,
+            anchor_at,
+            last_active: AtomicI64::new(last_active)
+        }
+    }
+
+    /// records a use of the session at `now`, extending the idle timer
+    pub fn touch(&self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.anchor).as_millis() as i64;
+        self.last_active.store(elapsed, Ordering::Relaxed);
+    }
 
+    /// last time the session was used
+    pub fn last_used(&self) -> DateTime<Utc> {
+        self.anchor_at + chrono::Duration::milliseconds(self.last_active.load(Ordering::Relaxed))
+    }
+
+    /// wall clock time the absolute lifetime ends, for persistence
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        let lifetime = self.absolute_deadline.saturating_duration_since(self.anchor);
+
+        self.anchor_at + chrono::Duration::from_std(lifetime).unwrap_or(chrono::TimeDelta::MAX)
+    }
+
+    /// constant time token check, the pre-rotation secret is accepted until its grace window closes
+    pub fn verify(&self, key: &[u8;16], secret: &[u8;16], now: Instant) -> VerificationStatus {
+        if KeySet::verify(key, secret, &self.hash) == VerificationStatus::Verified {
+            return VerificationStatus::Verified;
+        }
+
+        match self.previous_hash {
+            Some((hash, deadline)) if now < deadline => KeySet::verify(key, secret, &hash),
+            _ => VerificationStatus::Unverified
+        }
+    }
+
+    /// true while an earlier rotation's grace window is still open
+    pub fn is_rotating(&self, now: Instant) -> bool {
+        self.previous_hash.is_some_and(|(_, deadline)| now < deadline)
+    }
+
+    /// swaps in the hash of a rotated token, the old token keeps working for the grace window
+    pub fn rotate(&mut self, hash: Hash, grace: Duration, now: Instant) {
+        let old_hash = std::mem::replace(&mut self.hash, hash);
+        self.previous_hash = Some((old_hash, Session::deadline(now, grace)));
+    }
+
+    /// replaces the cached user with a freshly loaded copy and pushes the next refresh forward
+    pub fn refresh(&mut self, user: User, now: Instant) {
+        self.user = user;
+        self.next_refresh = Session::next_refresh_time(now);
+    }
+
+    /// returns a refresh status at `now`
+    pub fn is_stale(&self, now: Instant) -> RefreshStatus {
         if now > self.next_refresh {
             RefreshStatus::Refresh
         } else {
@@ -47,19 +188,189 @@ impl Session {
         }
     }
 
-    /// returns expired status
-    pub fn is_expired(&self) -> ExpiredStatus {
-        let now = Instant::now();
-        let time_to_expiration = Duration::from_secs(MAX_SESSION_AGE);
-        let expiration = self.next_refresh
-            .checked_add(time_to_expiration)
-            .or(Some(now))
-            .expect("unreachable after .or()");
+    /// returns expired status at `now`, either the absolute lifetime ended or the session sat idle too long
+    pub fn is_expired(&self, now: Instant) -> ExpiredStatus {
+        let idle_millis = now.saturating_duration_since(self.anchor).as_millis() as i64 - self.last_active.load(Ordering::Relaxed);
 
-        if now > expiration {
+        if now >= self.absolute_deadline || idle_millis > self.idle_timeout.as_millis() as i64 {
             ExpiredStatus::Expired
         } else {
             ExpiredStatus::NotExpired
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enums::UserAccountStatus,
+        types::{users::SystemUser, UserPermissions}
+    };
+
+    use super::*;
+
+    const SECOND:Duration = Duration::from_secs(1);
+
+    fn system_user() -> User {
+        User::System(SystemUser{
+            id: 0,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions: UserPermissions::default()
+        })
+    }
+
+    fn policy(idle_millis: u64, lifetime_millis: u64) -> SessionPolicy {
+        SessionPolicy {
+            idle_timeout: Duration::from_millis(idle_millis),
+            absolute_lifetime: Duration::from_millis(lifetime_millis)
+        }
+    }
+
+    fn record(key_set: &KeySet, created_at: DateTime<Utc>, expires_at: DateTime<Utc>, last_used_at: DateTime<Utc>) -> SessionRecord {
+        SessionRecord {
+            key: key_set.key,
+            hash: key_set.hash,
+            user_id: 0,
+            created_at,
+            expires_at,
+            last_used_at,
+            ip_address: Some(String::from("127.0.0.1")),
+            user_agent: None
+        }
+    }
+
+    /// a rehydrated session keeps its wall clock expiry and reloads the user on first use
+    #[test]
+    fn rehydrate_round_trip() {
+        let key_set = KeySet::new().unwrap();
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::hours(2);
+        let last_used_at = now - chrono::Duration::minutes(5);
+        let record = record(&key_set, now - chrono::Duration::hours(22), expires_at, last_used_at);
+        let start = Instant::now();
+        let session = Session::rehydrate(record.clone(), system_user(), SessionPolicy::default(), start);
+
+        let drift = (session.expires_at() - expires_at).num_seconds().abs();
+        assert!(drift <= 1, "drift: {drift}s");
+        assert_eq!(session.is_stale(start + SECOND), RefreshStatus::Refresh);
+        assert_eq!(session.is_expired(start), ExpiredStatus::NotExpired);
+        assert_eq!(session.created_at, record.created_at);
+        assert!((session.last_used() - last_used_at).num_milliseconds().abs() <= 1);
+        assert_eq!(session.ip_address, record.ip_address);
+    }
+
+    /// restored sessions that sat idle too long, or outlived a shortened lifetime, are expired
+    #[test]
+    fn rehydrate_applies_policy() {
+        let key_set = KeySet::new().unwrap();
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        let start = Instant::now();
+
+        let idle = Session::rehydrate(record(&key_set, now - hour * 3, now + hour, now - hour * 2), system_user(), policy(3_600_000, 86_400_000), start);
+        assert_eq!(idle.is_expired(start), ExpiredStatus::Expired);
+
+        let shortened = Session::rehydrate(record(&key_set, now - hour * 3, now + hour, now), system_user(), policy(3_600_000, 7_200_000), start);
+        assert_eq!(shortened.is_expired(start), ExpiredStatus::Expired);
+
+        let live = Session::rehydrate(record(&key_set, now - hour * 3, now + hour, now), system_user(), policy(3_600_000, 86_400_000), start);
+        assert_eq!(live.is_expired(start), ExpiredStatus::NotExpired);
+        assert!((live.expires_at() - (now + hour)).num_seconds().abs() <= 1);
+    }
+
+    /// touching a session moves its last use forward
+    #[test]
+    fn touch_updates_last_used() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let session = Session::new(&key_set, system_user(), start);
+        let first_use = session.last_used();
+
+        session.touch(start + SECOND * 5);
+
+        assert_eq!(session.last_used() - first_use, chrono::Duration::seconds(5));
+        assert_eq!(session.clone().last_used(), session.last_used());
+    }
+
+    /// sessions that are not used within the idle timeout expire
+    #[test]
+    fn idle_timeout_expires() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let session = Session::new(&key_set, system_user(), start).with_policy(policy(20_000, 60_000));
+        assert_eq!(session.is_expired(start + SECOND * 20), ExpiredStatus::NotExpired);
+        assert_eq!(session.is_expired(start + SECOND * 21), ExpiredStatus::Expired);
+    }
+
+    /// activity restarts the idle timer
+    #[test]
+    fn activity_extends_idle_timeout() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let session = Session::new(&key_set, system_user(), start).with_policy(policy(20_000, 60_000));
+
+        for step in 1..=3 {
+            session.touch(start + SECOND * 10 * step);
+        }
+
+        // 40 seconds since creation but only 10 since the last use
+        assert_eq!(session.is_expired(start + SECOND * 40), ExpiredStatus::NotExpired);
+        assert_eq!(session.is_expired(start + SECOND * 51), ExpiredStatus::Expired);
+    }
+
+    /// activity never extends the absolute lifetime
+    #[test]
+    fn activity_never_extends_absolute_lifetime() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let session = Session::new(&key_set, system_user(), start).with_policy(policy(60_000, 150_000));
+        let expires_at = session.expires_at();
+
+        for step in 1..=4 {
+            session.touch(start + SECOND * 50 * step);
+        }
+
+        assert_eq!(session.expires_at(), expires_at);
+        assert_eq!(session.is_expired(start + SECOND * 149), ExpiredStatus::NotExpired);
+        assert_eq!(session.is_expired(start + SECOND * 200), ExpiredStatus::Expired);
+    }
+
+    /// refreshing the user pushes the next refresh forward but leaves expiry alone
+    #[test]
+    fn refresh_keeps_expiry() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let later = start + Duration::from_secs(BASE_REFRESH_TIME * 2);
+        let mut session = Session::new(&key_set, system_user(), start).with_policy(policy(60_000, 120_000));
+        let expires_at = session.expires_at();
+        assert_eq!(session.is_stale(later), RefreshStatus::Refresh);
+
+        session.refresh(system_user(), later);
+
+        assert_eq!(session.is_stale(later), RefreshStatus::None);
+        assert_eq!(session.expires_at(), expires_at);
+    }
+
+    /// the old token keeps working only for the grace window after a rotation
+    #[test]
+    fn rotation_grace_window() {
+        let key_set = KeySet::new().unwrap();
+        let start = Instant::now();
+        let closed = start + SECOND * 30;
+        let mut session = Session::new(&key_set, system_user(), start);
+        assert!(!session.is_rotating(start));
+
+        let rotated = KeySet::rotate(&key_set.key).unwrap();
+        session.rotate(rotated.hash, SECOND * 30, start);
+
+        assert!(session.is_rotating(start));
+        assert_eq!(session.verify(&rotated.key, &rotated.secret, start), VerificationStatus::Verified);
+        assert_eq!(session.verify(&key_set.key, &key_set.secret, start), VerificationStatus::Verified);
+
+        assert!(!session.is_rotating(closed));
+        assert_eq!(session.verify(&rotated.key, &rotated.secret, closed), VerificationStatus::Verified);
+        assert_eq!(session.verify(&key_set.key, &key_set.secret, closed), VerificationStatus::Unverified);
+    }
+}
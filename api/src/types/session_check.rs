@@ -0,0 +1,8 @@
+use crate::enums::Permission;
+
+/// outcome of an authorization, carries the replacement token when the session was rotated
+#[derive(Debug)]
+pub struct SessionCheck {
+    pub permission: Permission,
+    pub rotated_token: Option<String>
+}
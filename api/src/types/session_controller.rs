@@ -1,34 +1,37 @@
-use std::{collections::HashMap, hash::{DefaultHasher,Hash,Hasher}, sync::RwLock, time::{Duration,Instant}};
+use chrono::{DateTime,Utc};
+use rate_limit::{traits::Clock, types::SystemClock};
+use std::{cmp::Reverse, collections::{HashMap, HashSet}, hash::{DefaultHasher,Hash,Hasher}, sync::{Arc,RwLock}, time::{Duration,Instant}};
 
 use crate::{
-    enums::{Error, ExpiredStatus, Permission, RefreshStatus, User, VerificationStatus},
+    enums::{Error, ExpiredStatus, Permission, RefreshStatus, RowsUpdated, SessionStoreStatus, SystemFlag, User, UserAccountStatus, VerificationStatus},
     traits::{FromBase64, HasPermission, ToBase64, ToKeySet},
-    types::{KeySet, PermissionCheck, Session, UserPermissions}
+    types::{DatabaseConnection, KeySet, PermissionCheck, Session, SessionCheck, SessionPolicies, SessionPolicy, SessionSummary, UserPermissions}
 };
 
 type Result<T> = std::result::Result<T,Error>;
 
 const MAX_GARBAGE_COLLECTION:u64 = 60;     // 10 seconds
 const COLLECTION_TTL:u64 = 10;             // 10 miliseconds
+const ROTATION_GRACE:u64 = 30;             // 30 seconds
 
 #[derive(Debug,Default)]
 struct GarbageCollector;
 
 impl GarbageCollector {
-    /// accepts a locked shard and removes expired sessions
-    pub fn sweep(&mut self, list: &RwLock<HashMap<[u8;16],Session>>) -> Result<()> {
+    /// accepts a locked shard, removes sessions expired on the clock and returns their keys and user ids.
+    /// the work budget is real time while expiry follows the clock
+    pub fn sweep(&mut self, list: &RwLock<HashMap<[u8;16],Session>>, clock: &dyn Clock) -> Result<Vec<([u8;16],i64)>> {
         let time = Duration::from_millis(COLLECTION_TTL);
         let stop_time = Instant::now().checked_add(time).ok_or(Error::DevError("couldn't create a time window to work in garbage collector".to_string()))?;
+        let expires_at = clock.now();
         let mut now = Instant::now();
         let mut sessions_to_remove: Vec<[u8;16]> = Vec::with_capacity(2048);
 
         // begin locked read scope
         {
             let locked_list = list.read().map_err(|_e| Error::PoisonedSessionList)?;
-            let mut list = locked_list.iter();
-
-            while let Some((key,session)) = list.next() {
-                if session.is_expired() == ExpiredStatus::Expired {
+            for (key,session) in locked_list.iter() {
+                if session.is_expired(expires_at) == ExpiredStatus::Expired {
                     sessions_to_remove.push(*key);
                 }
 
@@ -42,17 +45,21 @@ impl GarbageCollector {
         }
         // end locked read scope
 
+        let mut removed = Vec::with_capacity(sessions_to_remove.len());
+
         // begin locked write scope
         if !sessions_to_remove.is_empty() {
             let mut locked_list = list.write().map_err(|_e| Error::PoisonedSessionList)?;
 
             for k in sessions_to_remove {
-                locked_list.remove(&k);
+                if let Some(session) = locked_list.remove(&k) {
+                    removed.push((k, session.user.id()));
+                }
             }
         }
         // end locked write scope
 
-        Ok(())
+        Ok(removed)
     }
 
 }
@@ -60,7 +67,12 @@ impl GarbageCollector {
 #[derive(Debug)]
 pub struct SessionController {
     list: Vec<RwLock<HashMap<[u8;16],Session>>>,
-    garbage_collector: RwLock<GarbageCollector>
+    garbage_collector: RwLock<GarbageCollector>,
+    store: SessionStoreStatus,
+    users: RwLock<HashMap<i64,HashSet<[u8;16]>>>,   // user id -> session keys
+    policies: SessionPolicies,
+    rotation: SystemFlag,                           // replace token secrets when sessions refresh
+    clock: Arc<dyn Clock>                           // time source for expiry, refresh and rotation windows
 }
 
 impl SessionController {
@@ -73,6 +85,10 @@ impl SessionController {
         loop {
             interval.tick().await;
             let _ = self.start_collector();
+
+            if let SessionStoreStatus::Enabled(store) = &self.store {
+                let _ = store.delete_expired().await;
+            }
         }
     }
 
@@ -82,7 +98,9 @@ impl SessionController {
         let mut locked_collector = self.garbage_collector.write().map_err(|_e| Error::PoisonedSessionList)?;
 
         for shard in 0..self.list.len() {
-            locked_collector.sweep(&self.list[shard])?;
+            for (key,user_id) in locked_collector.sweep(&self.list[shard], self.clock.as_ref())? {
+                self.unindex(user_id, &key)?;
+            }
         }
         // end write lock
 
@@ -90,22 +108,15 @@ impl SessionController {
     }
 
     /// deletes session from controller
-    pub fn delete(&self, token_b64: &str) -> Result<()> {
+    pub async fn delete(&self, token_b64: &str) -> Result<()> {
         let token = token_b64.vec_from_base64_url()?;
         let key = token.to_key()?;
 
-        // derive shard id
-        let idx = self.idx(&key)?;
+        let _session = self.remove(&key)?;
 
-        // begin locked scope
-        let () = {
-            let mut locked_list = self.list[idx]
-                .write()
-                .map_err(|_e| Error::PoisonedSessionList)?;
-
-            locked_list.remove(&key);
-        };
-        // end locked scope
+        if let SessionStoreStatus::Enabled(store) = &self.store {
+            store.delete(&key).await?;
+        }
         
         Ok(())
     }
@@ -134,10 +145,97 @@ impl SessionController {
 
         Self {
             garbage_collector: RwLock::new(garbage_collector),
-            list
+            list,
+            store: SessionStoreStatus::Disabled,
+            users: RwLock::new(HashMap::with_capacity(shard_capacity)),
+            policies: SessionPolicies::default(),
+            rotation: SystemFlag::Disabled,
+            clock: Arc::new(SystemClock)
         }
     }
 
+    /// accepts the idle timeout and absolute lifetime to apply per user type
+    pub fn with_policies(mut self, policies: SessionPolicies) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// rotates the token secret whenever a session refreshes
+    pub fn with_rotation(mut self, rotation: SystemFlag) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// set the time source, the system clock by default
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// current time on the controller's clock, new sessions start here
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// session policy for a user
+    pub fn policy(&self, user: &User) -> SessionPolicy {
+        self.policies.for_user_type(&user.user_type())
+    }
+
+    /// accepts a session store and writes sessions through to it
+    pub fn with_store_status(mut self, status: SessionStoreStatus) -> Self {
+        self.store = status;
+        self
+    }
+
+    /// reloads persisted sessions into the shards and returns the number restored, disabled or banned accounts are dropped
+    pub async fn rehydrate(&self, database: &DatabaseConnection) -> Result<usize> {
+        let store = match &self.store {
+            SessionStoreStatus::Enabled(store) => store,
+            SessionStoreStatus::Disabled => return Ok(0)
+        };
+
+        let mut restored:usize = 0;
+
+        for record in store.all().await? {
+            let user = match User::by_id(record.user_id, database).await? {
+                Some(user) if *user.status() == UserAccountStatus::Enabled => user,
+                _ => {
+                    store.delete(&record.key).await?;
+                    continue;
+                }
+            };
+
+            let key = record.key;
+            let user_id = user.id();
+            let policy = self.policy(&user);
+            let session = Session::rehydrate(record, user, policy, self.clock.now());
+
+            // idle or past a shortened lifetime while the server was down
+            if session.is_expired(self.clock.now()) == ExpiredStatus::Expired {
+                store.delete(&key).await?;
+                continue;
+            }
+
+            let idx = self.idx(&key)?;
+
+            // begin locked write scope
+            {
+                let mut locked_list = self.list[idx]
+                    .write()
+                    .map_err(|_e| Error::PoisonedSessionList)?;
+
+                let _ = locked_list.insert(key, session);
+            }
+            // end locked write scope
+
+            self.index(user_id, key)?;
+            restored += 1;
+        }
+
+        Ok(restored)
+    }
+
     /// produces the shard id 
     fn idx(&self, key: &[u8]) -> Result<usize> {
         let mut hasher = DefaultHasher::new();
@@ -147,11 +245,164 @@ impl SessionController {
         Ok((hash as usize) % self.list.len())
     }
 
+    /// adds a session key to the user index
+    fn index(&self, user_id: i64, key: [u8;16]) -> Result<()> {
+        let mut locked_users = self.users
+            .write()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        locked_users.entry(user_id).or_default().insert(key);
+
+        Ok(())
+    }
+
+    /// drops a session key from the user index
+    fn unindex(&self, user_id: i64, key: &[u8;16]) -> Result<()> {
+        let mut locked_users = self.users
+            .write()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        if let Some(keys) = locked_users.get_mut(&user_id) {
+            keys.remove(key);
+
+            if keys.is_empty() {
+                locked_users.remove(&user_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// removes a session from its shard and the user index
+    fn remove(&self, key: &[u8;16]) -> Result<Option<Session>> {
+        let idx = self.idx(key)?;
+
+        // begin locked write scope
+        let session_opt = {
+            let mut locked_list = self.list[idx]
+                .write()
+                .map_err(|_e| Error::PoisonedSessionList)?;
+
+            locked_list.remove(key)
+        };
+        // end locked write scope
+
+        if let Some(session) = &session_opt {
+            self.unindex(session.user.id(), key)?;
+        }
+
+        Ok(session_opt)
+    }
+
+    /// public id of the session behind a token, safe to show since it cannot authenticate without the secret
+    pub fn session_id(token_b64: &str) -> Result<String> {
+        let token = token_b64.vec_from_base64_url()?;
+        let key = token.to_key()?;
+
+        Ok(key.to_base64_url())
+    }
+
+    /// summaries of every live session a user holds, newest first
+    pub fn sessions_by_user(&self, user_id: i64) -> Result<Vec<SessionSummary>> {
+        let keys:Vec<[u8;16]> = {
+            let locked_users = self.users
+                .read()
+                .map_err(|_e| Error::PoisonedSessionList)?;
+
+            locked_users
+                .get(&user_id)
+                .map(|keys| keys.iter().copied().collect())
+                .unwrap_or_default()
+        };
+
+        let mut summaries = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            let idx = self.idx(&key)?;
+            let locked_list = self.list[idx]
+                .read()
+                .map_err(|_e| Error::PoisonedSessionList)?;
+
+            if let Some(session) = locked_list.get(&key)
+                && session.is_expired(self.clock.now()) == ExpiredStatus::NotExpired {
+                summaries.push(SessionSummary {
+                    id: key.to_base64_url(),
+                    user_id,
+                    created_at: session.created_at,
+                    last_used: session.last_used(),
+                    ip_address: session.ip_address.clone(),
+                    user_agent: session.user_agent.clone()
+                });
+            }
+        }
+
+        summaries.sort_by_key(|summary| Reverse(summary.created_at));
+
+        Ok(summaries)
+    }
+
+    /// revokes one of a user's sessions by its public id
+    pub async fn revoke(&self, user_id: i64, session_id: &str) -> Result<RowsUpdated> {
+        let key:[u8;16] = session_id
+            .vec_from_base64_url()?
+            .try_into()
+            .map_err(|_e| Error::SessionIdInvalid)?;
+
+        // sessions belonging to another user are treated as missing
+        let owned = self.users
+            .read()
+            .map_err(|_e| Error::PoisonedSessionList)?
+            .get(&user_id)
+            .is_some_and(|keys| keys.contains(&key));
+
+        if !owned || self.remove(&key)?.is_none() {
+            return Ok(RowsUpdated::NoRowsUpdated);
+        }
+
+        if let SessionStoreStatus::Enabled(store) = &self.store {
+            store.delete(&key).await?;
+        }
+
+        Ok(RowsUpdated::RowsUpdated(1))
+    }
+
+    /// revokes every session a user holds
+    pub async fn revoke_all(&self, user_id: i64) -> Result<RowsUpdated> {
+        let keys = self.users
+            .write()
+            .map_err(|_e| Error::PoisonedSessionList)?
+            .remove(&user_id)
+            .unwrap_or_default();
+
+        let mut revoked:u64 = 0;
+
+        for key in keys {
+            if self.remove(&key)?.is_some() {
+                revoked += 1;
+            }
+        }
+
+        if let SessionStoreStatus::Enabled(store) = &self.store {
+            store.delete_by_user(user_id).await?;
+        }
+
+        match revoked {
+            0 => Ok(RowsUpdated::NoRowsUpdated),
+            _ => Ok(RowsUpdated::RowsUpdated(revoked))
+        }
+    }
+
     /// inserts a new session into the controller and runs the trash collector
-    pub fn insert(&self, session: Session, key_set: &KeySet) -> Result<String> {
+    pub async fn insert(&self, session: Session, key_set: &KeySet) -> Result<String> {
         let key = &key_set.key;
-        let secret = &key_set.secret;
         let idx = self.idx(key)?;
+
+        let user_id = session.user.id();
+
+        // persist first so a failed write never leaves an in-memory only session
+        if let SessionStoreStatus::Enabled(store) = &self.store {
+            store.insert(key, &session).await?;
+        }
     
         // begin locked write scope
         {
@@ -163,13 +414,43 @@ impl SessionController {
         }
         // end locked write scope
 
-        let mut token_buf:[u8;32] = [0;32];
-        token_buf[..16].copy_from_slice(key);
-        token_buf[16..].copy_from_slice(secret);
-        
-        let token = token_buf.to_base64_url();
+        self.index(user_id, *key)?;
 
-        Ok(token)
+        Ok(key_set.token())
+    }
+
+    /// returns a copy of the user attached to a verified session
+    pub fn user(&self, token_b64: &str) -> Result<Option<User>> {
+        // decode from base64 to Vec<u8> and extract segments
+        let token = token_b64.vec_from_base64_url()?;
+        let key = token.to_key()?;
+        let secret = token.to_secret()?;
+
+        // derive shard id
+        let idx = self.idx(&key)?;
+
+        // begin read lock scope
+        let locked_list = self.list[idx]
+            .read()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        let session = match locked_list.get(&key) {
+            Some(s) => s,
+            None => return Ok(None)
+        };
+
+        let now = self.clock.now();
+
+        if session.is_expired(now) == ExpiredStatus::Expired {
+            return Ok(None);
+        }
+
+        // constant time hash check
+        match session.verify(&key, &secret, now) {
+            VerificationStatus::Verified => Ok(Some(session.user.clone())),
+            VerificationStatus::Unverified => Ok(None)
+        }
+        // end read lock scope
     }
 
     /// returns a reference to the list of sessions
@@ -177,8 +458,23 @@ impl SessionController {
         &self.list
     }
 
-    /// verify user has software access rights / permissions
-    pub fn permission_check(&self, token_b64: &str, required_rights: &UserPermissions) -> Result<Permission> {
+    /// verify user has software access rights / permissions, reloading stale sessions from the database.
+    /// never rotates, the caller has no way to hand a replacement token back to the client
+    pub async fn permission_check(&self, token_b64: &str, required_rights: &UserPermissions, database: &DatabaseConnection) -> Result<Permission> {
+        let session_check = self.check(token_b64, required_rights, database, SystemFlag::Disabled).await?;
+
+        Ok(session_check.permission)
+    }
+
+    /// permission check that also hands back a replacement token when a refresh rotated the session
+    pub async fn authorize(&self, token_b64: &str, required_rights: &UserPermissions, database: &DatabaseConnection) -> Result<SessionCheck> {
+        self.check(token_b64, required_rights, database, self.rotation).await
+    }
+
+    /// shared by permission_check and authorize, a refresh only rotates the session when rotation is enabled
+    async fn check(&self, token_b64: &str, required_rights: &UserPermissions, database: &DatabaseConnection, rotation: SystemFlag) -> Result<SessionCheck> {
+        let denied = SessionCheck { permission: Permission::None, rotated_token: None };
+
         // decode from base64 to Vec<u8> and extract segments
         let token = token_b64.vec_from_base64_url()?;
         let key = token.to_key()?;
@@ -197,47 +493,128 @@ impl SessionController {
             // and retrieve sesssion
             let session = match locked_list.get(&key) {
                 Some(s) => s,
-                None => return Ok(Permission::None)
+                None => return Ok(denied)
             };
 
+            let now = self.clock.now();
+
             // check if it's expired and deny if it is
-            if session.is_expired() == ExpiredStatus::Expired {
-                return Ok(Permission::None);
+            if session.is_expired(now) == ExpiredStatus::Expired {
+                return Ok(denied);
             }
 
-            // constant time hash check
-            let verify_status = KeySet::verify(&key,&secret,&session.hash);
-
-            // permission check
-            let permission = {
-                 if verify_status == VerificationStatus::Verified {
-                    // run permission checks
-                    match &session.user {
-                        User::Business(u) => u.permissions.has_permission(required_rights),
-                        User::Community(c) => c.permissions.has_permission(required_rights),
-                        User::System(s) => s.permissions.has_permission(required_rights)
-                    }
-                } else {
-                    Permission::None
-                }
-            };
+            // constant time hash check, unverified tokens never reach the database
+            if session.verify(&key,&secret,now) == VerificationStatus::Unverified {
+                return Ok(denied);
+            }
+
+            session.touch(now);
 
             // package a response
             PermissionCheck {
-                permission,
-                refresh_status: session.is_stale()
+                permission: session.user.permissions().has_permission(required_rights),
+                refresh_status: session.is_stale(now),
+                user_id: session.user.id()
             }
         };
         // end read lock scope
 
         match permission_check.refresh_status {
-            RefreshStatus::None => Ok(permission_check.permission),
+            RefreshStatus::None => Ok(SessionCheck { permission: permission_check.permission, rotated_token: None }),
             RefreshStatus::Refresh => {
-                // do a database check here
-                Ok(permission_check.permission)
+                // no lock is held across the database call
+                let user_opt = User::by_id(permission_check.user_id, database).await?;
+                let revoked = !user_opt
+                    .as_ref()
+                    .is_some_and(|user| *user.status() == UserAccountStatus::Enabled);
+
+                let permission = self.apply_refresh(&key, user_opt, required_rights)?;
+
+                let rotated = match (revoked, rotation) {
+                    (false, SystemFlag::Enabled) => self.rotate(&key)?,
+                    _ => None
+                };
+
+                if let SessionStoreStatus::Enabled(store) = &self.store {
+                    match (revoked, self.last_used(&key)?) {
+                        (true, _) => store.delete(&key).await?,
+                        (false, Some(last_used_at)) => store.update_last_used(&key, last_used_at).await?,
+                        (false, None) => {}
+                    }
+
+                    if let Some(key_set) = &rotated {
+                        store.update_hash(&key, &key_set.hash).await?;
+                    }
+                }
+
+                Ok(SessionCheck {
+                    permission,
+                    rotated_token: rotated.map(|key_set| key_set.token())
+                })
             }
         }
     }
+
+    /// replaces the secret of a live session, the old token stays valid for the grace window.
+    /// returns None when the session is gone or a concurrent request already rotated it
+    fn rotate(&self, key: &[u8;16]) -> Result<Option<KeySet>> {
+        let key_set = KeySet::rotate(key)?;
+        let idx = self.idx(key)?;
+        let now = self.clock.now();
+
+        // begin locked write scope
+        let mut locked_list = self.list[idx]
+            .write()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        match locked_list.get_mut(key) {
+            Some(session) if !session.is_rotating(now) => {
+                session.rotate(key_set.hash, Duration::from_secs(ROTATION_GRACE), now);
+                Ok(Some(key_set))
+            },
+            _ => Ok(None)
+        }
+        // end locked write scope
+    }
+
+    /// last use of a live session
+    fn last_used(&self, key: &[u8;16]) -> Result<Option<DateTime<Utc>>> {
+        let idx = self.idx(key)?;
+        let locked_list = self.list[idx]
+            .read()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        Ok(locked_list.get(key).map(Session::last_used))
+    }
+
+    /// writes a freshly loaded user back into its session, revoking the session if the account is gone or no longer enabled
+    fn apply_refresh(&self, key: &[u8;16], user_opt: Option<User>, required_rights: &UserPermissions) -> Result<Permission> {
+        let user = match user_opt {
+            Some(user) if *user.status() == UserAccountStatus::Enabled => user,
+            _ => {
+                let _session = self.remove(key)?;
+                return Ok(Permission::None);
+            }
+        };
+
+        let permission = user.permissions().has_permission(required_rights);
+        let idx = self.idx(key)?;
+
+        // begin locked write scope
+        let mut locked_list = self.list[idx]
+            .write()
+            .map_err(|_e| Error::PoisonedSessionList)?;
+
+        // session may have been deleted while the database was queried
+        match locked_list.get_mut(key) {
+            Some(session) => {
+                session.refresh(user, self.clock.now());
+                Ok(permission)
+            },
+            None => Ok(Permission::None)
+        }
+        // end locked write scope
+    }
 }
 
 impl Default for SessionController {
@@ -251,13 +628,36 @@ impl Default for SessionController {
 
 #[cfg(test)]
 mod tests {
+    use rate_limit::types::ManualClock;
+    use sqlx::mysql::MySqlPoolOptions;
+
     use crate::types::users::SystemUser;
 
     use super::*;
 
+    /// database handle that never connects, fresh sessions don't need one
+    fn lazy_database() -> DatabaseConnection {
+        let pool = MySqlPoolOptions::new()
+            .connect_lazy("mysql://localhost/idropr")
+            .unwrap();
+
+        DatabaseConnection { pool }
+    }
+
+    /// system user with the given status and permissions
+    fn system_user(status: UserAccountStatus, permissions: UserPermissions) -> User {
+        User::System(SystemUser{
+            id: 0,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status,
+            permissions
+        })
+    }
+
     /// loads 1_000_000 random session ids into memory and checks for overwrites, which would indicate collisions
-    #[test]
-    fn collision_test() {
+    #[actix_rt::test]
+    async fn collision_test() {
         let sessions_to_create = 1_000_000;
         let controller = SessionController::new(sessions_to_create, 4);
 
@@ -270,8 +670,8 @@ mod tests {
                 status: crate::enums::UserAccountStatus::Enabled,
                 permissions: UserPermissions::default()
             });
-            let session = Session::new(&key_set,user);
-            let _token = controller.insert(session, &key_set).unwrap();
+            let session = Session::new(&key_set, user, controller.now());
+            let _token = controller.insert(session, &key_set).await.unwrap();
         }
 
         let list = controller.list();
@@ -294,8 +694,9 @@ mod tests {
 
     /// loads 1_000_000 random sessions into memory, encoding and decoding the hash strings and 
     /// running a permission check to make sure each hash string is still valid
-    #[test]
-    fn hash_decode_check() {
+    #[actix_rt::test]
+    async fn hash_decode_check() {
+        let database = lazy_database();
         let sessions_to_create = 1_000_000;
         let controller = SessionController::new(sessions_to_create, 4);
         let permissions = UserPermissions::default().with_sessions_full();
@@ -305,30 +706,30 @@ mod tests {
             username: String::from("username"),
             hash: String::from("hash"),
             status: crate::enums::UserAccountStatus::Enabled,
-            permissions: permissions.clone()
+            permissions
         });
 
         for _ in 0..sessions_to_create {
             let key_set = KeySet::new().unwrap();
             let user = user.clone();
-            let session = Session::new(&key_set,user);
+            let session = Session::new(&key_set, user, controller.now());
 
             // insert and encode with base64
-            let token = controller.insert(session, &key_set).unwrap();
+            let token = controller.insert(session, &key_set).await.unwrap();
 
             // permission check will decode and validate the token and allow access 
-            let check = controller.permission_check(&token, &permissions).unwrap();
+            let check = controller.permission_check(&token, &permissions, &database).await.unwrap();
             assert_eq!(check,Permission::Granted);
 
             // permission check will decode and validate the token and deny access
-            let check = controller.permission_check(&token, &denied_permissions).unwrap();
+            let check = controller.permission_check(&token, &denied_permissions, &database).await.unwrap();
             assert_eq!(check,Permission::None);
         }
     }
 
     /// verifies sessions are removed
-    #[test]
-    fn session_delete() {
+    #[actix_rt::test]
+    async fn session_delete() {
         let sessions_to_create = 1_000_000;
         let controller = SessionController::new(sessions_to_create, 4);
         let key_set = KeySet::new().unwrap();
@@ -339,15 +740,20 @@ mod tests {
             status: crate::enums::UserAccountStatus::Enabled,
             permissions: UserPermissions::default()
         });
-        let session = Session::new(&key_set,user);
-        let _token = controller.insert(session, &key_set).unwrap();
+        let session = Session::new(&key_set, user, controller.now());
+        let token = controller.insert(session, &key_set).await.unwrap();
+        assert!(controller.user(&token).unwrap().is_some());
+
+        controller.delete(&token).await.unwrap();
+        assert!(controller.user(&token).unwrap().is_none());
     }
 
     /// load tests the garbage collector
-    #[test]
-    fn garbage_collector() {
+    #[actix_rt::test]
+    async fn garbage_collector() {
         let sessions_to_create = 1_000_000;
-        let controller = SessionController::new(sessions_to_create, 4);
+        let clock = Arc::new(ManualClock::new());
+        let controller = SessionController::new(sessions_to_create, 4).with_clock(clock.clone());
 
         for _ in 0..sessions_to_create {
             let key_set = KeySet::new().unwrap();
@@ -359,13 +765,14 @@ mod tests {
                 permissions: UserPermissions::default()
             });
 
-            let mut session = Session::new(&key_set,user);
-            session.next_refresh = Instant::now().checked_sub(Duration::from_secs(186400)).unwrap();
-
-            let _token = controller.insert(session, &key_set).unwrap();
+            let session = Session::new(&key_set, user, controller.now());
+            let _token = controller.insert(session, &key_set).await.unwrap();
         }
 
-        let _a = match controller.start_collector() {
+        // every session outlives its lifetime
+        clock.advance(SessionPolicy::default().absolute_lifetime + Duration::from_secs(1));
+
+        match controller.start_collector() {
             Ok(_) => println!("ok"),
             Err(e) => println!("{:?}",e)
         };
@@ -379,4 +786,140 @@ mod tests {
 
         assert!(count <= (sessions_to_create - (4*2048)), "Total sessions: {}", count);
     }
+
+    /// a refreshed user replaces the cached one and pushes the next refresh forward
+    #[actix_rt::test]
+    async fn refresh_updates_session() {
+        let clock = Arc::new(ManualClock::new());
+        let controller = SessionController::new(16, 1).with_clock(clock.clone());
+        let key_set = KeySet::new().unwrap();
+        let session = Session::new(&key_set, system_user(UserAccountStatus::Enabled, UserPermissions::default().with_buckets_full()), controller.now());
+        let _token = controller.insert(session, &key_set).await.unwrap();
+
+        // well past the jittered refresh deadline
+        clock.advance(Duration::from_secs(60 * 20));
+
+        let required = UserPermissions::default().with_sessions_full();
+        let reloaded = system_user(UserAccountStatus::Enabled, required);
+        let permission = controller.apply_refresh(&key_set.key, Some(reloaded), &required).unwrap();
+        assert_eq!(permission, Permission::Granted);
+
+        let idx = controller.idx(&key_set.key).unwrap();
+        let locked_list = controller.list[idx].read().unwrap();
+        let session = locked_list.get(&key_set.key).unwrap();
+        assert_eq!(session.is_stale(controller.now()), RefreshStatus::None);
+        assert_eq!(*session.user.permissions(), required);
+    }
+
+    /// sessions are revoked when the account is no longer enabled or no longer exists
+    #[actix_rt::test]
+    async fn refresh_revokes_session() {
+        let controller = SessionController::new(16, 1);
+        let required = UserPermissions::default().with_sessions_full();
+        let reloads = [
+            Some(system_user(UserAccountStatus::Banned, required)),
+            Some(system_user(UserAccountStatus::Disabled, required)),
+            Some(system_user(UserAccountStatus::Suspended, required)),
+            None
+        ];
+
+        for reloaded in reloads {
+            let key_set = KeySet::new().unwrap();
+            let session = Session::new(&key_set, system_user(UserAccountStatus::Enabled, required), controller.now());
+            let _token = controller.insert(session, &key_set).await.unwrap();
+
+            let permission = controller.apply_refresh(&key_set.key, reloaded, &required).unwrap();
+            assert_eq!(permission, Permission::None);
+
+            let idx = controller.idx(&key_set.key).unwrap();
+            assert!(!controller.list[idx].read().unwrap().contains_key(&key_set.key));
+        }
+    }
+
+    /// a rotation issues a working token, keeps the old one alive through the grace window and only happens once per refresh
+    #[actix_rt::test]
+    async fn rotation_issues_new_token() {
+        let database = lazy_database();
+        let clock = Arc::new(ManualClock::new());
+        let controller = SessionController::new(16, 1)
+            .with_rotation(SystemFlag::Enabled)
+            .with_clock(clock.clone());
+        let required = UserPermissions::default().with_sessions_full();
+        let key_set = KeySet::new().unwrap();
+        let session = Session::new(&key_set, system_user(UserAccountStatus::Enabled, required), controller.now());
+        let token = controller.insert(session, &key_set).await.unwrap();
+
+        let rotated = controller.rotate(&key_set.key).unwrap().unwrap();
+        let rotated_token = rotated.token();
+        assert_ne!(rotated_token, token);
+
+        // a concurrent refresh inside the grace window must not rotate again
+        assert!(controller.rotate(&key_set.key).unwrap().is_none());
+
+        let permission = controller.permission_check(&rotated_token, &required, &database).await.unwrap();
+        assert_eq!(permission, Permission::Granted);
+        let permission = controller.permission_check(&token, &required, &database).await.unwrap();
+        assert_eq!(permission, Permission::Granted);
+
+        // close the grace window
+        clock.advance(Duration::from_secs(ROTATION_GRACE));
+
+        let permission = controller.permission_check(&token, &required, &database).await.unwrap();
+        assert_eq!(permission, Permission::None);
+        assert!(controller.user(&rotated_token).unwrap().is_some());
+        assert!(controller.user(&token).unwrap().is_none());
+    }
+
+    /// the user index tracks inserts, single revocations, bulk revocations and garbage collection
+    #[actix_rt::test]
+    async fn user_index_revocation() {
+        let clock = Arc::new(ManualClock::new());
+        let controller = SessionController::new(16, 2).with_clock(clock.clone());
+        let permissions = UserPermissions::default();
+        let user_with_id = |id: i64| User::System(SystemUser{
+            id,
+            username: String::from("username"),
+            hash: String::from("hash"),
+            status: UserAccountStatus::Enabled,
+            permissions
+        });
+
+        let mut tokens = Vec::new();
+        for id in [1,1,1,2] {
+            let key_set = KeySet::new().unwrap();
+            let session = Session::new(&key_set, user_with_id(id), controller.now())
+                .with_client(Some(String::from("127.0.0.1")), Some(String::from("agent")));
+            tokens.push(controller.insert(session, &key_set).await.unwrap());
+        }
+
+        let summaries = controller.sessions_by_user(1).unwrap();
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].ip_address.as_deref(), Some("127.0.0.1"));
+        assert_eq!(controller.sessions_by_user(2).unwrap().len(), 1);
+        assert!(controller.sessions_by_user(3).unwrap().is_empty());
+
+        // another user's session id cannot be revoked
+        let session_id = SessionController::session_id(&tokens[0]).unwrap();
+        assert!(matches!(controller.revoke(2, &session_id).await.unwrap(), RowsUpdated::NoRowsUpdated));
+        assert!(matches!(controller.revoke(1, &session_id).await.unwrap(), RowsUpdated::RowsUpdated(1)));
+        assert!(matches!(controller.revoke(1, &session_id).await.unwrap(), RowsUpdated::NoRowsUpdated));
+        assert!(controller.user(&tokens[0]).unwrap().is_none());
+        assert_eq!(controller.sessions_by_user(1).unwrap().len(), 2);
+
+        // log out everywhere leaves other users alone
+        assert!(matches!(controller.revoke_all(1).await.unwrap(), RowsUpdated::RowsUpdated(2)));
+        assert!(controller.sessions_by_user(1).unwrap().is_empty());
+        assert!(controller.user(&tokens[3]).unwrap().is_some());
+
+        // expired sessions leave the index when collected
+        let key_set = KeySet::new().unwrap();
+        let short_lived = SessionPolicy { idle_timeout: Duration::from_secs(60), absolute_lifetime: Duration::from_secs(60) };
+        let session = Session::new(&key_set, user_with_id(2), controller.now()).with_policy(short_lived);
+        let _token = controller.insert(session, &key_set).await.unwrap();
+        clock.advance(Duration::from_secs(61));
+        controller.start_collector().unwrap();
+
+        let locked_users = controller.users.read().unwrap();
+        assert_eq!(locked_users.get(&2).map(HashSet::len), Some(1));
+    }
 }
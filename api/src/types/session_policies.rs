@@ -0,0 +1,50 @@
+use crate::{
+    enums::UserType,
+    types::SessionPolicy
+};
+
+/// one session policy per user type
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct SessionPolicies {
+    pub business: SessionPolicy,
+    pub community: SessionPolicy,
+    pub system: SessionPolicy
+}
+
+impl SessionPolicies {
+    /// policy applied to sessions of the given user type
+    pub fn for_user_type(&self, user_type: &UserType) -> SessionPolicy {
+        match user_type {
+            UserType::Business => self.business,
+            UserType::Community => self.community,
+            UserType::System => self.system
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// each user type resolves to its own policy
+    #[test]
+    fn policy_by_user_type() {
+        let policy = |secs: u64| SessionPolicy {
+            idle_timeout: Duration::from_secs(secs),
+            absolute_lifetime: Duration::from_secs(secs * 10)
+        };
+
+        let policies = SessionPolicies {
+            business: policy(1),
+            community: policy(2),
+            system: policy(3)
+        };
+
+        assert_eq!(policies.for_user_type(&UserType::Business), policy(1));
+        assert_eq!(policies.for_user_type(&UserType::Community), policy(2));
+        assert_eq!(policies.for_user_type(&UserType::System), policy(3));
+        assert_eq!(SessionPolicies::default().for_user_type(&UserType::System), SessionPolicy::default());
+    }
+}
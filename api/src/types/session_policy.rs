@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+const DEFAULT_IDLE_TIMEOUT:u64 = 60 * 60 * 24;             // 1 day
+const DEFAULT_ABSOLUTE_LIFETIME:u64 = 60 * 60 * 24 * 7;    // 7 days
+
+/// how long a session may sit unused, and how long it may live no matter how often it is used
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SessionPolicy {
+    pub idle_timeout: Duration,
+    pub absolute_lifetime: Duration
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        SessionPolicy {
+            idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT),
+            absolute_lifetime: Duration::from_secs(DEFAULT_ABSOLUTE_LIFETIME)
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use blake3::Hash;
+use chrono::{DateTime,Utc};
+use sqlx::FromRow;
+
+use crate::{
+    enums::Error,
+    types::{DatabaseConnection, Session}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// a persisted session, the token secret is never stored
+#[derive(Clone,Debug,PartialEq)]
+pub struct SessionRecord {
+    pub key: [u8;16],
+    pub hash: Hash,
+    pub user_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>
+}
+
+/// database record transformer
+#[derive(Debug,FromRow)]
+struct DatabaseHelper {
+    session_key: Vec<u8>,
+    hash: Vec<u8>,
+    user_id: i64,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    last_used_at: DateTime<Utc>,
+    ip_address: Option<String>,
+    user_agent: Option<String>
+}
+
+impl DatabaseHelper {
+    /// transforms the raw database record into a SessionRecord
+    fn transform(self) -> Result<SessionRecord> {
+        let key: [u8;16] = self.session_key
+            .try_into()
+            .map_err(|_e| Error::SessionRecordInvalid)?;
+
+        let hash: [u8;32] = self.hash
+            .try_into()
+            .map_err(|_e| Error::SessionRecordInvalid)?;
+
+        let record = SessionRecord {
+            key,
+            hash: Hash::from(hash),
+            user_id: self.user_id,
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            last_used_at: self.last_used_at,
+            ip_address: self.ip_address,
+            user_agent: self.user_agent
+        };
+
+        Ok(record)
+    }
+}
+
+/// writes sessions through to the `user_session` table so they survive a restart
+#[derive(Clone,Debug)]
+pub struct SessionStore {
+    database: DatabaseConnection
+}
+
+impl SessionStore {
+    pub fn new(database: DatabaseConnection) -> Self {
+        SessionStore { database }
+    }
+
+    /// every unexpired session
+    pub async fn all(&self) -> Result<Vec<SessionRecord>> {
+        let sql = "SELECT session_key,hash,user_id,created_at,expires_at,last_used_at,ip_address,user_agent FROM `user_session` WHERE expires_at > ?";
+        let helpers:Vec<DatabaseHelper> = sqlx::query_as(sql)
+            .bind(Utc::now())
+            .fetch_all(&self.database.pool)
+            .await?;
+
+        helpers
+            .into_iter()
+            .map(DatabaseHelper::transform)
+            .collect()
+    }
+
+    /// persists a new session
+    pub async fn insert(&self, key: &[u8;16], session: &Session) -> Result<()> {
+        let sql = "INSERT INTO `user_session` (session_key,hash,user_id,created_at,expires_at,last_used_at,ip_address,user_agent) VALUES(?,?,?,?,?,?,?,?)";
+        sqlx::query(sql)
+            .bind(key.as_slice())
+            .bind(session.hash.as_bytes().as_slice())
+            .bind(session.user.id())
+            .bind(session.created_at)
+            .bind(session.expires_at())
+            .bind(session.last_used())
+            .bind(&session.ip_address)
+            .bind(&session.user_agent)
+            .execute(&self.database.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// records the last use after a session refresh so the idle timer survives a restart
+    pub async fn update_last_used(&self, key: &[u8;16], last_used_at: DateTime<Utc>) -> Result<()> {
+        let sql = "UPDATE `user_session` SET last_used_at = ? WHERE session_key = ?";
+        sqlx::query(sql)
+            .bind(last_used_at)
+            .bind(key.as_slice())
+            .execute(&self.database.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// swaps the stored hash after a token rotation
+    pub async fn update_hash(&self, key: &[u8;16], hash: &Hash) -> Result<()> {
+        let sql = "UPDATE `user_session` SET hash = ? WHERE session_key = ?";
+        sqlx::query(sql)
+            .bind(hash.as_bytes().as_slice())
+            .bind(key.as_slice())
+            .execute(&self.database.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// removes a persisted session
+    pub async fn delete(&self, key: &[u8;16]) -> Result<()> {
+        let sql = "DELETE FROM `user_session` WHERE session_key = ?";
+        sqlx::query(sql)
+            .bind(key.as_slice())
+            .execute(&self.database.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// removes every session a user holds
+    pub async fn delete_by_user(&self, user_id: i64) -> Result<u64> {
+        let sql = "DELETE FROM `user_session` WHERE user_id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(user_id)
+            .execute(&self.database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    /// removes every expired session and returns the number removed
+    pub async fn delete_expired(&self) -> Result<u64> {
+        let sql = "DELETE FROM `user_session` WHERE expires_at <= ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(Utc::now())
+            .execute(&self.database.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// tests transforming of database helper struct into a SessionRecord, including malformed binary columns
+    #[test]
+    fn database_helper_transform() {
+        let now = Utc::now();
+        let helper = DatabaseHelper {
+            session_key: vec![1;16],
+            hash: vec![2;32],
+            user_id: 7,
+            created_at: now,
+            expires_at: now,
+            last_used_at: now,
+            ip_address: None,
+            user_agent: None
+        };
+
+        let record = helper.transform().unwrap();
+        assert_eq!(record.key, [1;16]);
+        assert_eq!(record.hash, Hash::from([2;32]));
+        assert_eq!(record.user_id, 7);
+        assert_eq!(record.created_at, now);
+        assert_eq!(record.expires_at, now);
+        assert_eq!(record.last_used_at, now);
+
+        let short_key = DatabaseHelper {
+            session_key: vec![1;15],
+            hash: vec![2;32],
+            user_id: 7,
+            created_at: now,
+            expires_at: now,
+            last_used_at: now,
+            ip_address: None,
+            user_agent: None
+        };
+        assert!(matches!(short_key.transform(), Err(Error::SessionRecordInvalid)));
+
+        let short_hash = DatabaseHelper {
+            session_key: vec![1;16],
+            hash: vec![2;31],
+            user_id: 7,
+            created_at: now,
+            expires_at: now,
+            last_used_at: now,
+            ip_address: None,
+            user_agent: None
+        };
+        assert!(matches!(short_hash.transform(), Err(Error::SessionRecordInvalid)));
+    }
+}
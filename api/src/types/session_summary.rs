@@ -0,0 +1,12 @@
+use chrono::{DateTime,Utc};
+
+/// what an owner or admin can see about a live session
+#[derive(Clone,Debug,PartialEq)]
+pub struct SessionSummary {
+    pub id: String,
+    pub user_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>
+}
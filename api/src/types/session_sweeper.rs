@@ -1,10 +1,14 @@
+use std::time::Duration;
+
 use actix_web::web::Data;
 
 use crate::{
     enums::SessionControllerStatus,
-    types::AppState
+    types::{AppState, RefreshToken}
 };
 
+const REFRESH_TOKEN_SWEEP_INTERVAL:u64 = 3600;     // 1 hour
+
 pub struct SessionSweeper;
 
 impl SessionSweeper {
@@ -17,5 +21,20 @@ impl SessionSweeper {
                 SessionControllerStatus::Disabled => {}
             }
         });
+
+        let app_state = arc_state.clone();
+
+        // expired refresh token families
+        let _refresh_token_collector = actix_web::rt::spawn(async move {
+            let mut interval = actix_rt::time::interval(Duration::from_secs(REFRESH_TOKEN_SWEEP_INTERVAL));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(_e) = RefreshToken::delete_expired(app_state.database()).await {
+                    // log here
+                }
+            }
+        });
     }
-}
\ No newline at end of file
+}
@@ -81,6 +81,47 @@ impl Settings {
             Err(Error::SystemSettingsRecordNotReturned)
         }
     }
+
+    /// copies the database controlled fields from a freshly loaded record, port and master password stay local
+    pub fn merge(&self, record: &Settings) -> Settings {
+        Settings {
+            load_email_queue_service: record.load_email_queue_service,
+            postmark_email_service: record.postmark_email_service,
+            load_rate_limiter_service: record.load_rate_limiter_service,
+            load_text_queue_service: record.load_text_queue_service,
+            master_password: self.master_password.clone(),
+            ip_address: record.ip_address.clone(),
+            server_mode: record.server_mode,
+            server_port: self.server_port,
+            timestamp: record.timestamp
+        }
+    }
+
+    /// describes every database controlled field that differs between two settings
+    pub fn changes(&self, other: &Settings) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if self.load_email_queue_service != other.load_email_queue_service {
+            changes.push(format!("load_email_queue_service: {:?} -> {:?}", self.load_email_queue_service, other.load_email_queue_service));
+        }
+        if self.postmark_email_service != other.postmark_email_service {
+            changes.push(format!("postmark_email_service: {:?} -> {:?}", self.postmark_email_service, other.postmark_email_service));
+        }
+        if self.load_rate_limiter_service != other.load_rate_limiter_service {
+            changes.push(format!("load_rate_limiter_service: {:?} -> {:?}", self.load_rate_limiter_service, other.load_rate_limiter_service));
+        }
+        if self.load_text_queue_service != other.load_text_queue_service {
+            changes.push(format!("load_text_queue_service: {:?} -> {:?}", self.load_text_queue_service, other.load_text_queue_service));
+        }
+        if self.ip_address != other.ip_address {
+            changes.push(format!("ip_address: {} -> {} (applies on restart)", self.ip_address, other.ip_address));
+        }
+        if self.server_mode != other.server_mode {
+            changes.push(format!("server_mode: {:?} -> {:?}", self.server_mode, other.server_mode));
+        }
+
+        changes
+    }
 }
 
 impl Default for Settings {
@@ -137,4 +178,42 @@ mod tests {
         assert_eq!(transformed_data.timestamp, now);
         
     }
+
+    /// merging keeps local fields and reports only the fields that changed
+    #[test]
+    fn merge_and_changes() {
+        let now = chrono::Utc::now();
+        let current = Settings {
+            load_email_queue_service: SystemFlag::Disabled,
+            postmark_email_service: SystemFlag::Disabled,
+            load_rate_limiter_service: SystemFlag::Enabled,
+            load_text_queue_service: SystemFlag::Disabled,
+            master_password: MasterPassword::Some(String::from("password")),
+            ip_address: String::from("127.0.0.1"),
+            server_mode: ServerMode::Production,
+            server_port: 8080,
+            timestamp: now
+        };
+
+        let record = Settings {
+            load_rate_limiter_service: SystemFlag::Disabled,
+            master_password: MasterPassword::None,
+            server_mode: ServerMode::Maintenance,
+            server_port: 1,
+            ..current.clone()
+        };
+
+        assert!(current.changes(&current).is_empty());
+
+        let merged = current.merge(&record);
+        assert_eq!(merged.load_rate_limiter_service, SystemFlag::Disabled);
+        assert_eq!(merged.server_mode, ServerMode::Maintenance);
+        assert_eq!(merged.server_port, 8080);
+        assert_eq!(merged.master_password, MasterPassword::Some(String::from("password")));
+
+        let changes = current.changes(&merged);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0], "load_rate_limiter_service: Enabled -> Disabled");
+        assert_eq!(changes[1], "server_mode: Production -> Maintenance");
+    }
 }
\ No newline at end of file
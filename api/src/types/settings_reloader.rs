@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use actix_web::web::Data;
+
+use crate::types::AppState;
+
+const SETTINGS_POLL_INTERVAL:u64 = 30;     // 30 seconds
+
+pub struct SettingsReloader;
+
+impl SettingsReloader {
+    pub async fn run(arc_state: &Data<AppState>) {
+        let app_state = arc_state.clone();
+
+        let _settings_poll = actix_web::rt::spawn(async move {
+            let mut interval = actix_rt::time::interval(Duration::from_secs(SETTINGS_POLL_INTERVAL));
+
+            loop {
+                interval.tick().await;
+
+                match app_state.reload_settings().await {
+                    Ok(_changes) => {
+                        // log here
+                    },
+                    Err(_e) => {
+                        // log here
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,162 @@
+use std::net::IpAddr;
+
+use actix_web::http::header::{HeaderMap, FORWARDED, X_FORWARDED_FOR};
+use rate_limit::IpNetwork;
+
+use crate::enums::ForwardedHeader;
+
+/// proxies allowed to report the client address through forwarded headers
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct TrustedProxies {
+    networks: Vec<IpNetwork>,
+    header: ForwardedHeader
+}
+
+impl TrustedProxies {
+    /// constructor, proxies write X-Forwarded-For
+    pub fn new(networks: Vec<IpNetwork>) -> Self {
+        TrustedProxies { networks, header: ForwardedHeader::default() }
+    }
+
+    /// sets the header the proxies write, the other one is ignored so clients can't forge it
+    pub fn with_header(mut self, header: ForwardedHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// checks an address belongs to a trusted proxy
+    pub fn is_trusted(&self, ip_address: &IpAddr) -> bool {
+        self.networks
+            .iter()
+            .any(|network| network.contains(ip_address))
+    }
+
+    /// a single forwarded hop: bare, with a port, or a bracketed ipv6 address. none when obfuscated or unknown
+    fn parse_hop(hop: &str) -> Option<IpAddr> {
+        let hop = hop.trim().trim_matches('"');
+
+        if let Some(bracketed) = hop.strip_prefix('[') {
+            return bracketed.split(']').next()?.parse().ok();
+        }
+
+        hop.parse().ok().or_else(|| {
+            let (ip_address, _port) = hop.rsplit_once(':')?;
+            ip_address.parse().ok()
+        })
+    }
+
+    /// forwarded hops from the client outwards, read from the header the proxies write
+    fn hops(&self, headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+        let values = |name| headers
+            .get_all(name)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::to_string)
+            .collect::<Vec<String>>();
+
+        match self.header {
+            ForwardedHeader::Forwarded => values(FORWARDED)
+                .iter()
+                .filter_map(|element| element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .map(|(_, hop)| TrustedProxies::parse_hop(hop)))
+                .collect(),
+            ForwardedHeader::XForwardedFor => values(X_FORWARDED_FOR)
+                .iter()
+                .map(|hop| TrustedProxies::parse_hop(hop))
+                .collect()
+        }
+    }
+
+    /// the peer unless it's a trusted proxy, then the nearest forwarded hop that isn't one.
+    /// an unreadable hop stops the walk at the proxy that reported it
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let mut client = peer?;
+
+        if !self.is_trusted(&client) {
+            return Some(client);
+        }
+
+        for hop in self.hops(headers).into_iter().rev() {
+            match hop {
+                Some(ip_address) => {
+                    client = ip_address;
+
+                    if !self.is_trusted(&ip_address) {
+                        break;
+                    }
+                },
+                None => break
+            }
+        }
+
+        Some(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(name: actix_web::http::header::HeaderName, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    /// forwarded headers from untrusted peers are ignored
+    #[test]
+    fn untrusted_peer_is_the_client() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let peer: IpAddr = "203.0.113.9".parse().unwrap();
+
+        let client = proxies.client_ip(Some(peer), &headers(X_FORWARDED_FOR, "1.2.3.4"));
+        assert_eq!(client, Some(peer));
+        assert_eq!(proxies.client_ip(None, &HeaderMap::new()), None);
+    }
+
+    /// the walk skips trusted hops and stops at the first address a proxy vouched for
+    #[test]
+    fn trusted_proxies_are_skipped() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // a client forging the left of the header can't pick its address
+        let client = proxies.client_ip(Some(peer), &headers(X_FORWARDED_FOR, "6.6.6.6, 1.2.3.4, 10.0.0.2"));
+        assert_eq!(client, Some("1.2.3.4".parse().unwrap()));
+
+        let client = proxies.client_ip(Some(peer), &HeaderMap::new());
+        assert_eq!(client, Some(peer));
+
+        let proxies = proxies.with_header(ForwardedHeader::Forwarded);
+        let client = proxies.client_ip(Some(peer), &headers(FORWARDED, "for=\"[2001:db8::1]:4711\";proto=https, for=10.0.0.2"));
+        assert_eq!(client, Some("2001:db8::1".parse().unwrap()));
+
+        let client = proxies.client_ip(Some(peer), &headers(FORWARDED, "for=unknown, for=10.0.0.2"));
+        assert_eq!(client, Some("10.0.0.2".parse().unwrap()));
+    }
+
+    /// only the header the proxies write is read, a client forged one next to it is ignored
+    #[test]
+    fn forged_header_is_ignored() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        let mut forged = headers(X_FORWARDED_FOR, "1.2.3.4");
+        forged.insert(FORWARDED, HeaderValue::from_static("for=6.6.6.6"));
+
+        let client = proxies.client_ip(Some(peer), &forged);
+        assert_eq!(client, Some("1.2.3.4".parse().unwrap()));
+
+        let proxies = proxies.with_header(ForwardedHeader::Forwarded);
+        let mut forged = headers(FORWARDED, "for=1.2.3.4");
+        forged.insert(X_FORWARDED_FOR, HeaderValue::from_static("6.6.6.6"));
+
+        let client = proxies.client_ip(Some(peer), &forged);
+        assert_eq!(client, Some("1.2.3.4".parse().unwrap()));
+    }
+}
@@ -1,4 +1,5 @@
 // external libraries
+use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, MySql, Transaction};
 
 // internal libraries
@@ -10,7 +11,8 @@ use crate::{
 
 type Result<T> = std::result::Result<T,Error>;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserPermissions {
     pub admin_read: Permission,
     pub admin_write: Permission,
@@ -89,12 +91,12 @@ impl UserPermissions {
         let sessions_write = access_rights.sessions_write.to_i8();
         let sessions_delete = access_rights.sessions_delete.to_i8();
 
-        let sql = "INSERT INTO `user_permissions` (id,buckets_read,buckets_write,buckets_delete,images_read,images_write,images_delete,users_read,users_write,users_delete,sessions_read,sessions_write,sessions_delete) VALUES(?,?,?,?,?,?,?,?,?,?,?,?,?)";
+        let sql = "INSERT INTO `user_permissions` (id,admin_read,admin_write,admin_delete,buckets_read,buckets_write,buckets_delete,images_read,images_write,images_delete,users_read,users_write,users_delete,sessions_read,sessions_write,sessions_delete) VALUES(?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)";
         let insert_id = sqlx::query(sql)
-            .bind(admin_read)                    
-            .bind(admin_write)            
-            .bind(admin_delete)                    
             .bind(user_id)
+            .bind(admin_read)
+            .bind(admin_write)
+            .bind(admin_delete)
             .bind(buckets_read)
             .bind(buckets_write)
             .bind(buckets_delete)
@@ -114,8 +116,45 @@ impl UserPermissions {
         Ok(insert_id)
     }
 
+    pub async fn update_as_transaction(user_id: i64, access_rights: UserPermissions, tx: &mut Transaction<'static,MySql>) -> Result<u64> {
+        let sql = "UPDATE `user_permissions` SET admin_read = ?, admin_write = ?, admin_delete = ?, buckets_read = ?, buckets_write = ?, buckets_delete = ?, images_read = ?, images_write = ?, images_delete = ?, users_read = ?, users_write = ?, users_delete = ?, sessions_read = ?, sessions_write = ?, sessions_delete = ? WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(access_rights.admin_read.to_i8())
+            .bind(access_rights.admin_write.to_i8())
+            .bind(access_rights.admin_delete.to_i8())
+            .bind(access_rights.buckets_read.to_i8())
+            .bind(access_rights.buckets_write.to_i8())
+            .bind(access_rights.buckets_delete.to_i8())
+            .bind(access_rights.images_read.to_i8())
+            .bind(access_rights.images_write.to_i8())
+            .bind(access_rights.images_delete.to_i8())
+            .bind(access_rights.users_read.to_i8())
+            .bind(access_rights.users_write.to_i8())
+            .bind(access_rights.users_delete.to_i8())
+            .bind(access_rights.sessions_read.to_i8())
+            .bind(access_rights.sessions_write.to_i8())
+            .bind(access_rights.sessions_delete.to_i8())
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    pub async fn delete_as_transaction(user_id: i64, tx: &mut Transaction<'static,MySql>) -> Result<u64> {
+        let sql = "DELETE FROM `user_permissions` WHERE id = ?";
+        let rows_affected = sqlx::query(sql)
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+
     pub async fn from_user_id(user_id: i64,database: &DatabaseConnection) -> Result<UserPermissions> {
-        let sql = "SELECT buckets_read, buckets_write, buckets_delete, images_read, images_write, images_delete, sessions_read, sessions_write, sessions_delete, users_read, users_write, users_delete FROM `user_permissions` WHERE id = ? LIMIT 1";
+        let sql = "SELECT admin_read, admin_write, admin_delete, buckets_read, buckets_write, buckets_delete, images_read, images_write, images_delete, sessions_read, sessions_write, sessions_delete, users_read, users_write, users_delete FROM `user_permissions` WHERE id = ? LIMIT 1";
         let helper: DatabaseHelper = sqlx::query_as(sql)
             .bind(user_id)
             .fetch_one(&database.pool)
@@ -234,6 +273,20 @@ impl UserPermissions {
 
 }
 
+// accessors
+impl UserPermissions {
+    /// granted when any admin right is held
+    pub fn any_admin(&self) -> Permission {
+        if self.admin_read == Permission::Granted
+            || self.admin_write == Permission::Granted
+            || self.admin_delete == Permission::Granted {
+            Permission::Granted
+        } else {
+            Permission::None
+        }
+    }
+}
+
 impl Default for UserPermissions {
     fn default() -> Self {
         UserPermissions {
@@ -348,4 +401,14 @@ pub mod test {
         assert_eq!(test_full.has_permission(&no_rights),Permission::Granted);
         assert_eq!(test_full.has_permission(&full_rights),Permission::Granted);
     }
-}
\ No newline at end of file
+
+    /// any single admin right counts as admin
+    #[test]
+    fn any_admin_check() {
+        assert_eq!(UserPermissions::default().any_admin(),Permission::None);
+        assert_eq!(UserPermissions::default().with_users_full().any_admin(),Permission::None);
+        assert_eq!(UserPermissions::default().with_admin_read().any_admin(),Permission::Granted);
+        assert_eq!(UserPermissions::default().with_admin_write().any_admin(),Permission::Granted);
+        assert_eq!(UserPermissions::default().with_admin_delete().any_admin(),Permission::Granted);
+    }
+}
@@ -1,7 +1,11 @@
 mod business_user;
 mod community_user;
+mod new_user;
 mod system_user;
+mod user_update;
 
 pub use business_user::BusinessUser;
 pub use community_user::CommunityUser;
-pub use system_user::SystemUser;
\ No newline at end of file
+pub use new_user::NewUser;
+pub use system_user::SystemUser;
+pub use user_update::UserUpdate;
\ No newline at end of file
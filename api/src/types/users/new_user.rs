@@ -0,0 +1,94 @@
+use crate::{
+    enums::{Error, UserAccountStatus, UserType},
+    traits::ToNumber,
+    types::{DatabaseConnection, UserPermissions}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// account creation container, written to the database as a single transaction
+#[derive(Clone,Debug)]
+pub struct NewUser {
+    pub email: String,
+    pub username: String,
+    pub password: String,
+    pub user_type: UserType,
+    pub business_account_id: Option<i64>,
+    pub permissions: UserPermissions
+}
+
+impl NewUser {
+    /// hashes the password and writes the person, user, username, user type link and permissions records, returns the new user id
+    pub async fn into_db(self, database: &DatabaseConnection) -> Result<i64> {
+        // business users must belong to a business account
+        let business_account_id = match (&self.user_type, self.business_account_id) {
+            (UserType::Business, Some(id)) => Some(id),
+            (UserType::Business, None) => return Err(Error::BusinessAccountRequired),
+            (_, _) => None
+        };
+
+        let hash = bcrypt::hash(&self.password, bcrypt::DEFAULT_COST)?;
+        let user_status_id = UserAccountStatus::Enabled.to_i8();
+        let user_type_id = self.user_type.clone().to_i8();
+
+        // begin transaction
+        let mut tx = database.pool.begin().await?;
+
+        // users share their id with the person record
+        let sql = "INSERT INTO `person` (email) VALUES(?)";
+        let user_id = sqlx::query(sql)
+            .bind(&self.email)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_id() as i64;
+
+        let sql = "INSERT INTO `user` (id,hash,user_status_id,user_type_id) VALUES(?,?,?,?)";
+        sqlx::query(sql)
+            .bind(user_id)
+            .bind(hash)
+            .bind(user_status_id)
+            .bind(user_type_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let sql = "INSERT INTO `username` (user_id,username) VALUES(?,?)";
+        sqlx::query(sql)
+            .bind(user_id)
+            .bind(&self.username)
+            .execute(&mut *tx)
+            .await?;
+
+        // link the user to its user type table
+        match self.user_type {
+            UserType::Business => {
+                let sql = "INSERT INTO `business_account_users` (business_account_id,user_id) VALUES(?,?)";
+                sqlx::query(sql)
+                    .bind(business_account_id)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+            },
+            UserType::Community => {
+                let sql = "INSERT INTO `community_users` (user_id) VALUES(?)";
+                sqlx::query(sql)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+            },
+            UserType::System => {
+                let sql = "INSERT INTO `system_users` (user_id) VALUES(?)";
+                sqlx::query(sql)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        let _ = UserPermissions::into_db_as_transaction(user_id, self.permissions, &mut tx).await?;
+
+        tx.commit().await?;
+        // end transaction
+
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    enums::{Error, RowsUpdated, UserAccountStatus},
+    traits::{ToNumber, ToUpdatedResult},
+    types::{DatabaseConnection, UserPermissions}
+};
+
+type Result<T> = std::result::Result<T,Error>;
+
+/// partial account update, only fields set to Some(_) are written
+#[derive(Clone,Debug,Default)]
+pub struct UserUpdate {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub status: Option<UserAccountStatus>,
+    pub permissions: Option<UserPermissions>
+}
+
+impl UserUpdate {
+    /// true when there is nothing to write
+    pub fn is_empty(&self) -> bool {
+        self.username.is_none()
+            && self.password.is_none()
+            && self.status.is_none()
+            && self.permissions.is_none()
+    }
+
+    /// writes all requested changes in a single transaction
+    pub async fn into_db(self, user_id: i64, database: &DatabaseConnection) -> Result<RowsUpdated> {
+        // hash before the transaction is opened
+        let hash_opt = match &self.password {
+            Some(password) => Some(bcrypt::hash(password, bcrypt::DEFAULT_COST)?),
+            None => None
+        };
+
+        let mut rows_affected: u64 = 0;
+
+        // begin transaction
+        let mut tx = database.pool.begin().await?;
+
+        if let Some(username) = &self.username {
+            let sql = "UPDATE `username` SET username = ? WHERE user_id = ?";
+            rows_affected += sqlx::query(sql)
+                .bind(username)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        if let Some(hash) = hash_opt {
+            let sql = "UPDATE `user` SET hash = ? WHERE id = ?";
+            rows_affected += sqlx::query(sql)
+                .bind(hash)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        if let Some(status) = self.status {
+            let sql = "UPDATE `user` SET user_status_id = ? WHERE id = ?";
+            rows_affected += sqlx::query(sql)
+                .bind(status.to_i8())
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        if let Some(permissions) = self.permissions {
+            rows_affected += UserPermissions::update_as_transaction(user_id, permissions, &mut tx).await?;
+        }
+
+        tx.commit().await?;
+        // end transaction
+
+        Ok(rows_affected.to_updated_result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// an update with no fields set has nothing to write
+    #[test]
+    fn empty_update() {
+        let update = UserUpdate::default();
+        assert!(update.is_empty());
+
+        let update = UserUpdate {
+            status: Some(UserAccountStatus::Suspended),
+            ..UserUpdate::default()
+        };
+        assert!(!update.is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Instant;
+
+use crate::{
+    enums::{RateLimitError, RefillRate},
+    traits::Limiter,
+    types::{Gcra, SlidingWindow, SnapshotReader, SnapshotWriter, TokenBucket}
+};
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+/// admission algorithm used for every key of a RateLimiter
+#[derive(Copy,Clone,Debug,Default,PartialEq)]
+pub enum Algorithm {
+    #[default]
+    TokenBucket,
+    Gcra,
+    SlidingWindow
+}
+
+impl Algorithm {
+    /// builds a fresh key's limiter starting at `now`
+    pub fn build(&self, capacity: u32, initial_tokens: u32, refill_rate: &RefillRate, now: Instant) -> Box<dyn Limiter> {
+        match self {
+            Algorithm::TokenBucket => Box::new(
+                TokenBucket::new()
+                    .with_capacity(capacity)
+                    .with_initial_tokens(initial_tokens)
+                    .with_refill_rate(refill_rate.clone())
+                    .starting_at(now)
+            ),
+            Algorithm::Gcra => Box::new(
+                Gcra::new(capacity, refill_rate, now)
+                    .with_initial_tokens(initial_tokens)
+            ),
+            Algorithm::SlidingWindow => Box::new(
+                SlidingWindow::new(capacity, refill_rate, now)
+                    .with_initial_tokens(initial_tokens)
+            )
+        }
+    }
+
+    /// writes the algorithm tag ahead of a key's state
+    pub fn write_tag(&self, writer: &mut SnapshotWriter) {
+        writer.put_u8(match self {
+            Algorithm::TokenBucket => 0,
+            Algorithm::Gcra => 1,
+            Algorithm::SlidingWindow => 2
+        });
+    }
+
+    /// reads a tagged key state back into whichever algorithm wrote it
+    pub fn read_state(reader: &mut SnapshotReader) -> Result<Box<dyn Limiter>> {
+        match reader.get_u8()? {
+            0 => Ok(Box::new(TokenBucket::read_state(reader)?)),
+            1 => Ok(Box::new(Gcra::read_state(reader)?)),
+            2 => Ok(Box::new(SlidingWindow::read_state(reader)?)),
+            tag => Err(RateLimitError::InvalidSnapshot(format!("unknown algorithm {tag}")))
+        }
+    }
+}
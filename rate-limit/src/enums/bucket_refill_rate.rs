@@ -1,7 +1,47 @@
+use std::time::Duration;
+
+const DAY_AS_SECS:f32 = 24.0 * 60.0 * 60.0;
+const HOUR_AS_SECS:f32 = 60.0 * 60.0;
+const MINUTE_AS_SECS:f32 = 60.0;
+const SECOND:f32 = 1.0;
+
 #[derive(Clone,Debug,PartialEq)]
 pub enum RefillRate {
     PerSecond(f32),
     PerMinute(f32),
     PerHour(f32),
     PerDay(f32)
-}
\ No newline at end of file
+}
+
+impl RefillRate {
+    /// length of the window the rate is counted over
+    pub fn window_secs(&self) -> f32 {
+        match self {
+            RefillRate::PerDay(_) => DAY_AS_SECS,
+            RefillRate::PerHour(_) => HOUR_AS_SECS,
+            RefillRate::PerMinute(_) => MINUTE_AS_SECS,
+            RefillRate::PerSecond(_) => SECOND
+        }
+    }
+
+    /// time a single token takes to come back, none for a rate of zero.
+    /// divided out of the window so whole rates give exact intervals
+    pub fn interval(&self) -> Option<Duration> {
+        let (RefillRate::PerDay(n) | RefillRate::PerHour(n) | RefillRate::PerMinute(n) | RefillRate::PerSecond(n)) = self;
+
+        match *n > 0.0 {
+            true => Some(Duration::from_secs_f64(self.window_secs() as f64 / *n as f64)),
+            false => None
+        }
+    }
+
+    /// calculates the refill rate per second
+    pub fn tokens_per_sec(&self) -> f32 {
+        match self {
+            RefillRate::PerDay(n) => n / DAY_AS_SECS,
+            RefillRate::PerHour(n) => n / HOUR_AS_SECS,
+            RefillRate::PerMinute(n) => n / MINUTE_AS_SECS,
+            RefillRate::PerSecond(n) => *n
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod algorithm;
 mod bucket_refill_rate;
 mod bucket_status;
 mod list_status;
@@ -6,6 +7,7 @@ mod rate_limit_error;
 mod timer_status;
 mod time_window;
 
+pub use algorithm::Algorithm;
 pub use bucket_refill_rate::RefillRate;
 pub use bucket_status::BucketStatus;
 pub use list_status::ListStatus;
@@ -1,5 +1,5 @@
 use derive_more::derive::From;
-use std::{fmt::{Debug, Display}, net::IpAddr};
+use std::fmt::{Debug, Display};
 
 /// customer error types for RateLimter
 #[derive(Debug,From)]
@@ -7,14 +7,20 @@ pub enum RateLimitError {
     // derived errors ↴
     #[from]
     IpAddr(std::net::AddrParseError),
+    #[from]
+    Io(std::io::Error),
 
     // custom error types
-    DuplicateBlacklistEntry(IpAddr),
-    DuplicateWhitelistEntry(IpAddr),
+    DuplicateBlacklistEntry(String),    // debug formatted key
+    DuplicateWhitelistEntry(String),    // debug formatted key
+    InvalidNetwork(String),             // rejected cidr range
+    InvalidSnapshot(String),            // reason a snapshot could not be read
     PoisonedBlacklist,
     PoisonedRateLimiterMap,
     PoisonedWhitelistlist,
-    TimeWindowOutOfBounds
+    SharedStore(String),                // message passed back by a shared store
+    TimeWindowOutOfBounds,
+    UnknownAlgorithm(String)            // rejected algorithm name
 
     // disabled by default ↴
     // DevError(String),
@@ -26,12 +32,17 @@ impl Display for RateLimitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // print only on non-production server modes, otherwise do not print detailed
         match self {
-            RateLimitError::DuplicateBlacklistEntry(ip) => write!(f, "[rate limit] ip \"{ip:?}\" already blacklisted"),
-            RateLimitError::DuplicateWhitelistEntry(ip) => write!(f, "[rate limit] ip \"{ip:?}\" already whitelisted"),
+            RateLimitError::DuplicateBlacklistEntry(key) => write!(f, "[rate limit] key \"{key}\" already blacklisted"),
+            RateLimitError::DuplicateWhitelistEntry(key) => write!(f, "[rate limit] key \"{key}\" already whitelisted"),
+            RateLimitError::InvalidNetwork(network) => write!(f, "[rate limit] \"{network}\" is not a valid cidr range"),
+            RateLimitError::InvalidSnapshot(reason) => write!(f, "[rate limit] invalid snapshot: {reason}"),
+            RateLimitError::Io(e) => write!(f, "[rate limit] snapshot io error: {e}"),
             RateLimitError::PoisonedBlacklist => write!(f, "[rate limit] rate limiter black list poisoned"),
             RateLimitError::PoisonedRateLimiterMap => write!(f, "[rate limit] rate limiter map poisoned"),
             RateLimitError::PoisonedWhitelistlist => write!(f, "[rate limit] rate limiter white list poisoned"),
+            RateLimitError::SharedStore(message) => write!(f, "[rate limit] shared store error: {message}"),
             RateLimitError::TimeWindowOutOfBounds => write!(f, "[rate limit] time window setting out of bounds"),
+            RateLimitError::UnknownAlgorithm(name) => write!(f, "[rate limit] \"{name}\" is not a known algorithm"),
             // RateLimitError::DevError(dev_message) => write!(f,"[dev message] {dev_message}"),
             _ => write!(f, "[rate limit error]")
         }
@@ -2,4 +2,4 @@ pub mod types;
 pub mod enums;
 pub mod traits;
 
-pub use types::{RateLimitBuilder,RateLimiter};
\ No newline at end of file
+pub use types::{IpNetwork,Quota,RateLimitBuilder,RateLimiter};
\ No newline at end of file
@@ -0,0 +1,6 @@
+use std::{fmt::Debug, time::Instant};
+
+/// source of the current time, the system clock in production and a manual clock under test
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
@@ -0,0 +1,48 @@
+use std::{fmt::Debug, time::{Duration, Instant}};
+
+use crate::{
+    enums::{Algorithm, BucketStatus, Decision},
+    types::SnapshotWriter
+};
+
+/// per-key admission algorithm, the RateLimiter keeps one per key. time is always passed in
+/// so the algorithms never read the clock themselves
+pub trait Limiter: Debug + Send {
+    /// spends `cost` units at `now`. a denial only counts a single unit against the key
+    fn acquire(&mut self, cost: u32, now: Instant) -> Decision;
+
+    /// the first connection on a fresh key
+    fn open(&mut self, cost: u32, now: Instant) -> Decision {
+        self.acquire(cost, now)
+    }
+
+    /// most units the key can hold
+    fn capacity(&self) -> u32;
+
+    /// units left after the last connection, negative once denials pile up past empty
+    fn tokens(&self) -> i32;
+
+    /// time from `now` until `units` are available
+    fn time_until(&self, units: i32, now: Instant) -> Duration;
+
+    /// when the key's state is no different from a fresh key and can be dropped
+    fn expires_at(&self) -> Instant;
+
+    /// bumped on every approval so stale heap entries can be told apart
+    fn ver(&self) -> u64;
+
+    /// the algorithm that reads the state back
+    fn algorithm(&self) -> Algorithm;
+
+    /// writes the key's state for a snapshot, read back by Algorithm::read_state
+    fn write_state(&self, writer: &mut SnapshotWriter);
+
+    /// checks ttl vs `now` and returns a BucketStatus
+    fn is_expired(&self, now: Instant) -> BucketStatus {
+        if now >= self.expires_at() {
+            BucketStatus::Expired
+        } else {
+            BucketStatus::NotExpired
+        }
+    }
+}
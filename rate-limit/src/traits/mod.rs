@@ -1,9 +1,23 @@
+mod clock;
+mod limiter;
+mod rate_limit_backend;
+mod rate_limit_key;
+mod shared_store;
+mod snapshot_key;
+mod to_algorithm;
 mod to_blacklist_status;
 mod to_decision;
 mod to_timer_status;
 mod to_time_window;
 mod to_whitelist_status;
 
+pub use clock::Clock;
+pub use limiter::Limiter;
+pub use rate_limit_backend::{BackendFuture,RateLimitBackend};
+pub use rate_limit_key::RateLimitKey;
+pub use shared_store::{SharedStore,StoreFuture};
+pub use snapshot_key::SnapshotKey;
+pub use to_algorithm::ToAlgorithm;
 pub use to_decision::ToDecision;
 pub use to_blacklist_status::ToBlackListStatus;
 pub use to_timer_status::ToTimerStatus;
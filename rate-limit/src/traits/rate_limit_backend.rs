@@ -0,0 +1,42 @@
+use std::{fmt::Debug, future::Future, pin::Pin, time::Instant};
+
+use crate::{
+    enums::RateLimitError,
+    traits::{Limiter, RateLimitKey},
+    types::Quota
+};
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+/// future returned by a backend sync
+pub type BackendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// where a RateLimiter keeps each key's state. the local backend keeps it in this process, a shared
+/// backend counts against a store every server instance syncs with. black and whitelists stay with the RateLimiter
+pub trait RateLimitBackend<K: RateLimitKey>: Debug + Send + Sync {
+    /// spends `cost` units on `key` at `now`. returns the quota along with the units left,
+    /// negative once denials pile up past empty
+    fn acquire(&self, key: &K, cost: u32, now: Instant) -> Result<(Quota,i32)>;
+
+    /// drops keys whose state is no different from a fresh key
+    fn collect(&self, now: Instant);
+
+    /// keys held in this process
+    fn keys(&self) -> usize;
+
+    /// exchanges counts with the shared store, nothing to do for a local backend
+    fn sync(&self) -> BackendFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// visits every live key's limiter for a snapshot. shared backends keep their state in the store
+    /// and have nothing to visit
+    fn export(&self, _now: Instant, _visit: &mut dyn FnMut(&K, &dyn Limiter)) -> Result<()> {
+        Ok(())
+    }
+
+    /// takes a key's limiter back from a snapshot, false when the backend doesn't keep one
+    fn import(&self, _key: K, _bucket: Box<dyn Limiter>) -> Result<bool> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,7 @@
+use std::{fmt::Debug, hash::Hash};
+
+/// anything a rate limiter can bucket on: ip addresses, user ids, usernames, api keys.
+/// keys are shared with the backend's worker threads
+pub trait RateLimitKey: Clone + Debug + Eq + Hash + Send + Sync + 'static {}
+
+impl<K: Clone + Debug + Eq + Hash + Send + Sync + 'static> RateLimitKey for K {}
@@ -0,0 +1,17 @@
+use std::{fmt::Debug, future::Future, pin::Pin};
+
+use crate::{enums::RateLimitError, traits::RateLimitKey};
+
+/// future returned by a shared store
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T,RateLimitError>> + Send + 'a>>;
+
+/// counters every server instance adds to, one per key and window of wall clock time. a window's
+/// total is the sum of every instance's counts, which is why shared backends count in sliding windows
+pub trait SharedStore<K: RateLimitKey>: Debug + Send + Sync {
+    /// adds each count to the key's total in `window` and returns the totals across every instance.
+    /// a count of zero only reads the total
+    fn add(&self, window: u64, counts: Vec<(K,u32)>) -> StoreFuture<'_, Vec<(K,u32)>>;
+
+    /// drops every window before `window`
+    fn expire(&self, window: u64) -> StoreFuture<'_, ()>;
+}
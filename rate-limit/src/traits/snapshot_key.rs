@@ -0,0 +1,64 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    enums::RateLimitError,
+    types::{SnapshotReader, SnapshotWriter}
+};
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+/// keys a limiter snapshot can carry
+pub trait SnapshotKey: Sized {
+    fn write_key(&self, writer: &mut SnapshotWriter);
+    fn read_key(reader: &mut SnapshotReader) -> Result<Self>;
+}
+
+impl SnapshotKey for IpAddr {
+    fn write_key(&self, writer: &mut SnapshotWriter) {
+        match self {
+            IpAddr::V4(addr) => {
+                writer.put_u8(4);
+                writer.put_u32(u32::from(*addr));
+            },
+            IpAddr::V6(addr) => {
+                writer.put_u8(6);
+                writer.put_bytes(&addr.octets());
+            }
+        }
+    }
+
+    fn read_key(reader: &mut SnapshotReader) -> Result<Self> {
+        match reader.get_u8()? {
+            4 => Ok(IpAddr::V4(Ipv4Addr::from(reader.get_u32()?))),
+            6 => {
+                let octets: [u8;16] = reader.get_bytes()?
+                    .try_into()
+                    .map_err(|_e| RateLimitError::InvalidSnapshot(String::from("ipv6 key is not 16 bytes")))?;
+
+                Ok(IpAddr::V6(Ipv6Addr::from(octets)))
+            },
+            family => Err(RateLimitError::InvalidSnapshot(format!("unknown address family {family}")))
+        }
+    }
+}
+
+impl SnapshotKey for i64 {
+    fn write_key(&self, writer: &mut SnapshotWriter) {
+        writer.put_i64(*self);
+    }
+
+    fn read_key(reader: &mut SnapshotReader) -> Result<Self> {
+        reader.get_i64()
+    }
+}
+
+impl SnapshotKey for String {
+    fn write_key(&self, writer: &mut SnapshotWriter) {
+        writer.put_bytes(self.as_bytes());
+    }
+
+    fn read_key(reader: &mut SnapshotReader) -> Result<Self> {
+        String::from_utf8(reader.get_bytes()?.to_vec())
+            .map_err(|_e| RateLimitError::InvalidSnapshot(String::from("string key is not utf-8")))
+    }
+}
@@ -0,0 +1,16 @@
+use crate::enums::{Algorithm,RateLimitError};
+
+pub trait ToAlgorithm {
+    fn to_algorithm(self) -> Result<Algorithm,RateLimitError>;
+}
+
+impl ToAlgorithm for &String {
+    fn to_algorithm(self) -> Result<Algorithm,RateLimitError> {
+        match self.as_str() {
+            "TOKEN_BUCKET"   => Ok(Algorithm::TokenBucket),
+            "GCRA"           => Ok(Algorithm::Gcra),
+            "SLIDING_WINDOW" => Ok(Algorithm::SlidingWindow),
+            _ => Err(RateLimitError::UnknownAlgorithm(self.to_owned()))
+        }
+    }
+}
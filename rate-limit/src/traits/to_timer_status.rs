@@ -8,17 +8,11 @@ pub trait ToTimerStatus {
 
 impl ToTimerStatus for Timer {
     fn to_timer_status(self) -> TimerStatus {
-        let expires_opt = self.expires(); 
-        
-        if let Some(expires) = *expires_opt {
-            let now = Instant::now();
-            
-            match now.gt(&expires) {
-                true => TimerStatus::Expired,
-                false => TimerStatus::Running
-            }
-        } else {
-            TimerStatus::Poison
-        }
+        self.status_at(Instant::now())
     }
-}
\ No newline at end of file
+}
+impl ToTimerStatus for &Timer {
+    fn to_timer_status(self) -> TimerStatus {
+        self.status_at(Instant::now())
+    }
+}
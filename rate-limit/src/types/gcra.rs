@@ -0,0 +1,180 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    enums::{Algorithm, Decision, RateLimitError, RefillRate},
+    traits::Limiter,
+    types::{SnapshotReader, SnapshotWriter}
+};
+
+const MAX_INTERVAL:u64 = 60 * 60 * 24 * 365; // a year, stands in for a rate of zero
+
+/// generic cell rate algorithm. tracks the theoretical arrival time instead of a token count,
+/// so there is no window to sit out and no burst beyond the capacity
+#[derive(Clone,Debug)]
+pub struct Gcra {
+    capacity: u32,
+    interval: Duration, // time a single unit takes to come back
+    tat: Instant,       // theoretical arrival time, the key is back to full from here on
+    tokens: i32,        // units left after the last connection
+    ver: u64
+}
+
+impl Gcra {
+    /// constructor, starts full at `now`
+    pub fn new(capacity: u32, refill_rate: &RefillRate, now: Instant) -> Self {
+        let interval = refill_rate
+            .interval()
+            .unwrap_or(Duration::from_secs(MAX_INTERVAL));
+
+        Gcra {
+            capacity,
+            interval,
+            tat: now,
+            tokens: capacity as i32,
+            ver: 0
+        }
+    }
+
+    /// reads back the state written by write_state
+    pub fn read_state(reader: &mut SnapshotReader) -> Result<Self, RateLimitError> {
+        Ok(Gcra {
+            capacity: reader.get_u32()?,
+            interval: reader.get_duration()?,
+            tat: reader.get_instant()?,
+            tokens: reader.get_i32()?,
+            ver: 0
+        })
+    }
+
+    /// starts the key with fewer units than it can hold
+    pub fn with_initial_tokens(mut self, initial_tokens: u32) -> Self {
+        let spent = self.capacity.saturating_sub(initial_tokens);
+
+        self.tat += self.interval * spent;
+        self.tokens = initial_tokens.min(self.capacity) as i32;
+        self
+    }
+
+    /// whole units left at `now`, negative while the arrival time runs past the burst allowance
+    fn units_at(&self, now: Instant) -> i32 {
+        let burst = self.interval * self.capacity;
+        let full_at = now + burst;
+
+        let nanos = match full_at.checked_duration_since(self.tat) {
+            Some(ahead) => ahead.as_nanos() as i128,
+            None => -(self.tat.duration_since(full_at).as_nanos() as i128)
+        };
+
+        nanos
+            .div_euclid(self.interval.as_nanos().max(1) as i128)
+            .clamp(i32::MIN as i128, i32::MAX as i128) as i32
+    }
+}
+
+impl Limiter for Gcra {
+    fn acquire(&mut self, cost: u32, now: Instant) -> Decision {
+        let cost = cost.max(1);
+        let tat = self.tat.max(now);
+        let next_tat = tat + self.interval * cost;
+
+        let decision = if next_tat <= now + self.interval * self.capacity {
+            self.tat = next_tat;
+
+            // wrap on overflow
+            self.ver = self.ver.checked_add(1).unwrap_or(0);
+
+            Decision::Approved
+        } else {
+            self.tat = tat + self.interval;
+
+            Decision::Denied
+        };
+
+        self.tokens = self.units_at(now);
+
+        decision
+    }
+
+    fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    fn tokens(&self) -> i32 {
+        self.tokens
+    }
+
+    fn time_until(&self, units: i32, now: Instant) -> Duration {
+        let units = units.clamp(0, self.capacity as i32) as u32;
+
+        (self.tat + self.interval * units)
+            .checked_sub(self.interval * self.capacity)
+            .map_or(Duration::ZERO, |allowed_at| allowed_at.saturating_duration_since(now))
+    }
+
+    fn expires_at(&self) -> Instant {
+        self.tat
+    }
+
+    fn ver(&self) -> u64 {
+        self.ver
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Gcra
+    }
+
+    fn write_state(&self, writer: &mut SnapshotWriter) {
+        writer.put_u32(self.capacity);
+        writer.put_duration(self.interval);
+        writer.put_instant(self.tat);
+        writer.put_i32(self.tokens);
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// a full key spends its burst, then admits one unit per interval with denials pushing it back
+    #[test]
+    fn gcra_sequence() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // one unit every 10 seconds, 3 in a burst
+        let mut gcra = Gcra::new(3, &RefillRate::PerMinute(6.0), start);
+
+        let sequence = [
+            (0, 1, Decision::Approved, 2),
+            (0, 1, Decision::Approved, 1),
+            (0, 1, Decision::Approved, 0),
+            (0, 1, Decision::Denied, -1),   // denials push the arrival time back a unit
+            (5, 1, Decision::Denied, -2),
+            (30, 1, Decision::Approved, 0), // the debt is paid off exactly on time
+            (30, 1, Decision::Denied, -1),
+            (100, 1, Decision::Approved, 2),// no burst past the capacity after a long quiet spell
+            (100, 2, Decision::Approved, 0),
+            (100, 1, Decision::Denied, -1)
+        ];
+
+        for (step, (secs, cost, decision, tokens)) in sequence.into_iter().enumerate() {
+            assert_eq!(gcra.acquire(cost, at(secs)), decision, "step {step}");
+            assert_eq!(gcra.tokens(), tokens, "step {step}");
+        }
+
+        assert_eq!(gcra.time_until(1, at(100)), Duration::from_secs(20));
+        assert_eq!(gcra.time_until(3, at(100)), Duration::from_secs(40));
+        assert_eq!(gcra.expires_at(), at(140));
+    }
+
+    /// initial tokens start the key part spent
+    #[test]
+    fn gcra_initial_tokens() {
+        let start = Instant::now();
+        let mut gcra = Gcra::new(3, &RefillRate::PerMinute(6.0), start).with_initial_tokens(1);
+
+        assert_eq!(gcra.acquire(1, start), Decision::Approved);
+        assert_eq!(gcra.acquire(1, start), Decision::Denied);
+        assert_eq!(gcra.acquire(1, start + Duration::from_secs(20)), Decision::Approved);
+    }
+}
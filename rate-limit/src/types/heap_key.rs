@@ -1,8 +1,29 @@
-use std::{time::Instant,net::IpAddr};
+use std::{cmp::Ordering, net::IpAddr, time::Instant};
 
-#[derive(Clone,Debug,PartialEq, Eq, PartialOrd, Ord)]
-pub struct HeapKey {
+/// heap entries are ordered by expiry and version only, so keys don't need to be ordered
+#[derive(Clone,Debug)]
+pub struct HeapKey<K = IpAddr> {
     pub expires_at: Instant,
     pub ver: u64,
-    pub ip: IpAddr
-}
\ No newline at end of file
+    pub key: K
+}
+
+impl<K> PartialEq for HeapKey<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K> Eq for HeapKey<K> {}
+
+impl<K> PartialOrd for HeapKey<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for HeapKey<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.expires_at, self.ver).cmp(&(other.expires_at, other.ver))
+    }
+}
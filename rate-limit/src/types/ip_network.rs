@@ -0,0 +1,140 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr
+};
+
+use crate::enums::RateLimitError;
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+/// an ip address range in CIDR notation, a bare address is a single host
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct IpNetwork {
+    addr: IpAddr,   // network address, host bits zeroed
+    prefix: u8
+}
+
+impl IpNetwork {
+    /// constructor, host bits past the prefix are dropped
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self> {
+        if prefix > IpNetwork::max_prefix(&addr) {
+            return Err(RateLimitError::InvalidNetwork(format!("{addr}/{prefix}")));
+        }
+
+        Ok(IpNetwork {
+            addr: IpNetwork::mask(addr, prefix),
+            prefix
+        })
+    }
+
+    /// a network holding a single address
+    pub fn host(addr: IpAddr) -> Self {
+        IpNetwork {
+            addr,
+            prefix: IpNetwork::max_prefix(&addr)
+        }
+    }
+
+    /// network address getter
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// prefix length getter
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// 32 for ipv4, 128 for ipv6
+    pub fn max_prefix(addr: &IpAddr) -> u8 {
+        match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128
+        }
+    }
+
+    /// zeroes the bits of an address past the prefix
+    pub fn mask(addr: IpAddr, prefix: u8) -> IpAddr {
+        match addr {
+            IpAddr::V4(v4) => {
+                let mask = u32::MAX.checked_shl(32u32.saturating_sub(prefix as u32)).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            },
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX.checked_shl(128u32.saturating_sub(prefix as u32)).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+        }
+    }
+
+    /// checks an address falls inside the range, never across families
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.addr.is_ipv4() == addr.is_ipv4()
+            && IpNetwork::mask(*addr, self.prefix) == self.addr
+    }
+
+    /// single host networks
+    pub fn is_host(&self) -> bool {
+        self.prefix == IpNetwork::max_prefix(&self.addr)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = RateLimitError;
+
+    /// parses "10.0.0.0/8", "2001:db8::/64" or a bare address
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().split_once('/') {
+            Some((addr, prefix)) => {
+                let addr: IpAddr = addr.parse()?;
+                let prefix: u8 = prefix
+                    .parse()
+                    .map_err(|_e| RateLimitError::InvalidNetwork(s.to_string()))?;
+
+                IpNetwork::new(addr, prefix)
+            },
+            None => Ok(IpNetwork::host(s.trim().parse()?))
+        }
+    }
+}
+
+impl Display for IpNetwork {
+    /// hosts print as a bare address
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.is_host() {
+            true => write!(f, "{}", self.addr),
+            false => write!(f, "{}/{}", self.addr, self.prefix)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// parsing masks host bits and rejects out of range prefixes
+    #[test]
+    fn parse_networks() {
+        let network: IpNetwork = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(network.to_string(), "10.0.0.0/8");
+        assert!(network.contains(&"10.255.0.1".parse().unwrap()));
+        assert!(!network.contains(&"11.0.0.1".parse().unwrap()));
+        assert!(!network.contains(&"::a00:1".parse().unwrap()));
+
+        let network: IpNetwork = "2001:db8:1:2:3:4:5:6/64".parse().unwrap();
+        assert_eq!(network.to_string(), "2001:db8:1:2::/64");
+        assert!(network.contains(&"2001:db8:1:2:ffff::1".parse().unwrap()));
+
+        let host: IpNetwork = "192.168.0.1".parse().unwrap();
+        assert!(host.is_host());
+        assert_eq!(host.to_string(), "192.168.0.1");
+
+        let everything: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(everything.contains(&"8.8.8.8".parse().unwrap()));
+
+        assert!(matches!("10.0.0.0/33".parse::<IpNetwork>(), Err(RateLimitError::InvalidNetwork(_))));
+        assert!("10.0.0.0/x".parse::<IpNetwork>().is_err());
+        assert!("not an ip".parse::<IpNetwork>().is_err());
+    }
+}
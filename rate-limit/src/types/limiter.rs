@@ -1,212 +1,277 @@
 /// the primary purpose of the rate limiter is to deter good actors from overusing resources, not necessarily to deter DDOS attacks.
 use std::{
-    cmp::Reverse,
-    collections::{hash_map::{DefaultHasher, HashMap,},BinaryHeap},
-    hash::{Hash,Hasher},
+    collections::HashMap,
     net::IpAddr,
-    sync::{Mutex, RwLock},
-    time::{Duration, Instant}
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH}
 };
 
 use crate::{
-    enums::{BucketStatus, Decision, ListStatus, RateLimitError, RefillRate},
-    traits::{ToBlackListStatus,ToWhiteListStatus},
-    types::{HeapKey,RateLimitBuilder,Timer,TokenBucket}
+    enums::{Algorithm, BucketStatus, Decision, ListStatus, RateLimitError, TimerStatus},
+    traits::{Clock,Limiter,RateLimitBackend,RateLimitKey,SnapshotKey,ToBlackListStatus,ToWhiteListStatus},
+    types::{IpNetwork,LocalBackend,NetworkTable,Quota,RateLimitBuilder,SharedBackend,SnapshotReader,SnapshotWriter,Timer}
 };
 
 type Result<T> = std::result::Result<T,RateLimitError>;
 
 const BLACK_LIST_TIME:u64   = 60;
+const MAX_BLACK_LIST_TIME:u64 = 86_400;  // 1 day, re-offenders stop escalating here
+const OFFENSE_TTL:u64       = 86_400;   // offenses are forgotten a day after the last ban
 const BLACK_LIST_LIMIT:i32  = -25;
-const SHARD_FACTOR:usize    = 2;    // 2 shards per worker thread
 const INTERVAL_SECS:u64     = 15;   // 60 second u64 for a duration
-const BASE_GC_WORK:usize    = 1024;
-
-#[derive(Debug,Default)]
-struct GarbageCollector;
-
-impl GarbageCollector {
-    pub fn sweep(&self, shard_lock: &ShardLock) -> Result<()> {
-        let mut locked_shard = shard_lock.inner
-            .lock()
-            .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
-
-        // configure max-work timer
-        let timer = Duration::from_millis(20);
-        let stop_time = Instant::now()
-            .checked_add(timer)
-            .or(Some(Instant::now()))
-            .expect("unreachable after .or()");
-
-        // determine max number of work cycles on the map (heap should be fast, no limits there)
-        let max_work_cycles = match locked_shard.map.len() {
-            0   => return Ok(()), // no work to do
-            1.. => BASE_GC_WORK.max(locked_shard.map.len() / 20) // work on a max of 5% of buckets as the map grows
-        };
-
-        let mut cur_work_cycle: usize = 0;
-
-        while let Some(Reverse(heap_key)) = locked_shard.heap.peek() {
-            let now = Instant::now();
-
-            // extract HeapKey data and drop mutable reference to locked_shard
-            let HeapKey {expires_at,ver,ip} = heap_key.clone();
-
-            if now > expires_at {
-                // remove stale heap entry
-                locked_shard.heap.pop();
-
-                // compare version numbers and remove expired entries
-                if let Some(bucket) = locked_shard.map.get(&ip) {
-                    if ver == bucket.ver() && bucket.is_expired() == BucketStatus::Expired {
-                        locked_shard.map.remove(&ip);
-                    }
-                }
-
-                cur_work_cycle += 1;
-
-            } else {
-                break;
-            }
-
-            // quick work window expiration check
-            if cur_work_cycle >= max_work_cycles || now >= stop_time {
-                break;
-            }
-        }
-        
-        Ok(())
-    }
-}
+const SNAPSHOT_MAGIC:&[u8]   = b"RLSNAP";
+const SNAPSHOT_VERSION:u8   = 1;
 
+/// repeat blacklisting record for a key
 #[derive(Debug)]
-struct Inner {
-    pub map: HashMap<IpAddr,TokenBucket>,
-    pub heap: BinaryHeap<Reverse<HeapKey>>
+struct Offense {
+    count: u32,
+    last: Instant
 }
 
-impl Inner {
-    pub fn new(size: usize) -> Self {
-        let map = HashMap::with_capacity(size);
-        let heap = BinaryHeap::new();
-        Self {
-            map,
-            heap
-        }
-    }
-}
-
-#[derive(Debug)]
-struct ShardLock {
-    pub inner: Mutex<Inner>
-}
+impl Offense {
+    /// each ban doubles the last, capped at a day
+    fn ban_secs(count: u32) -> u64 {
+        let exponent = count.saturating_sub(1);
 
-impl ShardLock {
-    pub fn new(size: usize) -> Self {
-        let unlocked_inner = Inner::new(size);
-        let inner = Mutex::new(unlocked_inner);
+        BLACK_LIST_TIME
+            .checked_shl(exponent)
+            .unwrap_or(MAX_BLACK_LIST_TIME)
+            .min(MAX_BLACK_LIST_TIME)
+    }
 
-        Self {
-            inner
-        }
+    /// offenses older than the ttl no longer count towards escalation
+    fn is_stale(&self, now: Instant) -> bool {
+        now.duration_since(self.last) >= Duration::from_secs(OFFENSE_TTL)
     }
 }
 
+/// rate limiter keyed on K, ip addresses unless another key type is named
 #[derive(Debug)]
-pub struct RateLimiter {
-    shards: Vec<ShardLock>,
-    blacklist:  RwLock<HashMap<IpAddr,Timer>>,
-    whitelist:  RwLock<HashMap<IpAddr,Timer>>,
+pub struct RateLimiter<K: RateLimitKey = IpAddr> {
+    backend: Arc<dyn RateLimitBackend<K>>,
+    blacklist:  RwLock<HashMap<K,Timer>>,
+    whitelist:  RwLock<HashMap<K,Timer>>,
+    offenses:   Mutex<HashMap<K,Offense>>,
+    network_blacklist: RwLock<NetworkTable>,   // cidr ranges, ip keyed limiters only
+    network_whitelist: RwLock<NetworkTable>,
+    ipv4_prefix: u8,
+    ipv6_prefix: u8,
     max_tokens_per_bucket: u32,
-    initial_tokens_per_bucket: u32,
-    base_refill_rate: RefillRate,
-    garbage_collector: GarbageCollector
+    clock: Arc<dyn Clock>,
+    sync_interval: Duration
 }
 
-impl RateLimiter {
+impl<K: RateLimitKey> RateLimiter<K> {
     /// takes the builder and passes back the RateLimiter
-    pub fn new(builder: RateLimitBuilder) -> Self {
-        let garbage_collector = GarbageCollector;
-
-        let shard_number = SHARD_FACTOR * builder.threads;
-        let mut shards: Vec<ShardLock> = Vec::with_capacity(shard_number);
-        
-        // build shard list
-        for _ in 0..shard_number {
-            // clone the pre-configured hashmap
-            let shard = ShardLock::new(100);
-           
-            // push as a shard
-            shards.push(shard);
-        }
+    pub fn new(builder: RateLimitBuilder<K>) -> Self {
+        // pick the backend, in this process unless one is shared
+        let backend: Arc<dyn RateLimitBackend<K>> = match (&builder.backend, &builder.shared_store) {
+            (Some(backend), _) => backend.clone(),
+            (None, Some(store)) => Arc::new(SharedBackend::new(store.clone(), &builder)),
+            (None, None) => Arc::new(LocalBackend::new(&builder))
+        };
 
         // assemble RateLImiter
         RateLimiter {
-            shards,
+            backend,
             blacklist: RwLock::new(builder.blacklist),
             whitelist: RwLock::new(builder.whitelist),
+            offenses: Mutex::new(HashMap::new()),
+            network_blacklist: RwLock::new(NetworkTable::default()),
+            network_whitelist: RwLock::new(NetworkTable::default()),
+            ipv4_prefix: builder.ipv4_prefix,
+            ipv6_prefix: builder.ipv6_prefix,
             max_tokens_per_bucket: builder.bucket_capacity,
-            initial_tokens_per_bucket: builder.initial_tokens_per_bucket,
-            base_refill_rate: builder.refill_rate,
-            garbage_collector
+            clock: builder.clock,
+            sync_interval: builder.sync_interval
         }
     }
 
-    /// provides a mechanism to run garbage collection from outside the main thread
+    /// provides a mechanism to run garbage collection and shared store syncs from outside the main thread
     pub async fn watch(&self) {
-        let time = Duration::from_secs(INTERVAL_SECS);
-        let mut interval = tokio::time::interval(time);
+        let mut interval = tokio::time::interval(self.sync_interval);
+        let mut collected_at = Instant::now();
 
         loop {
             interval.tick().await;
-            self.start_collector();
+
+            if let Err(_e) = self.sync().await {
+                // log here
+            }
+
+            if collected_at.elapsed() >= Duration::from_secs(INTERVAL_SECS) {
+                self.start_collector();
+                collected_at = Instant::now();
+            }
         }
     }
 
+    /// exchanges counts with a shared store, nothing to do for a limiter that isn't sharing one
+    pub async fn sync(&self) -> Result<()> {
+        self.backend.sync().await
+    }
+
     /// starts garbage collector
     fn start_collector(&self) {
-        for idx in 0..self.shards.len() {
-            let _ = self.garbage_collector.sweep(&self.shards[idx]);
-        }
+        self.backend.collect(self.clock.now());
+
+        let _ = self.sweep_lists();
     }
 
-    /// adds a connection to the blacklist
-    fn add_to_blacklist(&self, ip_address: IpAddr, secs: u64) -> Result<()> {
+    /// drops expired black and whitelist entries and forgotten offenses
+    fn sweep_lists(&self) -> Result<()> {
+        let now = self.clock.now();
+
+        self.blacklist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .retain(|_, timer| timer.status_at(now) != TimerStatus::Expired);
+
+        self.whitelist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .retain(|_, timer| timer.status_at(now) != TimerStatus::Expired);
+
+        self.network_blacklist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .sweep(now);
+
+        self.network_whitelist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .sweep(now);
+
+        self.offenses
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .retain(|_, offense| !offense.is_stale(now));
+
+        Ok(())
+    }
+
+    /// adds a key to the blacklist, an expired entry is replaced
+    pub fn add_to_blacklist(&self, key: K, secs: u64) -> Result<()> {
         // begin locked scope
-        let locked_list = &mut self.blacklist
-            .try_write()
+        let mut locked_list = self.blacklist
+            .write()
             .map_err(|_e| RateLimitError::PoisonedBlacklist)?;
 
-        
-        if locked_list.contains_key(&ip_address) {
-            return Err(RateLimitError::DuplicateBlacklistEntry(ip_address))
+        if locked_list.get(&key).is_some_and(|timer| self.is_running(timer)) {
+            return Err(RateLimitError::DuplicateBlacklistEntry(format!("{key:?}")))
         }
 
-        let timer = Timer::new(secs);
-        let _ = locked_list.insert(ip_address, timer);
+        let timer = Timer::starting_at(self.clock.now(), secs);
+        let _ = locked_list.insert(key, timer);
 
         Ok(())
     }
 
-    /// adds a connection to the whitelist
-    pub fn add_to_whitelist(&self, ip_address: IpAddr, secs: u64) -> Result<()> {
+    /// blacklists a key that drained its bucket, each offense within the ttl doubles the ban
+    fn blacklist_offender(&self, key: K) -> Result<()> {
+        let secs = {
+            let now = self.clock.now();
+            let mut locked_offenses = self.offenses
+                .lock()
+                .map_err(|_e| RateLimitError::PoisonedBlacklist)?;
+
+            let offense = locked_offenses
+                .entry(key.clone())
+                .or_insert(Offense { count: 0, last: now });
+
+            if offense.is_stale(now) {
+                offense.count = 0;
+            }
+
+            offense.count = offense.count.saturating_add(1);
+            offense.last = now;
+
+            Offense::ban_secs(offense.count)
+        };
+
+        // a re-offender replaces its expired entry, a concurrent denial may have already banned the key
+        match self.add_to_blacklist(key, secs) {
+            Err(RateLimitError::DuplicateBlacklistEntry(_)) => Ok(()),
+            result => result
+        }
+    }
+
+    /// adds a key to the whitelist, an expired entry is replaced
+    pub fn add_to_whitelist(&self, key: K, secs: u64) -> Result<()> {
         // begin locked scope
         let mut locked_list = self.whitelist
             .write()
             .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?;
 
-        if locked_list.contains_key(&ip_address) {
-            return Err(RateLimitError::DuplicateBlacklistEntry(ip_address))
+        if locked_list.get(&key).is_some_and(|timer| self.is_running(timer)) {
+            return Err(RateLimitError::DuplicateWhitelistEntry(format!("{key:?}")))
         }
 
-        let timer = Timer::new(secs);
-        let _ = locked_list.insert(ip_address, timer);
+        let timer = Timer::starting_at(self.clock.now(), secs);
+        let _ = locked_list.insert(key, timer);
 
         Ok(())
     }
 
-    /// checks the blacklist for an ip
-    fn is_blacklisted(&self, ip_address: IpAddr) -> Result<ListStatus> {
+    /// lifts a key's ban and forgets its offenses, false when it wasn't blacklisted
+    pub fn remove_from_blacklist(&self, key: &K) -> Result<bool> {
+        let _offense = self.offenses
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .remove(key);
+
+        let removed = self.blacklist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .remove(key)
+            .is_some_and(|timer| self.is_running(&timer));
+
+        Ok(removed)
+    }
+
+    /// removes a key from the whitelist, false when it wasn't whitelisted
+    pub fn remove_from_whitelist(&self, key: &K) -> Result<bool> {
+        let removed = self.whitelist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .remove(key)
+            .is_some_and(|timer| self.is_running(&timer));
+
+        Ok(removed)
+    }
+
+    /// active blacklist entries and the time left on each
+    pub fn blacklist(&self) -> Result<Vec<(K,Duration)>> {
+        let now = self.clock.now();
+        let entries = self.blacklist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .iter()
+            .filter(|(_, timer)| timer.status_at(now) != TimerStatus::Expired)
+            .map(|(key, timer)| (key.clone(), timer.remaining_at(now)))
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// active whitelist entries and the time left on each
+    pub fn whitelist(&self) -> Result<Vec<(K,Duration)>> {
+        let now = self.clock.now();
+        let entries = self.whitelist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .iter()
+            .filter(|(_, timer)| timer.status_at(now) != TimerStatus::Expired)
+            .map(|(key, timer)| (key.clone(), timer.remaining_at(now)))
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// checks the blacklist for a key
+    fn is_blacklisted(&self, key: &K) -> Result<ListStatus> {
         // read-lock
         let result = {
             let locked_list = &self.blacklist
@@ -214,15 +279,16 @@ impl RateLimiter {
                 .map_err(|_e| RateLimitError::PoisonedBlacklist)?;
             
             locked_list
-                .contains_key(&ip_address)
+                .get(key)
+                .is_some_and(|timer| self.is_running(timer))
                 .to_blacklist_status()
         };
 
         Ok(result)
     }
 
-    /// checks the whitelist for an ip
-    fn is_whitelisted(&self, ip_address: IpAddr) -> Result<ListStatus> {
+    /// checks the whitelist for a key
+    fn is_whitelisted(&self, key: &K) -> Result<ListStatus> {
         // read-lock
         let result = {
             let locked_list = &self.whitelist
@@ -230,106 +296,454 @@ impl RateLimiter {
                 .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?;
             
             locked_list
-                .contains_key(&ip_address)
+                .get(key)
+                .is_some_and(|timer| self.is_running(timer))
                 .to_whitelist_status()
         };
 
         Ok(result)
     }
 
-    /// hashes an ip address for shard routing
-    fn hash(&self, ip_address: &IpAddr) -> usize {
-        let mut hasher = DefaultHasher::new();
-        let shard_count = self.shards.len();
-        
-        ip_address.hash(&mut hasher);
-        
-        let hash = hasher.finish() as usize;
-        
-        hash % shard_count
+    /// a list entry still counts until its timer runs out on the limiter's clock
+    fn is_running(&self, timer: &Timer) -> bool {
+        timer.status_at(self.clock.now()) != TimerStatus::Expired
     }
 
-    fn create_heap_key(&self, bucket: &TokenBucket, ip_addr: &IpAddr) -> HeapKey {
-        HeapKey {
-            expires_at: bucket.expires_at(),
-            ver: bucket.ver(),
-            ip: *ip_addr
-        }
+    /// time left on a key's blacklist entry
+    fn blacklisted_for(&self, key: &K) -> Result<Duration> {
+        let remaining = self.blacklist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .get(key)
+            .map_or(Duration::ZERO, |timer| timer.remaining_at(self.clock.now()));
+
+        Ok(remaining)
     }
 
-    /// entry point for a connection
-    pub fn try_connect(&self, ip_address: &str) -> Result<Decision> {
-        let ip_address: IpAddr = ip_address.parse()?;
+    /// entry point for a connection on any key
+    pub fn try_connect_key(&self, key: &K) -> Result<Quota> {
+        self.try_connect_key_weighted(key, 1)
+    }
 
+    /// entry point for a connection on any key that spends `cost` tokens
+    pub fn try_connect_key_weighted(&self, key: &K, cost: u32) -> Result<Quota> {
         // early return, no mutex locked
-        if self.is_blacklisted(ip_address)? == ListStatus::Blacklisted {
-            return Ok(Decision::Denied);
+        if self.is_blacklisted(key)? == ListStatus::Blacklisted {
+            let wait = self.blacklisted_for(key)?;
+
+            return Ok(Quota::blacklisted(self.max_tokens_per_bucket, wait));
         }
 
         // early return, no mutex locked
-        if self.is_whitelisted(ip_address)? == ListStatus::Whitelisted {
-            return Ok(Decision::Approved);
+        if self.is_whitelisted(key)? == ListStatus::Whitelisted {
+            return Ok(Quota::whitelisted(self.max_tokens_per_bucket));
         }
 
-        let mut blacklist_flag = ListStatus::None;
+        let (quota, tokens) = self.backend.acquire(key, cost, self.clock.now())?;
 
-        // begin locked scope
-        let decision = {
-            let idx = self.hash(&ip_address);
-            let locked_list = &mut self
-                .shards[idx]
-                .inner
+        // check for blacklist on deny
+        if quota.decision == Decision::Denied && tokens < BLACK_LIST_LIMIT {
+            self.blacklist_offender(key.clone())?;
+        }
+
+        Ok(quota)
+    }
+}
+
+impl RateLimiter<IpAddr> {
+    /// entry point for a connection from an ip address
+    pub fn try_connect(&self, ip_address: &str) -> Result<Quota> {
+        self.try_connect_weighted(ip_address, 1)
+    }
+
+    /// entry point for a connection from an ip address that spends `cost` tokens
+    pub fn try_connect_weighted(&self, ip_address: &str, cost: u32) -> Result<Quota> {
+        let ip_address: IpAddr = ip_address.parse()?;
+
+        self.try_connect_addr(ip_address, cost)
+    }
+
+    /// entry point for a connection from an already parsed ip address that spends `cost` tokens
+    pub fn try_connect_addr(&self, ip_address: IpAddr, cost: u32) -> Result<Quota> {
+        let now = self.clock.now();
+
+        // early return on a banned range, no mutex locked
+        let banned_for = self.network_blacklist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .lookup(&ip_address, now)
+            .map(|timer| timer.remaining_at(now));
+
+        if let Some(wait) = banned_for {
+            return Ok(Quota::blacklisted(self.max_tokens_per_bucket, wait));
+        }
+
+        // early return on a trusted range, no mutex locked
+        let trusted = self.network_whitelist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .lookup(&ip_address, now)
+            .is_some();
+
+        if trusted {
+            return Ok(Quota::whitelisted(self.max_tokens_per_bucket));
+        }
+
+        self.try_connect_key_weighted(&self.aggregate(ip_address), cost)
+    }
+
+    /// prefix an address family is bucketed on
+    fn prefix_for(&self, ip_address: &IpAddr) -> u8 {
+        match ip_address {
+            IpAddr::V4(_) => self.ipv4_prefix,
+            IpAddr::V6(_) => self.ipv6_prefix
+        }
+    }
+
+    /// the key an address is bucketed and automatically banned on
+    pub fn aggregate(&self, ip_address: IpAddr) -> IpAddr {
+        IpNetwork::mask(ip_address, self.prefix_for(&ip_address))
+    }
+
+    /// automatic bans are keyed on the aggregated address, reported as the range they cover
+    fn aggregate_network(&self, ip_address: IpAddr) -> IpNetwork {
+        IpNetwork::new(ip_address, self.prefix_for(&ip_address)).unwrap_or(IpNetwork::host(ip_address))
+    }
+
+    /// bans an address or range, an expired entry is replaced
+    pub fn add_network_to_blacklist(&self, network: IpNetwork, secs: u64) -> Result<()> {
+        let mut locked_table = self.network_blacklist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?;
+
+        if locked_table.get(&network).is_some_and(|timer| self.is_running(timer)) {
+            return Err(RateLimitError::DuplicateBlacklistEntry(network.to_string()));
+        }
+
+        let _ = locked_table.insert(network, Timer::starting_at(self.clock.now(), secs));
+
+        Ok(())
+    }
+
+    /// trusts an address or range, an expired entry is replaced
+    pub fn add_network_to_whitelist(&self, network: IpNetwork, secs: u64) -> Result<()> {
+        let mut locked_table = self.network_whitelist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?;
+
+        if locked_table.get(&network).is_some_and(|timer| self.is_running(timer)) {
+            return Err(RateLimitError::DuplicateWhitelistEntry(network.to_string()));
+        }
+
+        let _ = locked_table.insert(network, Timer::starting_at(self.clock.now(), secs));
+
+        Ok(())
+    }
+
+    /// lifts a range ban, or an automatic ban when the range is the one it covers
+    pub fn remove_network_from_blacklist(&self, network: &IpNetwork) -> Result<bool> {
+        let removed = self.network_blacklist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .remove(network)
+            .is_some_and(|timer| self.is_running(&timer));
+
+        let automatic = self.aggregate_network(network.addr()) == *network
+            && self.remove_from_blacklist(&network.addr())?;
+
+        Ok(removed || automatic)
+    }
+
+    /// removes a trusted range, or a builder supplied entry when the range is the one it covers
+    pub fn remove_network_from_whitelist(&self, network: &IpNetwork) -> Result<bool> {
+        let removed = self.network_whitelist
+            .write()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .remove(network)
+            .is_some_and(|timer| self.is_running(&timer));
+
+        let keyed = self.aggregate_network(network.addr()) == *network
+            && self.remove_from_whitelist(&network.addr())?;
+
+        Ok(removed || keyed)
+    }
+
+    /// banned ranges and automatic bans with the time left on each
+    pub fn network_blacklist(&self) -> Result<Vec<(IpNetwork,Duration)>> {
+        let now = self.clock.now();
+        let mut entries: Vec<(IpNetwork,Duration)> = self.network_blacklist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .active(now)
+            .map(|(network, timer)| (*network, timer.remaining_at(now)))
+            .collect();
+
+        entries.extend(self.blacklist()?
+            .into_iter()
+            .map(|(ip_address, remaining)| (self.aggregate_network(ip_address), remaining)));
+
+        Ok(entries)
+    }
+
+    /// trusted ranges and builder supplied entries with the time left on each
+    pub fn network_whitelist(&self) -> Result<Vec<(IpNetwork,Duration)>> {
+        let now = self.clock.now();
+        let mut entries: Vec<(IpNetwork,Duration)> = self.network_whitelist
+            .read()
+            .map_err(|_e| RateLimitError::PoisonedWhitelistlist)?
+            .active(now)
+            .map(|(network, timer)| (*network, timer.remaining_at(now)))
+            .collect();
+
+        entries.extend(self.whitelist()?
+            .into_iter()
+            .map(|(ip_address, remaining)| (self.aggregate_network(ip_address), remaining)));
+
+        Ok(entries)
+    }
+}
+
+impl<K: RateLimitKey + SnapshotKey> RateLimiter<K> {
+    /// encodes every live bucket, timed list entry and offense into a compact snapshot
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        self.snapshot_at(SystemTime::now())
+    }
+
+    /// snapshot stamped as taken when the wall clock read `wall_now`
+    fn snapshot_at(&self, wall_now: SystemTime) -> Result<Vec<u8>> {
+        let now = self.clock.now();
+        let mut writer = SnapshotWriter::new(now);
+
+        writer.put_bytes(SNAPSHOT_MAGIC);
+        writer.put_u8(SNAPSHOT_VERSION);
+        writer.put_u64(unix_millis(wall_now));
+
+        // buckets, a shared backend keeps them in its store
+        let mut buckets: Vec<(K,Vec<u8>)> = Vec::new();
+        self.backend.export(now, &mut |key, bucket| {
+            let mut state = SnapshotWriter::new(now);
+            bucket.algorithm().write_tag(&mut state);
+            bucket.write_state(&mut state);
+            buckets.push((key.clone(), state.into_bytes()));
+        })?;
+
+        writer.put_len(buckets.len());
+        for (key, state) in buckets {
+            key.write_key(&mut writer);
+            writer.put_bytes(&state);
+        }
+
+        // timed list entries
+        for list in [self.blacklist()?, self.whitelist()?] {
+            writer.put_len(list.len());
+            for (key, remaining) in list {
+                key.write_key(&mut writer);
+                writer.put_remaining(remaining);
+            }
+        }
+
+        let tables = [
+            (&self.network_blacklist, RateLimitError::PoisonedBlacklist),
+            (&self.network_whitelist, RateLimitError::PoisonedWhitelistlist)
+        ];
+
+        for (table, poisoned) in tables {
+            let entries: Vec<(IpNetwork,Duration)> = table
+                .read()
+                .map_err(|_e| poisoned)?
+                .active(now)
+                .map(|(network, timer)| (*network, timer.remaining_at(now)))
+                .collect();
+
+            writer.put_len(entries.len());
+            for (network, remaining) in entries {
+                network.addr().write_key(&mut writer);
+                writer.put_u8(network.prefix());
+                writer.put_remaining(remaining);
+            }
+        }
+
+        // offenses, so re-offenders keep escalating across a restart
+        {
+            let locked_offenses = self.offenses
                 .lock()
-                .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
-
-            // check for existing bucket or create one
-            if let Some(bucket) = locked_list.map.get_mut(&ip_address) {
-                let decision = bucket.drip();
-
-                match decision {
-                    // push success to heap
-                    Decision::Approved => {
-                        let key = self.create_heap_key(bucket, &ip_address);
-                        locked_list.heap.push(Reverse(key));
-                    },
-                    // check for blacklist on deny
-                    Decision::Denied => {
-                        if bucket.tokens() < BLACK_LIST_LIMIT {
-                            blacklist_flag = ListStatus::Blacklisted
-                        }
-                    }
-                };
-
-                decision
-            } else {
-                let bucket = TokenBucket::new()
-                    .with_capacity(self.max_tokens_per_bucket)
-                    .with_initial_tokens(self.initial_tokens_per_bucket)
-                    .with_refill_rate(self.base_refill_rate.clone());
-
-                let key = self.create_heap_key(&bucket, &ip_address);
-                locked_list.heap.push(Reverse(key));
-                locked_list.map.insert(ip_address, bucket);
-
-                Decision::Approved
+                .map_err(|_e| RateLimitError::PoisonedBlacklist)?;
+
+            writer.put_len(locked_offenses.len());
+            for (key, offense) in locked_offenses.iter() {
+                key.write_key(&mut writer);
+                writer.put_u32(offense.count);
+                writer.put_instant(offense.last);
             }
-        };
-        // end locked scope
+        }
+
+        Ok(writer.into_bytes())
+    }
+
+    /// loads a snapshot, buckets pick up the tokens they earned and timers lose the time spent down.
+    /// returns the number of keys and list entries restored
+    pub fn restore(&self, bytes: &[u8]) -> Result<usize> {
+        self.restore_at(bytes, SystemTime::now())
+    }
+
+    /// writes a snapshot to `path`, through a temporary file so a crash never leaves half a snapshot
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = self.snapshot()?;
+        let temporary = path.with_extension("tmp");
+
+        std::fs::write(&temporary, bytes)?;
+        std::fs::rename(&temporary, path)?;
+
+        Ok(())
+    }
+
+    /// restores the snapshot at `path`, nothing to restore when there isn't one
+    pub fn load(&self, path: &Path) -> Result<usize> {
+        match std::fs::read(path) {
+            Ok(bytes) => self.restore(&bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    /// restores a snapshot as if the wall clock read `wall_now`. the whole snapshot is decoded
+    /// before anything is applied, so a corrupt file changes nothing
+    fn restore_at(&self, bytes: &[u8], wall_now: SystemTime) -> Result<usize> {
+        let now = self.clock.now();
+        let mut reader = SnapshotReader::new(bytes, now, Duration::ZERO);
+
+        if reader.get_bytes()? != SNAPSHOT_MAGIC {
+            return Err(RateLimitError::InvalidSnapshot(String::from("not a rate limiter snapshot")));
+        }
+
+        let version = reader.get_u8()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(RateLimitError::InvalidSnapshot(format!("unsupported version {version}")));
+        }
+
+        let saved_at = reader.get_u64()?;
+        reader.set_downtime(Duration::from_millis(unix_millis(wall_now).saturating_sub(saved_at)));
+
+        let mut buckets: Vec<(K,Box<dyn Limiter>)> = Vec::new();
+        for _ in 0..reader.get_len()? {
+            let key = K::read_key(&mut reader)?;
+            let state = reader.get_bytes()?;
+            let mut state_reader = reader.nested(state);
+            let bucket = Algorithm::read_state(&mut state_reader)?;
+
+            if !state_reader.is_empty() {
+                return Err(RateLimitError::InvalidSnapshot(format!("trailing bytes in the state for {key:?}")));
+            }
+
+            buckets.push((key, bucket));
+        }
+
+        let mut lists: [Vec<(K,Duration)>;2] = [Vec::new(), Vec::new()];
+        for list in lists.iter_mut() {
+            for _ in 0..reader.get_len()? {
+                let key = K::read_key(&mut reader)?;
+
+                if let Some(remaining) = reader.get_remaining()? {
+                    list.push((key, remaining));
+                }
+            }
+        }
+
+        let mut tables: [Vec<(IpNetwork,Duration)>;2] = [Vec::new(), Vec::new()];
+        for table in tables.iter_mut() {
+            for _ in 0..reader.get_len()? {
+                let network = IpNetwork::new(IpAddr::read_key(&mut reader)?, reader.get_u8()?)?;
+
+                if let Some(remaining) = reader.get_remaining()? {
+                    table.push((network, remaining));
+                }
+            }
+        }
 
-        if blacklist_flag == ListStatus::Blacklisted {
-            self.add_to_blacklist(ip_address,BLACK_LIST_TIME)?;
+        let mut offenses: Vec<(K,Offense)> = Vec::new();
+        for _ in 0..reader.get_len()? {
+            let key = K::read_key(&mut reader)?;
+            let offense = Offense { count: reader.get_u32()?, last: reader.get_instant()? };
+
+            if !offense.is_stale(now) {
+                offenses.push((key, offense));
+            }
+        }
+
+        if !reader.is_empty() {
+            return Err(RateLimitError::InvalidSnapshot(String::from("trailing bytes after the snapshot")));
+        }
+
+        // decoded, apply
+        let mut restored: usize = 0;
+
+        for (key, bucket) in buckets {
+            // a bucket that would have been collected while the server was down
+            if bucket.is_expired(now) == BucketStatus::Expired {
+                continue;
+            }
+
+            if self.backend.import(key, bucket)? {
+                restored += 1;
+            }
+        }
+
+        let [blacklist, whitelist] = lists;
+        let list_pairs = [
+            (&self.blacklist, blacklist, RateLimitError::PoisonedBlacklist),
+            (&self.whitelist, whitelist, RateLimitError::PoisonedWhitelistlist)
+        ];
+
+        for (list, entries, poisoned) in list_pairs {
+            let mut locked_list = list
+                .write()
+                .map_err(|_e| poisoned)?;
+
+            for (key, remaining) in entries {
+                locked_list.insert(key, Timer::lasting(now, remaining));
+                restored += 1;
+            }
+        }
+
+        let [network_blacklist, network_whitelist] = tables;
+        let table_pairs = [
+            (&self.network_blacklist, network_blacklist, RateLimitError::PoisonedBlacklist),
+            (&self.network_whitelist, network_whitelist, RateLimitError::PoisonedWhitelistlist)
+        ];
+
+        for (table, entries, poisoned) in table_pairs {
+            let mut locked_table = table
+                .write()
+                .map_err(|_e| poisoned)?;
+
+            for (network, remaining) in entries {
+                locked_table.insert(network, Timer::lasting(now, remaining));
+                restored += 1;
+            }
         }
 
-        Ok(decision)
+        self.offenses
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedBlacklist)?
+            .extend(offenses);
+
+        Ok(restored)
     }
 }
 
+/// whole milliseconds since the unix epoch, zero for a clock set before it
+pub(crate) fn unix_millis(time: SystemTime) -> u64 {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    u64::try_from(since_epoch.as_millis()).unwrap_or(u64::MAX)
+}
+
 #[cfg(test)]
 pub mod test {
     use std::{str::FromStr, time::Instant};
     use rand::Rng;
 
-    use crate::enums::RefillRate;
+    use crate::{enums::RefillRate, types::{ManualClock, TokenBucket}};
 
     use super::*;
 
@@ -346,20 +760,298 @@ pub mod test {
         let ip_address = IpAddr::from_str("127.0.0.1").expect("test failed parsing &str to ip address");
 
         rate_limiter.add_to_whitelist(ip_address, 60).expect("test failed adding ip address to whitelist");
-        let decision = rate_limiter.try_connect(&"127.0.0.1").expect("test failed on try_connect()");
+        let decision = rate_limiter.try_connect("127.0.0.1").expect("test failed on try_connect()").decision;
         assert_eq!(decision,Decision::Approved);
 
         rate_limiter.add_to_blacklist(ip_address, 60).expect("test failed adding ip address to blacklist");
-        let decision = rate_limiter.try_connect(&"127.0.0.1").expect("test failed on try_connect()");
+        let decision = rate_limiter.try_connect("127.0.0.1").expect("test failed on try_connect()").decision;
         assert_eq!(decision,Decision::Denied);
         
-        let list_status = rate_limiter.is_whitelisted(ip_address).expect("test failed checking checking for white list status");
+        let list_status = rate_limiter.is_whitelisted(&ip_address).expect("test failed checking checking for white list status");
         assert_eq!(list_status, ListStatus::Whitelisted);
 
-        let list_status = rate_limiter.is_blacklisted(ip_address).expect("test failed checking checking for white list status");
+        let list_status = rate_limiter.is_blacklisted(&ip_address).expect("test failed checking checking for white list status");
         assert_eq!(list_status, ListStatus::Blacklisted);
     }
 
+    /// non-ip keys get their own buckets and lists
+    #[test]
+    fn test_generic_keys() {
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default()
+            .with_refill_rate(RefillRate::PerHour(60.0))
+            .with_tokens_per_bucket(2)
+            .with_bucket_capacity(2)
+            .build();
+
+        // the first connection opens the bucket
+        for _ in 0..3 {
+            assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        }
+
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Denied);
+        assert_eq!(rate_limiter.try_connect_key(&2).unwrap().decision, Decision::Approved);
+
+        rate_limiter.add_to_blacklist(2, 60).unwrap();
+        assert_eq!(rate_limiter.try_connect_key(&2).unwrap().decision, Decision::Denied);
+        assert!(matches!(rate_limiter.add_to_blacklist(2, 60), Err(RateLimitError::DuplicateBlacklistEntry(_))));
+
+        rate_limiter.add_to_whitelist(1, 60).unwrap();
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+    }
+
+    /// weighted connections spend their cost, denials cost a single token towards the blacklist
+    #[test]
+    fn test_weighted_connections() {
+        let rate_limiter = RateLimitBuilder::default()
+            .with_refill_rate(RefillRate::PerHour(60.0))
+            .with_tokens_per_bucket(10)
+            .with_bucket_capacity(10)
+            .build();
+
+        // 1 free + 4 paid on open, then 5 of the remaining 6
+        assert_eq!(rate_limiter.try_connect_weighted("127.0.0.1", 5).unwrap().decision, Decision::Approved);
+        assert_eq!(rate_limiter.try_connect_weighted("127.0.0.1", 5).unwrap().decision, Decision::Approved);
+        assert_eq!(rate_limiter.try_connect_weighted("127.0.0.1", 5).unwrap().decision, Decision::Denied);
+        assert_eq!(rate_limiter.try_connect("127.0.0.1").unwrap().decision, Decision::Denied);
+
+        // a heavy denial moves the bucket no closer to the blacklist than a light one
+        let ip_address = IpAddr::from_str("127.0.0.1").unwrap();
+        for _ in 0..(-BLACK_LIST_LIMIT - 3) {
+            assert_eq!(rate_limiter.try_connect_weighted("127.0.0.1", 50).unwrap().decision, Decision::Denied);
+        }
+        assert_eq!(rate_limiter.is_blacklisted(&ip_address).unwrap(), ListStatus::None);
+
+        // an opening request that costs more than a new bucket holds is denied
+        assert_eq!(rate_limiter.try_connect_weighted("127.0.0.2", 20).unwrap().decision, Decision::Denied);
+    }
+
+    /// weighted and unweighted drips agree at a cost of one and refill on the clock
+    #[test]
+    fn test_drip_n() {
+        let clock = ManualClock::new();
+        let mut bucket = TokenBucket::new()
+            .with_capacity(3)
+            .with_initial_tokens(3)
+            .with_refill_rate(RefillRate::PerMinute(3.0))
+            .starting_at(clock.now());
+
+        assert_eq!(bucket.drip_n(2, &clock), Decision::Approved);
+        assert_eq!(bucket.tokens(), 1);
+        assert_eq!(bucket.drip_n(2, &clock), Decision::Denied);
+        assert_eq!(bucket.tokens(), 0);
+        assert_eq!(bucket.drip(&clock), Decision::Denied);
+        assert_eq!(bucket.tokens(), -1);
+
+        // a token every 20 seconds pays off the debt first
+        clock.advance(Duration::from_secs(40));
+        assert_eq!(bucket.drip(&clock), Decision::Approved);
+        assert_eq!(bucket.tokens(), 0);
+    }
+
+    /// connections report the budget left, denials say how long until the cost is affordable
+    #[test]
+    fn test_quota() {
+        let rate_limiter = RateLimitBuilder::default()
+            .with_refill_rate(RefillRate::PerMinute(6.0))
+            .with_tokens_per_bucket(2)
+            .with_bucket_capacity(2)
+            .build();
+
+        // opening connection is free, the bucket starts full
+        let quota = rate_limiter.try_connect("127.0.0.1").unwrap();
+        assert_eq!(quota.decision, Decision::Approved);
+        assert_eq!(quota.limit, 2);
+        assert_eq!(quota.remaining, 2);
+        assert_eq!(quota.reset, Duration::ZERO);
+
+        rate_limiter.try_connect("127.0.0.1").unwrap();
+        let quota = rate_limiter.try_connect("127.0.0.1").unwrap();
+        assert_eq!(quota.remaining, 0);
+        assert!(quota.reset > Duration::from_secs(9) && quota.reset <= Duration::from_secs(10));
+        assert_eq!(quota.retry_after, Duration::ZERO);
+
+        // the denial still spends a token, so a cost of two is three tokens away
+        let quota = rate_limiter.try_connect_weighted("127.0.0.1", 2).unwrap();
+        assert_eq!(quota.decision, Decision::Denied);
+        assert!(quota.retry_after > Duration::from_secs(29) && quota.retry_after <= Duration::from_secs(30));
+
+        // blacklisted keys wait out the blacklist
+        let ip_address = IpAddr::from_str("127.0.0.2").unwrap();
+        rate_limiter.add_to_blacklist(ip_address, 60).unwrap();
+        let quota = rate_limiter.try_connect("127.0.0.2").unwrap();
+        assert_eq!(quota.decision, Decision::Denied);
+        assert!(quota.retry_after > Duration::from_secs(59));
+    }
+
+    /// expired entries stop matching, get swept, and can be re-added
+    #[test]
+    fn test_list_timers() {
+        let clock = Arc::new(ManualClock::new());
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default()
+            .with_clock(clock.clone())
+            .build();
+
+        rate_limiter.add_to_blacklist(1, 60).unwrap();
+        rate_limiter.add_to_whitelist(2, 60).unwrap();
+        clock.advance(Duration::from_secs(61));
+
+        assert_eq!(rate_limiter.is_blacklisted(&1).unwrap(), ListStatus::None);
+        assert_eq!(rate_limiter.is_whitelisted(&2).unwrap(), ListStatus::None);
+
+        rate_limiter.add_to_blacklist(3, 60).unwrap();
+        rate_limiter.sweep_lists().unwrap();
+        assert_eq!(rate_limiter.blacklist.read().unwrap().len(), 1);
+        assert_eq!(rate_limiter.whitelist.read().unwrap().len(), 0);
+
+        rate_limiter.add_to_blacklist(1, 60).unwrap();
+        assert_eq!(rate_limiter.is_blacklisted(&1).unwrap(), ListStatus::Blacklisted);
+        assert!(matches!(rate_limiter.add_to_whitelist(1, 60).and(rate_limiter.add_to_whitelist(1, 60)), Err(RateLimitError::DuplicateWhitelistEntry(_))));
+    }
+
+    /// re-offenders are banned for longer each time instead of erroring
+    #[test]
+    fn test_escalating_bans() {
+        assert_eq!(Offense::ban_secs(1), BLACK_LIST_TIME);
+        assert_eq!(Offense::ban_secs(2), BLACK_LIST_TIME * 2);
+        assert_eq!(Offense::ban_secs(3), BLACK_LIST_TIME * 4);
+        assert_eq!(Offense::ban_secs(40), MAX_BLACK_LIST_TIME);
+
+        let clock = Arc::new(ManualClock::new());
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default()
+            .with_clock(clock.clone())
+            .build();
+
+        rate_limiter.blacklist_offender(1).unwrap();
+        assert_eq!(rate_limiter.blacklisted_for(&1).unwrap(), Duration::from_secs(BLACK_LIST_TIME));
+
+        // ban runs out, the next offense lands on top of the expired entry
+        clock.advance(Duration::from_secs(BLACK_LIST_TIME + 1));
+        rate_limiter.blacklist_offender(1).unwrap();
+        assert_eq!(rate_limiter.blacklisted_for(&1).unwrap(), Duration::from_secs(BLACK_LIST_TIME * 2));
+
+        // offenses are forgotten after the ttl
+        clock.advance(Duration::from_secs(OFFENSE_TTL));
+        rate_limiter.sweep_lists().unwrap();
+        rate_limiter.blacklist_offender(1).unwrap();
+        assert_eq!(rate_limiter.blacklisted_for(&1).unwrap(), Duration::from_secs(BLACK_LIST_TIME));
+    }
+
+    /// lists report active entries only and removals lift them
+    #[test]
+    fn test_list_management() {
+        let clock = Arc::new(ManualClock::new());
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default()
+            .with_clock(clock.clone())
+            .build();
+
+        rate_limiter.add_to_blacklist(1, 120).unwrap();
+        rate_limiter.add_to_blacklist(2, 30).unwrap();
+        rate_limiter.add_to_whitelist(3, 120).unwrap();
+        clock.advance(Duration::from_secs(60));
+
+        let blacklist = rate_limiter.blacklist().unwrap();
+        assert_eq!(blacklist.len(), 1);
+        assert_eq!(blacklist[0].0, 1);
+        assert_eq!(blacklist[0].1, Duration::from_secs(60));
+        assert_eq!(rate_limiter.whitelist().unwrap().len(), 1);
+
+        assert!(rate_limiter.remove_from_blacklist(&1).unwrap());
+        assert!(!rate_limiter.remove_from_blacklist(&2).unwrap());
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+
+        assert!(rate_limiter.remove_from_whitelist(&3).unwrap());
+        assert!(!rate_limiter.remove_from_whitelist(&3).unwrap());
+    }
+
+    /// buckets refill and get collected on the limiter's clock, no sleeping required
+    #[test]
+    fn test_manual_clock() {
+        let clock = Arc::new(ManualClock::new());
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default()
+            .with_refill_rate(RefillRate::PerMinute(6.0))
+            .with_tokens_per_bucket(1)
+            .with_bucket_capacity(1)
+            .with_clock(clock.clone())
+            .build();
+
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        let quota = rate_limiter.try_connect_key(&1).unwrap();
+        assert_eq!(quota.decision, Decision::Denied);
+        assert_eq!(quota.retry_after, Duration::from_secs(20));
+
+        // a token lands every 10 seconds, each denial digs one below empty
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Denied);
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(rate_limiter.try_connect_key(&1).unwrap().decision, Decision::Approved);
+
+        // the bucket outlives its ttl and the collector drops it
+        rate_limiter.start_collector();
+        assert_eq!(rate_limiter.backend.keys(), 1);
+
+        clock.advance(Duration::from_secs(121));
+        rate_limiter.start_collector();
+        assert_eq!(rate_limiter.backend.keys(), 0);
+    }
+
+    /// ipv6 clients share a bucket across their /64, ipv4 only when a prefix is set
+    #[test]
+    fn test_prefix_aggregation() {
+        let rate_limiter = RateLimitBuilder::default()
+            .with_refill_rate(RefillRate::PerHour(60.0))
+            .with_tokens_per_bucket(2)
+            .with_bucket_capacity(2)
+            .with_ipv6_prefix(64)
+            .build();
+
+        // the first connection opens the bucket, rotating the interface id doesn't get a new one
+        for suffix in 1..=3 {
+            assert_eq!(rate_limiter.try_connect(&format!("2001:db8::{suffix}")).unwrap().decision, Decision::Approved);
+        }
+        assert_eq!(rate_limiter.try_connect("2001:db8::ffff").unwrap().decision, Decision::Denied);
+        assert_eq!(rate_limiter.try_connect("2001:db8:0:1::1").unwrap().decision, Decision::Approved);
+
+        for _ in 0..3 {
+            assert_eq!(rate_limiter.try_connect("10.0.0.1").unwrap().decision, Decision::Approved);
+        }
+        assert_eq!(rate_limiter.try_connect("10.0.0.2").unwrap().decision, Decision::Approved);
+
+        let ip_address: IpAddr = "2001:db8::1234".parse().unwrap();
+        assert_eq!(rate_limiter.aggregate(ip_address), "2001:db8::".parse::<IpAddr>().unwrap());
+    }
+
+    /// ranges ban and trust every address inside them, a ban beats trust
+    #[test]
+    fn test_network_lists() {
+        let rate_limiter = RateLimitBuilder::default()
+            .with_ipv6_prefix(64)
+            .build();
+
+        rate_limiter.add_network_to_blacklist("10.0.0.0/8".parse().unwrap(), 60).unwrap();
+        rate_limiter.add_network_to_whitelist("10.1.0.0/16".parse().unwrap(), 60).unwrap();
+        assert!(matches!(rate_limiter.add_network_to_blacklist("10.0.0.0/8".parse().unwrap(), 60), Err(RateLimitError::DuplicateBlacklistEntry(_))));
+
+        assert_eq!(rate_limiter.try_connect("10.200.0.1").unwrap().decision, Decision::Denied);
+        assert_eq!(rate_limiter.try_connect("10.1.0.1").unwrap().decision, Decision::Denied);
+        assert_eq!(rate_limiter.try_connect("11.0.0.1").unwrap().decision, Decision::Approved);
+
+        // automatic bans are listed and lifted as the range they cover
+        let ip_address: IpAddr = "2001:db8::".parse().unwrap();
+        rate_limiter.blacklist_offender(ip_address).unwrap();
+        assert_eq!(rate_limiter.try_connect("2001:db8::99").unwrap().decision, Decision::Denied);
+
+        let networks: Vec<String> = rate_limiter.network_blacklist().unwrap().iter().map(|(network, _)| network.to_string()).collect();
+        assert!(networks.contains(&"10.0.0.0/8".to_string()));
+        assert!(networks.contains(&"2001:db8::/64".to_string()));
+
+        assert!(rate_limiter.remove_network_from_blacklist(&"2001:db8::/64".parse().unwrap()).unwrap());
+        assert!(rate_limiter.remove_network_from_blacklist(&"10.0.0.0/8".parse().unwrap()).unwrap());
+        assert!(!rate_limiter.remove_network_from_blacklist(&"10.0.0.0/8".parse().unwrap()).unwrap());
+        assert_eq!(rate_limiter.try_connect("10.200.0.1").unwrap().decision, Decision::Approved);
+        assert_eq!(rate_limiter.try_connect("10.1.0.1").unwrap().decision, Decision::Approved);
+    }
+
     /// stress test successful connections
     #[test]
     fn test_try_connect() {
@@ -390,12 +1082,12 @@ pub mod test {
         ip_addresses.drain().for_each(|(ip,_)| {
             // approved connections
             for _x in 0..tokens_per_bucket+1 {
-                let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()");
+                let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()").decision;
                 assert_eq!(decision,Decision::Approved);
             }
 
             // denied connection
-            let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()");
+            let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()").decision;
             assert_eq!(decision,Decision::Denied);
         });
         let b = Instant::now();
@@ -441,7 +1133,7 @@ pub mod test {
 
         let a = Instant::now();
         denied_connections.drain().for_each(|(ip,_)| {
-            let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()");
+            let decision = rate_limiter.try_connect(&ip).expect("test failed on try_connect()").decision;
             assert_eq!(decision,Decision::Denied);
         });
         let b = Instant::now();
@@ -449,4 +1141,78 @@ pub mod test {
         let c = b - a;
         println!("\n{} miliseconds elapsed during blacklist test.\n", c.as_millis());
     }
+
+    /// buckets, timers and offenses survive a restart, earning and losing the time spent down
+    #[test]
+    fn test_snapshot_round_trip() {
+        let build = |clock: Arc<ManualClock>| -> RateLimiter<i64> {
+            RateLimitBuilder::default()
+                .with_refill_rate(RefillRate::PerMinute(6.0))
+                .with_tokens_per_bucket(2)
+                .with_bucket_capacity(2)
+                .with_clock(clock)
+                .build()
+        };
+
+        let rate_limiter = build(Arc::new(ManualClock::new()));
+        for _ in 0..3 {
+            rate_limiter.try_connect_key(&1).unwrap();
+        }
+        rate_limiter.add_to_blacklist(2, 60).unwrap();
+        rate_limiter.add_to_whitelist(3, 20).unwrap();
+        rate_limiter.blacklist_offender(4).unwrap();
+
+        let stopped_at = SystemTime::now();
+        let bytes = rate_limiter.snapshot_at(stopped_at).unwrap();
+
+        // restart 30 seconds later
+        let clock = Arc::new(ManualClock::new());
+        let restored = build(clock.clone());
+        assert_eq!(restored.restore_at(&bytes, stopped_at + Duration::from_secs(30)).unwrap(), 3);
+
+        // three tokens earned while down, capped at two
+        assert_eq!(restored.try_connect_key(&1).unwrap().remaining, 1);
+        assert_eq!(restored.blacklisted_for(&2).unwrap(), Duration::from_secs(30));
+        assert_eq!(restored.is_whitelisted(&3).unwrap(), ListStatus::None);
+
+        // the re-offender escalates instead of starting over
+        clock.advance(Duration::from_secs(31));
+        restored.blacklist_offender(4).unwrap();
+        assert_eq!(restored.blacklisted_for(&4).unwrap(), Duration::from_secs(BLACK_LIST_TIME * 2));
+    }
+
+    /// every algorithm reads back the state it wrote and corrupt snapshots change nothing
+    #[test]
+    fn test_snapshot_algorithms() {
+        let now = Instant::now();
+
+        for algorithm in [Algorithm::TokenBucket, Algorithm::Gcra, Algorithm::SlidingWindow] {
+            let mut bucket = algorithm.build(4, 4, &RefillRate::PerMinute(6.0), now);
+            bucket.acquire(3, now);
+
+            let mut writer = SnapshotWriter::new(now);
+            bucket.algorithm().write_tag(&mut writer);
+            bucket.write_state(&mut writer);
+
+            let bytes = writer.into_bytes();
+            let mut reader = SnapshotReader::new(&bytes, now, Duration::ZERO);
+            let restored = Algorithm::read_state(&mut reader).unwrap();
+
+            assert!(reader.is_empty());
+            assert_eq!(restored.algorithm(), algorithm);
+            assert_eq!(restored.tokens(), bucket.tokens(), "{algorithm:?}");
+            assert_eq!(restored.time_until(4, now), bucket.time_until(4, now), "{algorithm:?}");
+        }
+
+        let rate_limiter: RateLimiter<i64> = RateLimitBuilder::default().build();
+        rate_limiter.try_connect_key(&1).unwrap();
+        rate_limiter.add_to_blacklist(2, 60).unwrap();
+        let bytes = rate_limiter.snapshot().unwrap();
+
+        let restored: RateLimiter<i64> = RateLimitBuilder::default().build();
+        assert!(matches!(restored.restore(&bytes[..bytes.len() - 1]), Err(RateLimitError::InvalidSnapshot(_))));
+        assert!(matches!(restored.restore(b"not a snapshot"), Err(RateLimitError::InvalidSnapshot(_))));
+        assert_eq!(restored.is_blacklisted(&2).unwrap(), ListStatus::None);
+        assert_eq!(restored.restore(&bytes).unwrap(), 2);
+    }
 }
\ No newline at end of file
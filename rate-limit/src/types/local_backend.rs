@@ -0,0 +1,252 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::{DefaultHasher, HashMap}, BinaryHeap},
+    hash::Hasher,
+    sync::Mutex,
+    time::{Duration, Instant}
+};
+
+use crate::{
+    enums::{Algorithm, BucketStatus, Decision, RateLimitError, RefillRate},
+    traits::{Limiter, RateLimitBackend, RateLimitKey},
+    types::{HeapKey, Quota, RateLimitBuilder}
+};
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+const SHARD_FACTOR:usize    = 2;    // 2 shards per worker thread
+const BASE_GC_WORK:usize    = 1024;
+
+#[derive(Debug,Default)]
+struct GarbageCollector;
+
+impl GarbageCollector {
+    pub fn sweep<K: RateLimitKey>(&self, shard_lock: &ShardLock<K>, now: Instant) -> Result<()> {
+        let mut locked_shard = shard_lock.inner
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+        // configure max-work timer, the work budget is real time while expiry follows the clock
+        let timer = Duration::from_millis(20);
+        let stop_time = Instant::now()
+            .checked_add(timer)
+            .or(Some(Instant::now()))
+            .expect("unreachable after .or()");
+
+        // determine max number of work cycles on the map (heap should be fast, no limits there)
+        let max_work_cycles = match locked_shard.map.len() {
+            0   => return Ok(()), // no work to do
+            1.. => BASE_GC_WORK.max(locked_shard.map.len() / 20) // work on a max of 5% of buckets as the map grows
+        };
+
+        let mut cur_work_cycle: usize = 0;
+
+        while let Some(Reverse(heap_key)) = locked_shard.heap.peek() {
+
+            // extract HeapKey data and drop mutable reference to locked_shard
+            let HeapKey {expires_at,ver,key} = heap_key.clone();
+
+            if now > expires_at {
+                // remove stale heap entry
+                locked_shard.heap.pop();
+
+                // compare version numbers and remove expired entries
+                if let Some(bucket) = locked_shard.map.get(&key)
+                    && ver == bucket.ver()
+                    && bucket.is_expired(now) == BucketStatus::Expired {
+                    locked_shard.map.remove(&key);
+                }
+
+                cur_work_cycle += 1;
+
+            } else {
+                break;
+            }
+
+            // quick work window expiration check
+            if cur_work_cycle >= max_work_cycles || Instant::now() >= stop_time {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Inner<K> {
+    pub map: HashMap<K,Box<dyn Limiter>>,
+    pub heap: BinaryHeap<Reverse<HeapKey<K>>>
+}
+
+impl<K: RateLimitKey> Inner<K> {
+    pub fn new(size: usize) -> Self {
+        let map = HashMap::with_capacity(size);
+        let heap = BinaryHeap::new();
+        Self {
+            map,
+            heap
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ShardLock<K> {
+    pub inner: Mutex<Inner<K>>
+}
+
+impl<K: RateLimitKey> ShardLock<K> {
+    pub fn new(size: usize) -> Self {
+        let unlocked_inner = Inner::new(size);
+        let inner = Mutex::new(unlocked_inner);
+
+        Self {
+            inner
+        }
+    }
+}
+
+/// keeps every key's limiter in a sharded map in this process, each server instance has its own budget
+#[derive(Debug)]
+pub struct LocalBackend<K: RateLimitKey> {
+    shards: Vec<ShardLock<K>>,
+    max_tokens_per_bucket: u32,
+    initial_tokens_per_bucket: u32,
+    base_refill_rate: RefillRate,
+    algorithm: Algorithm,
+    garbage_collector: GarbageCollector
+}
+
+impl<K: RateLimitKey> LocalBackend<K> {
+    /// takes the limiter settings off the builder
+    pub fn new(builder: &RateLimitBuilder<K>) -> Self {
+        let shard_number = SHARD_FACTOR * builder.threads;
+        let mut shards: Vec<ShardLock<K>> = Vec::with_capacity(shard_number);
+
+        // build shard list
+        for _ in 0..shard_number {
+            // clone the pre-configured hashmap
+            let shard = ShardLock::new(100);
+
+            // push as a shard
+            shards.push(shard);
+        }
+
+        LocalBackend {
+            shards,
+            max_tokens_per_bucket: builder.bucket_capacity,
+            initial_tokens_per_bucket: builder.initial_tokens_per_bucket,
+            base_refill_rate: builder.refill_rate.clone(),
+            algorithm: builder.algorithm,
+            garbage_collector: GarbageCollector
+        }
+    }
+
+    /// hashes a key for shard routing
+    fn hash(&self, key: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+        let shard_count = self.shards.len();
+
+        key.hash(&mut hasher);
+
+        let hash = hasher.finish() as usize;
+
+        hash % shard_count
+    }
+
+    fn create_heap_key(&self, bucket: &dyn Limiter, key: &K) -> HeapKey<K> {
+        HeapKey {
+            expires_at: bucket.expires_at(),
+            ver: bucket.ver(),
+            key: key.clone()
+        }
+    }
+}
+
+impl<K: RateLimitKey> RateLimitBackend<K> for LocalBackend<K> {
+    fn acquire(&self, key: &K, cost: u32, now: Instant) -> Result<(Quota,i32)> {
+        // begin locked scope
+        let idx = self.hash(key);
+        let locked_list = &mut self
+            .shards[idx]
+            .inner
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+        // check for existing bucket or create one
+        if let Some(bucket) = locked_list.map.get_mut(key) {
+            let decision = bucket.acquire(cost, now);
+            let quota = Quota::from_bucket(decision, bucket.as_ref(), cost, now);
+            let tokens = bucket.tokens();
+
+            // push success to heap
+            if quota.decision == Decision::Approved {
+                let heap_key = self.create_heap_key(bucket.as_ref(), key);
+                locked_list.heap.push(Reverse(heap_key));
+            }
+
+            Ok((quota, tokens))
+        } else {
+            let mut bucket = self.algorithm.build(
+                self.max_tokens_per_bucket,
+                self.initial_tokens_per_bucket,
+                &self.base_refill_rate,
+                now
+            );
+
+            let decision = bucket.open(cost, now);
+            let quota = Quota::from_bucket(decision, bucket.as_ref(), cost, now);
+            let tokens = bucket.tokens();
+
+            let heap_key = self.create_heap_key(bucket.as_ref(), key);
+            locked_list.heap.push(Reverse(heap_key));
+            locked_list.map.insert(key.clone(), bucket);
+
+            Ok((quota, tokens))
+        }
+    }
+
+    fn collect(&self, now: Instant) {
+        for shard in &self.shards {
+            let _ = self.garbage_collector.sweep(shard, now);
+        }
+    }
+
+    fn keys(&self) -> usize {
+        self.shards
+            .iter()
+            .filter_map(|shard| shard.inner.lock().ok())
+            .map(|locked_shard| locked_shard.map.len())
+            .sum()
+    }
+
+    /// one shard locked at a time
+    fn export(&self, now: Instant, visit: &mut dyn FnMut(&K, &dyn Limiter)) -> Result<()> {
+        for shard in &self.shards {
+            let locked_shard = shard.inner
+                .lock()
+                .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+            for (key, bucket) in locked_shard.map.iter() {
+                if bucket.is_expired(now) == BucketStatus::NotExpired {
+                    visit(key, bucket.as_ref());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn import(&self, key: K, bucket: Box<dyn Limiter>) -> Result<bool> {
+        let heap_key = self.create_heap_key(bucket.as_ref(), &key);
+        let mut locked_shard = self.shards[self.hash(&key)]
+            .inner
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+        locked_shard.heap.push(Reverse(heap_key));
+        locked_shard.map.insert(key, bucket);
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant}
+};
+
+use crate::traits::Clock;
+
+/// clock that only moves when told to, lets tests step through time without sleeping
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<Instant>
+}
+
+impl ManualClock {
+    /// constructor, stopped at the moment it was built
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    /// constructor, stopped at `now`
+    pub fn starting_at(now: Instant) -> Self {
+        ManualClock {
+            now: Mutex::new(now)
+        }
+    }
+
+    /// moves the clock forward
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(PoisonError::into_inner);
+        *now += duration;
+    }
+
+    /// moves the clock to `now`
+    pub fn set(&self, now: Instant) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    enums::RateLimitError,
+    traits::{RateLimitKey, SharedStore, StoreFuture}
+};
+
+/// shared store held in memory, stands in for a database when several limiters in one process play
+/// the part of separate server instances
+#[derive(Debug)]
+pub struct MemoryStore<K: RateLimitKey> {
+    counts: Mutex<HashMap<(u64,K),u32>>
+}
+
+impl<K: RateLimitKey> MemoryStore<K> {
+    /// constructor, starts empty
+    pub fn new() -> Self {
+        MemoryStore {
+            counts: Mutex::new(HashMap::new())
+        }
+    }
+
+    /// the total for a key in `window`
+    pub fn total(&self, window: u64, key: &K) -> u32 {
+        self.counts
+            .lock()
+            .map_or(0, |locked_counts| locked_counts.get(&(window, key.clone())).copied().unwrap_or(0))
+    }
+}
+
+impl<K: RateLimitKey> Default for MemoryStore<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: RateLimitKey> SharedStore<K> for MemoryStore<K> {
+    fn add(&self, window: u64, counts: Vec<(K,u32)>) -> StoreFuture<'_, Vec<(K,u32)>> {
+        Box::pin(async move {
+            let mut locked_counts = self.counts
+                .lock()
+                .map_err(|_e| RateLimitError::SharedStore(String::from("memory store poisoned")))?;
+
+            let totals = counts
+                .into_iter()
+                .map(|(key, count)| {
+                    let total = locked_counts.entry((window, key.clone())).or_insert(0);
+                    *total = total.saturating_add(count);
+
+                    (key, *total)
+                })
+                .collect();
+
+            Ok(totals)
+        })
+    }
+
+    fn expire(&self, window: u64) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            self.counts
+                .lock()
+                .map_err(|_e| RateLimitError::SharedStore(String::from("memory store poisoned")))?
+                .retain(|(counted_in, _), _| *counted_in >= window);
+
+            Ok(())
+        })
+    }
+}
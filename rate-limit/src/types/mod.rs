@@ -1,11 +1,35 @@
+mod gcra;
 mod heap_key;
+mod ip_network;
 mod limiter;
+mod local_backend;
+mod manual_clock;
+mod memory_store;
+mod network_table;
+mod quota;
 mod token_bucket;
 mod rate_limit_builder;
+mod shared_backend;
+mod sliding_window;
+mod snapshot_reader;
+mod snapshot_writer;
+mod system_clock;
 mod timer;
 
+pub use gcra::Gcra;
 pub use heap_key::HeapKey;
+pub use ip_network::IpNetwork;
 pub use limiter::RateLimiter;
+pub use local_backend::LocalBackend;
+pub use manual_clock::ManualClock;
+pub use memory_store::MemoryStore;
+pub use network_table::NetworkTable;
+pub use quota::Quota;
 pub use rate_limit_builder::RateLimitBuilder;
+pub use shared_backend::SharedBackend;
+pub use sliding_window::SlidingWindow;
+pub use snapshot_reader::SnapshotReader;
+pub use snapshot_writer::SnapshotWriter;
+pub use system_clock::SystemClock;
 pub use token_bucket::TokenBucket;
 pub use timer::Timer;
\ No newline at end of file
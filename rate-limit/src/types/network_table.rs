@@ -0,0 +1,135 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::IpAddr,
+    time::Instant
+};
+
+use crate::{
+    enums::TimerStatus,
+    types::{IpNetwork, Timer}
+};
+
+/// timed ip ranges keyed by network, a lookup masks the address once per prefix length in use
+#[derive(Clone,Debug,Default)]
+pub struct NetworkTable {
+    entries: HashMap<IpNetwork,Timer>,
+    v4_prefixes: BTreeMap<u8,usize>,    // prefix length -> entries using it
+    v6_prefixes: BTreeMap<u8,usize>
+}
+
+impl NetworkTable {
+    /// prefix lengths in use for an address family
+    fn prefixes(&mut self, network: &IpNetwork) -> &mut BTreeMap<u8,usize> {
+        match network.addr() {
+            IpAddr::V4(_) => &mut self.v4_prefixes,
+            IpAddr::V6(_) => &mut self.v6_prefixes
+        }
+    }
+
+    /// adds or replaces a range
+    pub fn insert(&mut self, network: IpNetwork, timer: Timer) -> Option<Timer> {
+        let replaced = self.entries.insert(network, timer);
+
+        if replaced.is_none() {
+            *self.prefixes(&network).entry(network.prefix()).or_insert(0) += 1;
+        }
+
+        replaced
+    }
+
+    /// removes a range
+    pub fn remove(&mut self, network: &IpNetwork) -> Option<Timer> {
+        let removed = self.entries.remove(network);
+
+        if removed.is_some() {
+            let prefixes = self.prefixes(network);
+
+            if let Some(count) = prefixes.get_mut(&network.prefix()) {
+                *count -= 1;
+
+                if *count == 0 {
+                    prefixes.remove(&network.prefix());
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// exact range getter
+    pub fn get(&self, network: &IpNetwork) -> Option<&Timer> {
+        self.entries.get(network)
+    }
+
+    /// the most specific range holding an address that is unexpired at `now`
+    pub fn lookup(&self, addr: &IpAddr, now: Instant) -> Option<&Timer> {
+        let prefixes = match addr {
+            IpAddr::V4(_) => &self.v4_prefixes,
+            IpAddr::V6(_) => &self.v6_prefixes
+        };
+
+        prefixes
+            .keys()
+            .rev()
+            .filter_map(|prefix| IpNetwork::new(*addr, *prefix).ok())
+            .filter_map(|network| self.entries.get(&network))
+            .find(|timer| timer.status_at(now) != TimerStatus::Expired)
+    }
+
+    /// drops ranges expired at `now`
+    pub fn sweep(&mut self, now: Instant) {
+        let expired: Vec<IpNetwork> = self.entries
+            .iter()
+            .filter(|(_, timer)| timer.status_at(now) == TimerStatus::Expired)
+            .map(|(network, _)| *network)
+            .collect();
+
+        for network in expired {
+            self.remove(&network);
+        }
+    }
+
+    /// ranges unexpired at `now`
+    pub fn active(&self, now: Instant) -> impl Iterator<Item = (&IpNetwork,&Timer)> {
+        self.entries
+            .iter()
+            .filter(move |(_, timer)| timer.status_at(now) != TimerStatus::Expired)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// the longest unexpired range wins and removals keep the prefix index in step
+    #[test]
+    fn network_lookup() {
+        let mut table = NetworkTable::default();
+        let wide: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        let narrow: IpNetwork = "10.1.0.0/16".parse().unwrap();
+        let now = Instant::now();
+        let later = now + Duration::from_secs(61);
+
+        table.insert(wide, Timer::starting_at(now, 120));
+        table.insert(narrow, Timer::starting_at(now, 240));
+
+        let addr: IpAddr = "10.1.2.3".parse().unwrap();
+        assert_eq!(table.lookup(&addr, now).unwrap().remaining_at(now), Duration::from_secs(240));
+        assert!(table.lookup(&"10.2.0.1".parse().unwrap(), now).is_some());
+        assert!(table.lookup(&"11.0.0.1".parse().unwrap(), now).is_none());
+
+        table.remove(&narrow);
+        assert_eq!(table.lookup(&addr, now).unwrap().remaining_at(now), Duration::from_secs(120));
+        assert!(!table.v4_prefixes.contains_key(&16));
+
+        // the narrow range runs out first, the wide one shows through
+        table.insert(narrow, Timer::starting_at(now, 60));
+        assert_eq!(table.lookup(&addr, later).unwrap().remaining_at(later), Duration::from_secs(59));
+
+        table.sweep(later);
+        assert_eq!(table.active(later).count(), 1);
+        assert!(table.get(&narrow).is_none());
+    }
+}
\ No newline at end of file
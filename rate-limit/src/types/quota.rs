@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+use crate::{enums::Decision, traits::Limiter};
+
+/// outcome of a connection attempt along with what's left of the key's budget
+#[derive(Clone,Debug,PartialEq)]
+pub struct Quota {
+    pub decision: Decision,
+    pub limit: u32,             // bucket capacity
+    pub remaining: u32,         // tokens left after this connection
+    pub reset: Duration,        // time until the next token is added, zero on a full bucket
+    pub retry_after: Duration   // time until the connection's cost is affordable, zero when approved
+}
+
+impl Quota {
+    /// a banned key has nothing left until the ban runs out
+    pub fn blacklisted(limit: u32, wait: Duration) -> Self {
+        Quota {
+            decision: Decision::Denied,
+            limit,
+            remaining: 0,
+            reset: wait,
+            retry_after: wait
+        }
+    }
+
+    /// a whitelisted key always has a full bucket
+    pub fn whitelisted(limit: u32) -> Self {
+        Quota {
+            decision: Decision::Approved,
+            limit,
+            remaining: limit,
+            reset: Duration::ZERO,
+            retry_after: Duration::ZERO
+        }
+    }
+
+    /// reads the budget off a key's limiter the connection has just been admitted or denied by
+    pub fn from_bucket(decision: Decision, bucket: &dyn Limiter, cost: u32, now: Instant) -> Self {
+        let retry_after = match decision {
+            Decision::Approved => Duration::ZERO,
+            Decision::Denied => bucket.time_until(cost.max(1).min(i32::MAX as u32) as i32, now)
+        };
+
+        Quota {
+            decision,
+            limit: bucket.capacity(),
+            remaining: bucket.tokens().max(0) as u32,
+            reset: bucket.time_until(bucket.tokens().saturating_add(1), now),
+            retry_after
+        }
+    }
+}
@@ -1,19 +1,29 @@
-use std::{collections::HashMap, net::IpAddr};
+use std::{collections::HashMap, net::IpAddr, sync::Arc, time::Duration};
 
-use crate::{enums::RefillRate, types::{RateLimiter,Timer,TokenBucket}};
+use crate::{enums::{Algorithm,RefillRate}, traits::{Clock,RateLimitBackend,RateLimitKey,SharedStore}, types::{RateLimiter,SystemClock,Timer,TokenBucket}};
 
+const SYNC_INTERVAL_MILLIS:u64 = 1_000;
+
+/// builds a RateLimiter keyed on K, ip addresses unless another key type is named
 #[derive(Clone,Debug)]
-pub struct RateLimitBuilder {
-    pub map: HashMap<IpAddr,TokenBucket>,            /// collection of ip addresses and associated connection data
+pub struct RateLimitBuilder<K: RateLimitKey = IpAddr> {
+    pub map: HashMap<K,TokenBucket>,            /// collection of keys and associated connection data
     pub bucket_capacity: u32,
     pub initial_tokens_per_bucket: u32,
     pub refill_rate: RefillRate,
-    pub blacklist: HashMap<IpAddr,Timer>,
-    pub whitelist: HashMap<IpAddr,Timer>,
-    pub threads: usize
+    pub blacklist: HashMap<K,Timer>,
+    pub whitelist: HashMap<K,Timer>,
+    pub threads: usize,
+    pub algorithm: Algorithm,                   // admission algorithm used for every key
+    pub clock: Arc<dyn Clock>,                  // time source for buckets, list timers and the garbage collector
+    pub backend: Option<Arc<dyn RateLimitBackend<K>>>,  // keeps each key's state, built from the settings here when unset
+    pub shared_store: Option<Arc<dyn SharedStore<K>>>,  // counts keys against a store shared between server instances
+    pub sync_interval: Duration,                // time between exchanges with a shared store
+    pub ipv4_prefix: u8,                        // ip keyed limiters bucket ipv4 addresses on this prefix
+    pub ipv6_prefix: u8                         // ip keyed limiters bucket ipv6 addresses on this prefix
 }
 
-impl RateLimitBuilder {
+impl<K: RateLimitKey> RateLimitBuilder<K> {
     /// constructor
     pub fn new(
         default_map_size: usize,
@@ -23,11 +33,11 @@ impl RateLimitBuilder {
         threads: usize
     ) -> Self {
         // default settings
-        let whitelist: HashMap<IpAddr,Timer> = HashMap::new();
-        let blacklist: HashMap<IpAddr,Timer> = HashMap::new();
+        let whitelist: HashMap<K,Timer> = HashMap::new();
+        let blacklist: HashMap<K,Timer> = HashMap::new();
 
         // allocate and move into mutex
-        let map: HashMap<IpAddr, TokenBucket> = HashMap::with_capacity(default_map_size);
+        let map: HashMap<K, TokenBucket> = HashMap::with_capacity(default_map_size);
 
         RateLimitBuilder {
             map,
@@ -36,7 +46,14 @@ impl RateLimitBuilder {
             refill_rate,
             blacklist,
             whitelist,
-            threads
+            threads,
+            algorithm: Algorithm::default(),
+            clock: Arc::new(SystemClock),
+            backend: None,
+            shared_store: None,
+            sync_interval: Duration::from_millis(SYNC_INTERVAL_MILLIS),
+            ipv4_prefix: 32,
+            ipv6_prefix: 128
         }
     }
 
@@ -70,6 +87,36 @@ impl RateLimitBuilder {
         self
     }
 
+    /// set the admission algorithm, token bucket by default
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// set the time source, the system clock by default
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// set a backend for each key's state in place of the one built from these settings
+    pub fn with_backend(mut self, backend: Arc<dyn RateLimitBackend<K>>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// share budgets with every server instance counting against `store`, in this process by default
+    pub fn with_shared_store(mut self, store: Arc<dyn SharedStore<K>>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// set the time between exchanges with a shared store, a second by default
+    pub fn with_sync_interval(mut self, interval: Duration) -> Self {
+        self.sync_interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
     /// the number of threads the server will use to determine shard quantity
     pub fn shard_into(mut self, threads: usize) -> Self {
         self.threads = threads;
@@ -77,12 +124,26 @@ impl RateLimitBuilder {
     }
 
     /// returns the RateLimiter
-    pub fn build(self) -> RateLimiter {
+    pub fn build(self) -> RateLimiter<K> {
         RateLimiter::new(self)
     }
 }
 
-impl Default for RateLimitBuilder {
+impl RateLimitBuilder<IpAddr> {
+    /// buckets ipv4 addresses on a shared prefix, 24 puts a /24 in one bucket
+    pub fn with_ipv4_prefix(mut self, prefix: u8) -> Self {
+        self.ipv4_prefix = prefix.min(32);
+        self
+    }
+
+    /// buckets ipv6 addresses on a shared prefix, 64 stops a client rotating through its /64
+    pub fn with_ipv6_prefix(mut self, prefix: u8) -> Self {
+        self.ipv6_prefix = prefix.min(128);
+        self
+    }
+}
+
+impl<K: RateLimitKey> Default for RateLimitBuilder<K> {
     fn default() -> Self {
         // base settings
         let default_map_size:usize = 100;
@@ -103,16 +164,17 @@ impl Default for RateLimitBuilder {
 
 #[cfg(test)]
 pub mod test {
-    use crate::RateLimitBuilder;
+    use crate::{RateLimitBuilder, RateLimiter};
 
     /// tests the default builder and associated builder functions
     #[test]
     fn builder() {
-        let _default = RateLimitBuilder::default()
+        let _default: RateLimiter = RateLimitBuilder::default()
             .with_initial_capacity(1000)
             .with_tokens_per_bucket(100)
             .with_bucket_capacity(200)
             .with_refill_rate(crate::enums::RefillRate::PerMinute(60.0))
+            .with_algorithm(crate::enums::Algorithm::Gcra)
             .shard_into(4)
             .build();
     }
@@ -0,0 +1,398 @@
+use std::{
+    collections::{hash_map::{DefaultHasher, HashMap}, BTreeMap},
+    hash::Hasher,
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
+    time::{Duration, Instant, SystemTime}
+};
+
+use crate::{
+    enums::RateLimitError,
+    traits::{BackendFuture, Clock, Limiter, RateLimitBackend, RateLimitKey, SharedStore},
+    types::{Quota, RateLimitBuilder, SlidingWindow}
+};
+
+use super::limiter::unix_millis;
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+const SHARD_FACTOR:usize    = 2;    // 2 shards per worker thread
+
+/// this instance's view of a key, every instance's totals as of the last sync plus what it has counted since
+#[derive(Debug)]
+struct Counter {
+    window: u64,            // index of the key's current window
+    previous: u32,          // total in the window before
+    current: u32,           // total in the current window
+    unsent_previous: u32,   // counted here in the window before, not yet added to the store
+    unsent: u32             // counted here in the current window, not yet added to the store
+}
+
+impl Counter {
+    fn new(window: u64) -> Self {
+        Counter {
+            window,
+            previous: 0,
+            current: 0,
+            unsent_previous: 0,
+            unsent: 0
+        }
+    }
+
+    /// moves the counts along to `window`, counts older than the window before no longer matter
+    fn roll(&mut self, window: u64) {
+        match window.saturating_sub(self.window) {
+            0 => return,
+            1 => {
+                self.previous = self.current;
+                self.unsent_previous = self.unsent;
+            },
+            _ => {
+                self.previous = 0;
+                self.unsent_previous = 0;
+            }
+        }
+
+        self.current = 0;
+        self.unsent = 0;
+        self.window = window;
+    }
+}
+
+/// counts keys against a store every server instance shares, so adding instances doesn't add budget.
+/// admissions are decided on a local cache and the counts sent to the store in batches on each sync,
+/// which leaves the hot path free of network calls. instances can overshoot by what they admit between
+/// syncs. windows line up on the unix clock so every instance counts into the same ones, capacity and
+/// refill rate set the window like a sliding window limiter's and initial tokens are ignored
+#[derive(Debug)]
+pub struct SharedBackend<K: RateLimitKey> {
+    store: Arc<dyn SharedStore<K>>,
+    shards: Vec<Mutex<HashMap<K,Counter>>>,
+    limit: u32,
+    window: Duration,       // whole milliseconds so every instance agrees on the boundaries
+    epoch: Instant,         // the clock's reading at `epoch_millis` on the unix clock
+    epoch_millis: u64,
+    clock: Arc<dyn Clock>,
+    expired_before: AtomicU64   // store windows before this one have already been dropped
+}
+
+impl<K: RateLimitKey> SharedBackend<K> {
+    /// takes the limiter settings off the builder, windows are anchored on the unix clock now
+    pub fn new(store: Arc<dyn SharedStore<K>>, builder: &RateLimitBuilder<K>) -> Self {
+        let shard_number = SHARD_FACTOR * builder.threads;
+        let shards = (0..shard_number.max(1))
+            .map(|_| Mutex::new(HashMap::new()))
+            .collect();
+
+        let window = SlidingWindow::length(builder.bucket_capacity, &builder.refill_rate);
+        let window_millis = u64::try_from(window.as_millis()).unwrap_or(u64::MAX).max(1);
+
+        SharedBackend {
+            store,
+            shards,
+            limit: builder.bucket_capacity,
+            window: Duration::from_millis(window_millis),
+            epoch: builder.clock.now(),
+            epoch_millis: unix_millis(SystemTime::now()),
+            clock: builder.clock.clone(),
+            expired_before: AtomicU64::new(0)
+        }
+    }
+
+    /// anchors the windows with the clock reading `epoch` at `epoch_millis` on the unix clock
+    pub fn with_epoch(mut self, epoch: Instant, epoch_millis: u64) -> Self {
+        self.epoch = epoch;
+        self.epoch_millis = epoch_millis;
+        self
+    }
+
+    /// hashes a key for shard routing
+    fn hash(&self, key: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+
+        key.hash(&mut hasher);
+
+        hasher.finish() as usize % self.shards.len()
+    }
+
+    fn window_millis(&self) -> u64 {
+        self.window.as_millis() as u64
+    }
+
+    /// unix milliseconds at `now`
+    fn millis(&self, now: Instant) -> u64 {
+        let after = now.saturating_duration_since(self.epoch).as_millis() as u64;
+        let before = self.epoch.saturating_duration_since(now).as_millis() as u64;
+
+        self.epoch_millis.saturating_add(after).saturating_sub(before)
+    }
+
+    /// the window `now` falls in
+    fn index(&self, now: Instant) -> u64 {
+        self.millis(now) / self.window_millis()
+    }
+
+    /// when a window starts on the clock
+    fn window_start(&self, index: u64) -> Instant {
+        let start = index.saturating_mul(self.window_millis());
+
+        match start.checked_sub(self.epoch_millis) {
+            Some(after) => self.epoch + Duration::from_millis(after),
+            None => self.epoch
+                .checked_sub(Duration::from_millis(self.epoch_millis - start))
+                .unwrap_or(self.epoch)
+        }
+    }
+
+    /// takes every unsent count, grouped by window. both of a key's windows are sent even with
+    /// nothing to add so they pick up what the other instances counted
+    fn drain(&self, index: u64) -> Result<BTreeMap<u64,Vec<(K,u32)>>> {
+        let mut batches: BTreeMap<u64,Vec<(K,u32)>> = BTreeMap::new();
+
+        for shard in &self.shards {
+            let mut locked_shard = shard
+                .lock()
+                .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+            for (key, counter) in locked_shard.iter_mut() {
+                counter.roll(index);
+
+                if counter.window > 0 {
+                    batches.entry(counter.window - 1).or_default().push((key.clone(), counter.unsent_previous));
+                    counter.unsent_previous = 0;
+                }
+
+                batches.entry(counter.window).or_default().push((key.clone(), counter.unsent));
+                counter.unsent = 0;
+            }
+        }
+
+        Ok(batches)
+    }
+
+    /// adds a batch to the store and takes in the totals, a failed batch is kept for the next sync
+    async fn send(&self, window: u64, counts: Vec<(K,u32)>) -> Result<()> {
+        let totals = match self.store.add(window, counts.clone()).await {
+            Ok(totals) => totals,
+            Err(e) => {
+                self.update(window, counts, |counter, count| counter.unsent += count, |counter, count| counter.unsent_previous += count)?;
+                return Err(e);
+            }
+        };
+
+        // totals include what was sent, counts made during the round trip are still unsent
+        self.update(
+            window,
+            totals,
+            |counter, total| counter.current = total.saturating_add(counter.unsent),
+            |counter, total| counter.previous = total.saturating_add(counter.unsent_previous)
+        )
+    }
+
+    /// applies a value per key, to the current count when the key is still in `window` and to the
+    /// previous count when it has moved on to the next one
+    fn update<C, P>(&self, window: u64, values: Vec<(K,u32)>, current: C, previous: P) -> Result<()>
+    where
+        C: Fn(&mut Counter, u32),
+        P: Fn(&mut Counter, u32)
+    {
+        for (key, value) in values {
+            let mut locked_shard = self.shards[self.hash(&key)]
+                .lock()
+                .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+            let Some(counter) = locked_shard.get_mut(&key) else {
+                continue;
+            };
+
+            if counter.window == window {
+                current(counter, value);
+            } else if counter.window == window.saturating_add(1) {
+                previous(counter, value);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: RateLimitKey> RateLimitBackend<K> for SharedBackend<K> {
+    /// a key new to this instance starts from zero until the next sync brings in the other instances' counts
+    fn acquire(&self, key: &K, cost: u32, now: Instant) -> Result<(Quota,i32)> {
+        let index = self.index(now);
+        let window_start = self.window_start(index);
+
+        // begin locked scope
+        let mut locked_shard = self.shards[self.hash(key)]
+            .lock()
+            .map_err(|_e| RateLimitError::PoisonedRateLimiterMap)?;
+
+        let counter = locked_shard
+            .entry(key.clone())
+            .or_insert_with(|| Counter::new(index));
+
+        counter.roll(index);
+
+        let mut window = SlidingWindow::counted(self.limit, self.window, window_start, counter.previous, counter.current);
+        let decision = window.acquire(cost, now);
+        let counted = window.current().saturating_sub(counter.current);
+
+        counter.current = window.current();
+        counter.unsent = counter.unsent.saturating_add(counted);
+
+        Ok((Quota::from_bucket(decision, &window, cost, now), window.tokens()))
+    }
+
+    /// keys whose windows have both slid out
+    fn collect(&self, now: Instant) {
+        let index = self.index(now);
+
+        for shard in &self.shards {
+            if let Ok(mut locked_shard) = shard.lock() {
+                locked_shard.retain(|_, counter| counter.window.saturating_add(1) >= index);
+            }
+        }
+    }
+
+    fn keys(&self) -> usize {
+        self.shards
+            .iter()
+            .filter_map(|shard| shard.lock().ok())
+            .map(|locked_shard| locked_shard.len())
+            .sum()
+    }
+
+    /// sends the unsent counts a batch per window and takes in the totals, then drops store windows
+    /// too old to matter. a failed batch is kept for the next sync
+    fn sync(&self) -> BackendFuture<'_> {
+        Box::pin(async move {
+            let index = self.index(self.clock.now());
+            let mut failure: Option<RateLimitError> = None;
+
+            for (window, counts) in self.drain(index)? {
+                if let Err(e) = self.send(window, counts).await {
+                    failure.get_or_insert(e);
+                }
+            }
+
+            if let Some(e) = failure {
+                return Err(e);
+            }
+
+            let expire_before = index.saturating_sub(1);
+            if self.expired_before.fetch_max(expire_before, Ordering::Relaxed) < expire_before {
+                self.store.expire(expire_before).await?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+
+    use crate::{enums::{Decision, RefillRate}, types::{ManualClock, MemoryStore, RateLimiter}};
+
+    use super::*;
+
+    /// memory store futures are ready on the first poll
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    /// two limiters counting against one store, windows anchored on the same clock
+    fn instances(store: &Arc<MemoryStore<i64>>, clock: &Arc<ManualClock>) -> [(RateLimiter<i64>,Arc<SharedBackend<i64>>);2] {
+        let builder: RateLimitBuilder<i64> = RateLimitBuilder::default()
+            .with_bucket_capacity(4)
+            .with_refill_rate(RefillRate::PerMinute(4.0))
+            .with_clock(clock.clone());
+
+        [(), ()].map(|_| {
+            let backend = Arc::new(SharedBackend::new(store.clone(), &builder).with_epoch(clock.now(), 0));
+            let limiter = builder.clone()
+                .with_backend(backend.clone())
+                .build();
+
+            (limiter, backend)
+        })
+    }
+
+    /// instances share one budget once they've synced
+    #[test]
+    fn shared_budget() {
+        let store = Arc::new(MemoryStore::new());
+        let clock = Arc::new(ManualClock::new());
+        let [(a, _), (b, _)] = instances(&store, &clock);
+
+        for _ in 0..3 {
+            assert_eq!(a.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        }
+        assert_eq!(b.try_connect_key(&1).unwrap().decision, Decision::Approved);
+
+        // neither has sent anything yet
+        assert_eq!(store.total(0, &1), 0);
+
+        block_on(a.sync()).unwrap();
+        block_on(b.sync()).unwrap();
+        block_on(a.sync()).unwrap();
+        assert_eq!(store.total(0, &1), 4);
+
+        // the budget of 4 is spent between them
+        let quota = a.try_connect_key(&1).unwrap();
+        assert_eq!(quota.decision, Decision::Denied);
+        assert_eq!(quota.remaining, 0);
+        assert_eq!(b.try_connect_key(&1).unwrap().decision, Decision::Denied);
+
+        // both windows slide out
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(a.try_connect_key(&1).unwrap().decision, Decision::Approved);
+    }
+
+    /// counts made before a window boundary land in the window they were made in, old windows are dropped
+    #[test]
+    fn shared_windows() {
+        let store = Arc::new(MemoryStore::new());
+        let clock = Arc::new(ManualClock::new());
+        let [(a, backend), (b, _)] = instances(&store, &clock);
+
+        a.try_connect_key(&1).unwrap();
+        a.try_connect_key(&1).unwrap();
+        b.try_connect_key(&1).unwrap();
+
+        // a's counts are still unsent when its key moves on to the next window
+        clock.advance(Duration::from_secs(60));
+        block_on(b.sync()).unwrap();
+        block_on(a.sync()).unwrap();
+        assert_eq!(store.total(0, &1), 3);
+        assert_eq!(store.total(1, &1), 0);
+
+        // half the previous window still overlaps, 1.5 + 3 leaves no room
+        clock.advance(Duration::from_secs(30));
+        for _ in 0..2 {
+            assert_eq!(a.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        }
+        assert_eq!(b.try_connect_key(&1).unwrap().decision, Decision::Approved);
+        block_on(a.sync()).unwrap();
+        block_on(b.sync()).unwrap();
+        assert_eq!(b.try_connect_key(&1).unwrap().decision, Decision::Denied);
+
+        // windows before the previous one are dropped from the store and the cache
+        clock.advance(Duration::from_secs(60));
+        block_on(a.sync()).unwrap();
+        assert_eq!(store.total(0, &1), 0);
+        assert_eq!(store.total(1, &1), 3);   // b's denial is still unsent
+
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(backend.keys(), 1);
+        backend.collect(clock.now());
+        assert_eq!(backend.keys(), 0);
+    }
+}
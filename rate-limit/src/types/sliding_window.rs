@@ -0,0 +1,244 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    enums::{Algorithm, Decision, RateLimitError, RefillRate},
+    traits::Limiter,
+    types::{SnapshotReader, SnapshotWriter}
+};
+
+/// sliding window counter. the previous window's count is weighted by how much of it still
+/// overlaps the sliding window, so a boundary can't be straddled for a double burst
+#[derive(Clone,Debug)]
+pub struct SlidingWindow {
+    limit: u32,
+    window: Duration,       // time for `limit` units at the refill rate
+    window_start: Instant,
+    previous: u32,          // units counted in the window before the current one
+    current: u32,           // units counted in the current window
+    tokens: i32,            // units left after the last connection
+    ver: u64
+}
+
+impl SlidingWindow {
+    /// constructor, starts empty at `now`
+    pub fn new(limit: u32, refill_rate: &RefillRate, now: Instant) -> Self {
+        SlidingWindow {
+            limit,
+            window: SlidingWindow::length(limit, refill_rate),
+            window_start: now,
+            previous: 0,
+            current: 0,
+            tokens: limit as i32,
+            ver: 0
+        }
+    }
+
+    /// a window already holding counts, shared backends rebuild one from the totals they sync
+    pub(crate) fn counted(limit: u32, window: Duration, window_start: Instant, previous: u32, current: u32) -> Self {
+        SlidingWindow {
+            limit,
+            window,
+            window_start,
+            previous,
+            current,
+            tokens: limit.saturating_sub(current) as i32,
+            ver: 0
+        }
+    }
+
+    /// time for `limit` units at the refill rate
+    pub(crate) fn length(limit: u32, refill_rate: &RefillRate) -> Duration {
+        refill_rate
+            .interval()
+            .map_or(Duration::from_secs_f32(refill_rate.window_secs()), |interval| interval * limit.max(1))
+    }
+
+    /// units counted in the current window
+    pub(crate) fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// reads back the state written by write_state
+    pub fn read_state(reader: &mut SnapshotReader) -> Result<Self, RateLimitError> {
+        let limit = reader.get_u32()?;
+        let window = reader.get_duration()?;
+
+        if window.is_zero() {
+            return Err(RateLimitError::InvalidSnapshot(String::from("sliding window of zero length")));
+        }
+
+        Ok(SlidingWindow {
+            limit,
+            window,
+            window_start: reader.get_instant()?,
+            previous: reader.get_u32()?,
+            current: reader.get_u32()?,
+            tokens: reader.get_i32()?,
+            ver: 0
+        })
+    }
+
+    /// starts the key with fewer units than it can hold
+    pub fn with_initial_tokens(mut self, initial_tokens: u32) -> Self {
+        self.current = self.limit.saturating_sub(initial_tokens);
+        self.tokens = initial_tokens.min(self.limit) as i32;
+        self
+    }
+
+    /// window start and counts as they stand at `now`
+    fn rolled(&self, now: Instant) -> (Instant, u32, u32) {
+        let elapsed = now.saturating_duration_since(self.window_start).as_nanos();
+        let windows = elapsed / self.window.as_nanos().max(1);
+
+        match windows {
+            0 => (self.window_start, self.previous, self.current),
+            1 => (self.window_start + self.window, self.current, 0),
+            _ => {
+                let skipped = self.window.mul_f64(windows as f64);
+                (self.window_start + skipped, 0, 0)
+            }
+        }
+    }
+
+    /// units counted in the window sliding back from `now`
+    fn estimate(&self, window_start: Instant, previous: u32, current: u32, now: Instant) -> f64 {
+        let progress = now.saturating_duration_since(window_start).as_secs_f64() / self.window.as_secs_f64();
+
+        previous as f64 * (1.0 - progress) + current as f64
+    }
+}
+
+impl Limiter for SlidingWindow {
+    fn acquire(&mut self, cost: u32, now: Instant) -> Decision {
+        let cost = cost.max(1);
+        let (window_start, previous, current) = self.rolled(now);
+        let estimate = self.estimate(window_start, previous, current, now);
+
+        self.window_start = window_start;
+        self.previous = previous;
+
+        let decision = if estimate + cost as f64 <= self.limit as f64 {
+            self.current = current.saturating_add(cost);
+
+            // wrap on overflow
+            self.ver = self.ver.checked_add(1).unwrap_or(0);
+
+            Decision::Approved
+        } else {
+            self.current = current.saturating_add(1);
+
+            Decision::Denied
+        };
+
+        let used = self.estimate(self.window_start, self.previous, self.current, now);
+        self.tokens = (self.limit as f64 - used).floor() as i32;
+
+        decision
+    }
+
+    fn capacity(&self) -> u32 {
+        self.limit
+    }
+
+    fn tokens(&self) -> i32 {
+        self.tokens
+    }
+
+    /// the estimate only falls, first as the previous window slides out then as the current one does
+    fn time_until(&self, units: i32, now: Instant) -> Duration {
+        let allowed = (self.limit as f64 - units.clamp(0, self.limit as i32) as f64).max(0.0);
+        let (window_start, previous, current) = self.rolled(now);
+        let window = self.window.as_secs_f64();
+
+        let allowed_at = if current as f64 <= allowed {
+            // inside the current window while the previous one slides out
+            let progress = match previous {
+                0 => 0.0,
+                _ => (1.0 - (allowed - current as f64) / previous as f64).max(0.0)
+            };
+
+            window_start + Duration::from_secs_f64(window * progress)
+        } else {
+            // once the current window becomes the previous one
+            let progress = 1.0 - allowed / current as f64;
+
+            window_start + self.window + Duration::from_secs_f64(window * progress)
+        };
+
+        allowed_at.saturating_duration_since(now)
+    }
+
+    /// both windows have slid out
+    fn expires_at(&self) -> Instant {
+        self.window_start + self.window * 2
+    }
+
+    fn ver(&self) -> u64 {
+        self.ver
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::SlidingWindow
+    }
+
+    fn write_state(&self, writer: &mut SnapshotWriter) {
+        writer.put_u32(self.limit);
+        writer.put_duration(self.window);
+        writer.put_instant(self.window_start);
+        writer.put_u32(self.previous);
+        writer.put_u32(self.current);
+        writer.put_i32(self.tokens);
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// the previous window's count fades out as the window slides
+    #[test]
+    fn sliding_window_sequence() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // 4 units a minute
+        let mut window = SlidingWindow::new(4, &RefillRate::PerMinute(4.0), start);
+
+        let sequence = [
+            (0, 1, Decision::Approved, 3),
+            (0, 1, Decision::Approved, 2),
+            (0, 1, Decision::Approved, 1),
+            (0, 1, Decision::Approved, 0),
+            (0, 1, Decision::Denied, -1),     // denials are counted too
+            (60, 1, Decision::Denied, -2),    // the whole previous window still overlaps
+            (90, 1, Decision::Denied, -1),    // half of it, 2.5 + 1 leaves no room
+            (119, 1, Decision::Approved, 0),  // nearly none of it, 0.08 + 2 leaves room
+            (150, 2, Decision::Approved, 0),  // the last window slides out, 1.5 + 0
+            (150, 1, Decision::Denied, -1),
+            (300, 4, Decision::Approved, 0)   // both windows slid out
+        ];
+
+        for (step, (secs, cost, decision, tokens)) in sequence.into_iter().enumerate() {
+            assert_eq!(window.acquire(cost, at(secs)), decision, "step {step}");
+            assert_eq!(window.tokens(), tokens, "step {step}");
+        }
+    }
+
+    /// waits run until the estimate has room, across the window boundary if need be
+    #[test]
+    fn sliding_window_time_until() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut window = SlidingWindow::new(4, &RefillRate::PerMinute(4.0), start);
+
+        for _ in 0..4 {
+            window.acquire(1, start);
+        }
+
+        // 4 in the current window, room for one once a quarter of it has slid out
+        assert_eq!(window.time_until(1, at(30)), Duration::from_secs(45));
+        assert_eq!(window.time_until(4, at(0)), Duration::from_secs(120));
+        assert_eq!(window.time_until(0, at(0)), Duration::ZERO);
+        assert_eq!(window.expires_at(), at(120));
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use crate::enums::{RateLimitError, RefillRate};
+
+type Result<T> = std::result::Result<T,RateLimitError>;
+
+/// decoder for limiter snapshots. instants are rebased onto `now` less the downtime,
+/// so buckets refill and timers run while the server was down
+#[derive(Debug)]
+pub struct SnapshotReader<'a> {
+    now: Instant,
+    downtime: Duration,
+    bytes: &'a [u8]
+}
+
+impl<'a> SnapshotReader<'a> {
+    /// constructor, `downtime` is the wall clock time since the snapshot was taken
+    pub fn new(bytes: &'a [u8], now: Instant, downtime: Duration) -> Self {
+        SnapshotReader {
+            now,
+            downtime,
+            bytes
+        }
+    }
+
+    /// reader over a length prefixed block, sharing this reader's clock and downtime
+    pub fn nested(&self, bytes: &'a [u8]) -> Self {
+        SnapshotReader {
+            now: self.now,
+            downtime: self.downtime,
+            bytes
+        }
+    }
+
+    /// true once every byte has been read
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// moves the wall clock time since the snapshot was taken
+    pub fn set_downtime(&mut self, downtime: Duration) {
+        self.downtime = downtime;
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let (head, tail) = self.bytes
+            .split_first_chunk::<N>()
+            .ok_or(RateLimitError::InvalidSnapshot(String::from("unexpected end of snapshot")))?;
+
+        self.bytes = tail;
+
+        Ok(*head)
+    }
+
+    pub fn get_u8(&mut self) -> Result<u8> {
+        Ok(u8::from_le_bytes(self.take()?))
+    }
+
+    pub fn get_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub fn get_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    pub fn get_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub fn get_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.take()?))
+    }
+
+    pub fn get_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    /// length prefixed bytes
+    pub fn get_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.get_u32()? as usize;
+
+        if len > self.bytes.len() {
+            return Err(RateLimitError::InvalidSnapshot(String::from("unexpected end of snapshot")));
+        }
+
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    /// a collection length, capped by the bytes left so a corrupt length can't over allocate
+    pub fn get_len(&mut self) -> Result<usize> {
+        let len = self.get_u32()? as usize;
+
+        match len <= self.bytes.len() {
+            true => Ok(len),
+            false => Err(RateLimitError::InvalidSnapshot(String::from("collection longer than the snapshot")))
+        }
+    }
+
+    pub fn get_duration(&mut self) -> Result<Duration> {
+        Ok(Duration::from_nanos(self.get_u64()?))
+    }
+
+    /// rebased instant, one that would fall before the clock's earliest instant lands on `now`
+    pub fn get_instant(&mut self) -> Result<Instant> {
+        let offset = self.get_i64()? as i128 - self.downtime.as_millis() as i128;
+        let millis = Duration::from_millis(offset.unsigned_abs().min(u64::MAX as u128) as u64);
+
+        let instant = match offset >= 0 {
+            true => self.now.checked_add(millis),
+            false => self.now.checked_sub(millis)
+        };
+
+        Ok(instant.unwrap_or(self.now))
+    }
+
+    /// time left on a timer once the downtime is taken off, none when it ran out while down
+    pub fn get_remaining(&mut self) -> Result<Option<Duration>> {
+        match self.get_u64()? {
+            u64::MAX => Ok(Some(Duration::MAX)),
+            millis => Ok(Duration::from_millis(millis).checked_sub(self.downtime))
+        }
+    }
+
+    pub fn get_refill_rate(&mut self) -> Result<RefillRate> {
+        let tag = self.get_u8()?;
+        let rate = self.get_f32()?;
+
+        match tag {
+            0 => Ok(RefillRate::PerSecond(rate)),
+            1 => Ok(RefillRate::PerMinute(rate)),
+            2 => Ok(RefillRate::PerHour(rate)),
+            3 => Ok(RefillRate::PerDay(rate)),
+            _ => Err(RateLimitError::InvalidSnapshot(format!("unknown refill window {tag}")))
+        }
+    }
+}
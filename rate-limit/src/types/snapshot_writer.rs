@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use crate::enums::RefillRate;
+
+/// little endian encoder for limiter snapshots. instants are written as signed millisecond
+/// offsets from the moment the snapshot was taken, so they survive a restart
+#[derive(Debug)]
+pub struct SnapshotWriter {
+    now: Instant,
+    bytes: Vec<u8>
+}
+
+impl SnapshotWriter {
+    /// constructor, instants are written relative to `now`
+    pub fn new(now: Instant) -> Self {
+        SnapshotWriter {
+            now,
+            bytes: Vec::with_capacity(4096)
+        }
+    }
+
+    /// the encoded snapshot
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn put_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn put_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_i32(&mut self, value: i32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// length prefixed bytes
+    pub fn put_bytes(&mut self, value: &[u8]) {
+        self.put_u32(value.len() as u32);
+        self.bytes.extend_from_slice(value);
+    }
+
+    /// a collection length, written ahead of its entries
+    pub fn put_len(&mut self, len: usize) {
+        self.put_u32(len as u32);
+    }
+
+    /// whole nanoseconds, saturating at u64::MAX
+    pub fn put_duration(&mut self, value: Duration) {
+        self.put_u64(u64::try_from(value.as_nanos()).unwrap_or(u64::MAX));
+    }
+
+    /// signed milliseconds from the snapshot's `now`
+    pub fn put_instant(&mut self, value: Instant) {
+        let offset = match value.checked_duration_since(self.now) {
+            Some(ahead) => i64::try_from(ahead.as_millis()).unwrap_or(i64::MAX),
+            None => -i64::try_from(self.now.duration_since(value).as_millis()).unwrap_or(i64::MAX)
+        };
+
+        self.put_i64(offset);
+    }
+
+    /// time left on a timer in milliseconds, u64::MAX for one that never runs out
+    pub fn put_remaining(&mut self, value: Duration) {
+        match value {
+            Duration::MAX => self.put_u64(u64::MAX),
+            remaining => self.put_u64(u64::try_from(remaining.as_millis()).unwrap_or(u64::MAX - 1))
+        }
+    }
+
+    /// window tag followed by the rate
+    pub fn put_refill_rate(&mut self, value: &RefillRate) {
+        let (tag, rate) = match value {
+            RefillRate::PerSecond(rate) => (0, rate),
+            RefillRate::PerMinute(rate) => (1, rate),
+            RefillRate::PerHour(rate) => (2, rate),
+            RefillRate::PerDay(rate) => (3, rate)
+        };
+
+        self.put_u8(tag);
+        self.put_f32(*rate);
+    }
+}
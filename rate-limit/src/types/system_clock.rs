@@ -0,0 +1,13 @@
+use std::time::Instant;
+
+use crate::traits::Clock;
+
+/// reads the monotonic system clock
+#[derive(Clone,Copy,Debug,Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
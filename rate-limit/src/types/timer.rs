@@ -1,5 +1,7 @@
 use std::time::{Duration,Instant};
 
+use crate::enums::TimerStatus;
+
 #[derive(Clone,Debug,PartialEq)]
 pub struct Timer {
     expires: Box<Option<Instant>>
@@ -7,7 +9,17 @@ pub struct Timer {
 
 impl Timer {
     pub fn new(secs: u64) -> Self {
-        let expires = Instant::now().checked_add(Duration::from_secs(secs));
+        Self::starting_at(Instant::now(), secs)
+    }
+
+    /// runs for `secs` from `now` rather than from the moment it was built
+    pub fn starting_at(now: Instant, secs: u64) -> Self {
+        Self::lasting(now, Duration::from_secs(secs))
+    }
+
+    /// runs for `duration` from `now`, a duration past the clock's range never runs out
+    pub fn lasting(now: Instant, duration: Duration) -> Self {
+        let expires = now.checked_add(duration);
         let boxed = Box::new(expires);
 
         Timer {
@@ -18,4 +30,24 @@ impl Timer {
     pub fn expires(self) -> Box<Option<Instant>> {
         self.expires
     }
-}
\ No newline at end of file
+
+    /// time left on the timer, a timer that overflowed on creation never runs out
+    pub fn remaining(&self) -> Duration {
+        self.remaining_at(Instant::now())
+    }
+
+    /// time left on the timer at `now`
+    pub fn remaining_at(&self, now: Instant) -> Duration {
+        self.expires
+            .map_or(Duration::MAX, |expires| expires.saturating_duration_since(now))
+    }
+
+    /// timer status at `now`
+    pub fn status_at(&self, now: Instant) -> TimerStatus {
+        match *self.expires {
+            Some(expires) if now.gt(&expires) => TimerStatus::Expired,
+            Some(_) => TimerStatus::Running,
+            None => TimerStatus::Poison
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use std::time::{Duration, Instant};
 
-use crate::enums::{BucketStatus, Decision, RefillRate};
+use crate::{
+    enums::{Algorithm, BucketStatus, Decision, RateLimitError, RefillRate},
+    traits::{Clock, Limiter},
+    types::{SnapshotReader, SnapshotWriter}
+};
 
-const DAY_AS_SECS:f32 = 24.0 * 60.0 * 60.0;
-const HOUR_AS_SECS:f32 = 60.0 * 60.0;
-const MINUTE_AS_SECS:f32 = 60.0;
-const SECOND:f32 = 1.0;
 const MIN_BUCKET_TTL:u64 = 60 * 2; // 2 minutes
 
 #[derive(Clone,Debug)]
@@ -45,50 +45,6 @@ impl TokenBucket {
         self.last_refill
     }
 
-    /// ver getter
-    pub fn ver(&self) -> u64 {
-        self.ver
-    }
-
-    /// bucket ttl getter
-    pub fn expires_at(&self) -> Instant {
-        // bucket ttl should exceed the check window or a bucket could respawn after dropping with refilled tokens
-        let time = match self.refill_rate {
-            RefillRate::PerDay(_)    => Duration::from_secs(MIN_BUCKET_TTL.max(DAY_AS_SECS as u64)),
-            RefillRate::PerHour(_)   => Duration::from_secs(MIN_BUCKET_TTL.max(HOUR_AS_SECS as u64)),
-            RefillRate::PerMinute(_) => Duration::from_secs(MIN_BUCKET_TTL.max(MINUTE_AS_SECS as u64)),
-            RefillRate::PerSecond(_) => Duration::from_secs(MIN_BUCKET_TTL.max(MIN_BUCKET_TTL)),
-        };
-        
-        self.last_connect
-            .checked_add(time)
-            .or(Some(self.last_connect))
-            .expect("unreachable after .or()")
-    }
-   
-    /// checks ttl vs last connect and returns a BucketStatus
-    pub fn is_expired(&self) -> BucketStatus {
-        let now = Instant::now();
-
-        // bucket ttl should exceed the check window or a bucket could respawn after dropping with refilled tokens
-        let time = match self.refill_rate {
-            RefillRate::PerDay(_)    => Duration::from_secs(MIN_BUCKET_TTL.max(DAY_AS_SECS as u64)),
-            RefillRate::PerHour(_)   => Duration::from_secs(MIN_BUCKET_TTL.max(HOUR_AS_SECS as u64)),
-            RefillRate::PerMinute(_) => Duration::from_secs(MIN_BUCKET_TTL.max(MINUTE_AS_SECS as u64)),
-            RefillRate::PerSecond(_) => Duration::from_secs(MIN_BUCKET_TTL.max(MIN_BUCKET_TTL)),
-        };
-        let expire_time = self.last_connect
-            .checked_add(time)
-            .or(Some(now))
-            .expect("can't fail after the .or()");
-
-        if now >= expire_time {
-            BucketStatus::Expired
-        } else {
-            BucketStatus::NotExpired
-        }
-    }
-
     /// sets the bucket capacity
     pub fn with_capacity(mut self, capacity: u32) -> Self {
         self.capacity = capacity;
@@ -108,47 +64,55 @@ impl TokenBucket {
         self
     }
 
+    /// starts the bucket's clock at `now` rather than the moment it was built
+    pub fn starting_at(mut self, now: Instant) -> Self {
+        self.last_connect = now;
+        self.last_refill = now;
+        self
+    }
+
+    /// reads back the state written by write_state
+    pub fn read_state(reader: &mut SnapshotReader) -> Result<Self, RateLimitError> {
+        Ok(TokenBucket {
+            capacity: reader.get_u32()?,
+            tokens: reader.get_i32()?,
+            refill_rate: reader.get_refill_rate()?,
+            last_connect: reader.get_instant()?,
+            last_refill: reader.get_instant()?,
+            ver: 0
+        })
+    }
+
     /// caculates the number of new tokens to be added since last connection and adds them to the bucket
-    fn refill(&mut self) -> i32 {
+    fn refill(&mut self, now: Instant) -> i32 {
         
         // return early on an expired bucket
-        if self.is_expired() == BucketStatus::Expired {
+        if self.is_expired(now) == BucketStatus::Expired {
             return 0;
         }
 
-        let time_since_last_connect = self.last_connect.elapsed().as_secs_f32();
-        let time_since_last_refill = self.last_refill.elapsed().as_secs_f32();
+        let time_since_last_connect = now.saturating_duration_since(self.last_connect).as_secs_f32();
+        let time_since_last_refill = now.saturating_duration_since(self.last_refill).as_secs_f32();
 
-        // determine refill rate
-        let window_expired = match self.refill_rate {
-            RefillRate::PerDay(_) => time_since_last_connect.ge(&DAY_AS_SECS),
-            RefillRate::PerHour(_) => time_since_last_connect.ge(&HOUR_AS_SECS),
-            RefillRate::PerMinute(_) => time_since_last_connect.ge(&MINUTE_AS_SECS),
-            RefillRate::PerSecond(_) => time_since_last_connect.ge(&SECOND)
-        };
-
-        // early return with a full bucket if the window has expired
-        if window_expired {
+        // early return with a full bucket if the window has expired, the refill clock restarts
+        // so the quiet time isn't counted a second time on the next connection
+        if time_since_last_connect.ge(&self.refill_rate.window_secs()) {
             self.tokens = self.capacity as i32;
+            self.last_refill = now;
+            self.last_connect = now;
             return self.tokens;
         }
 
-        // calculate the refill rate per second
-        let refill_rate = match self.refill_rate {
-            RefillRate::PerDay(n) => n / DAY_AS_SECS,
-            RefillRate::PerHour(n) => n / HOUR_AS_SECS,
-            RefillRate::PerMinute(n) => n / MINUTE_AS_SECS,
-            RefillRate::PerSecond(n) => n
-        };
+        let refill_rate = self.refill_rate.tokens_per_sec();
 
         let refill_amount = (time_since_last_refill * refill_rate).trunc() as i32;
 
         if refill_amount > 0 {
             self.tokens += refill_amount;
-            self.last_refill = Instant::now();
-            self.last_connect = Instant::now();
+            self.last_refill = now;
+            self.last_connect = now;
         } else {
-            self.last_connect = Instant::now();
+            self.last_connect = now;
         }
 
         // ensure the bucket does not exceed max capacity
@@ -157,19 +121,36 @@ impl TokenBucket {
         self.tokens
     }
 
+    /// capacity getter
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
     /// tokens remaining getter  
     pub fn tokens(&self) -> i32 {
         self.tokens
     }
 
-    /// returns the number of tokens remaining after connection
-    pub fn drip(&mut self) -> Decision {
-        let tokens = self.refill();
+    /// spends a single token at the clock's current time
+    pub fn drip(&mut self, clock: &dyn Clock) -> Decision {
+        self.drip_n(1, clock)
+    }
+
+    /// spends `cost` tokens when the bucket holds enough of them. a denial only costs a single token
+    /// whatever the weight, so heavy requests don't race the bucket towards the blacklist
+    pub fn drip_n(&mut self, cost: u32, clock: &dyn Clock) -> Decision {
+        self.acquire(cost, clock.now())
+    }
+}
 
-        self.tokens -= 1;
+impl Limiter for TokenBucket {
+    fn acquire(&mut self, cost: u32, now: Instant) -> Decision {
+        let tokens = self.refill(now);
+        let cost = cost.max(1).min(i32::MAX as u32) as i32;
 
-        if tokens > 0 {
-            self.last_connect = Instant::now();
+        if tokens >= cost {
+            self.tokens -= cost;
+            self.last_connect = now;
 
             // wrap on overflow
             if self.ver + 1 == u64::MAX {
@@ -181,13 +162,113 @@ impl TokenBucket {
             Decision::Approved
             
         } else {
+            self.tokens -= 1;
+
             Decision::Denied
         }
     }
+
+    /// the opening connection's first token is free, heavier requests pay the rest up front
+    fn open(&mut self, cost: u32, now: Instant) -> Decision {
+        match cost {
+            0..=1 => Decision::Approved,
+            2.. => self.acquire(cost - 1, now)
+        }
+    }
+
+    fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    fn tokens(&self) -> i32 {
+        self.tokens
+    }
+
+    /// whichever comes first of the refill or the window expiring
+    fn time_until(&self, units: i32, now: Instant) -> Duration {
+        let needed = units.min(self.capacity as i32) - self.tokens;
+
+        if needed <= 0 {
+            return Duration::ZERO;
+        }
+
+        let since_connect = now.saturating_duration_since(self.last_connect).as_secs_f32();
+        let since_refill = now.saturating_duration_since(self.last_refill).as_secs_f32();
+
+        let by_window = (self.refill_rate.window_secs() - since_connect).max(0.0);
+        let by_refill = match self.refill_rate.tokens_per_sec() {
+            rate if rate > 0.0 => (needed as f32 / rate - since_refill).max(0.0),
+            _ => by_window
+        };
+
+        Duration::from_secs_f32(by_refill.min(by_window))
+    }
+
+    /// bucket ttl should exceed the check window or a bucket could respawn after dropping with refilled tokens
+    fn expires_at(&self) -> Instant {
+        let window = self.refill_rate.window_secs() as u64;
+        let time = Duration::from_secs(MIN_BUCKET_TTL.max(window));
+
+        self.last_connect
+            .checked_add(time)
+            .or(Some(self.last_connect))
+            .expect("unreachable after .or()")
+    }
+
+    fn ver(&self) -> u64 {
+        self.ver
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::TokenBucket
+    }
+
+    fn write_state(&self, writer: &mut SnapshotWriter) {
+        writer.put_u32(self.capacity);
+        writer.put_i32(self.tokens);
+        writer.put_refill_rate(&self.refill_rate);
+        writer.put_instant(self.last_connect);
+        writer.put_instant(self.last_refill);
+    }
 }
 
 impl Default for TokenBucket {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// a drained bucket refills at the rate and tops out after a quiet window
+    #[test]
+    fn token_bucket_sequence() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        let mut bucket = TokenBucket::new()
+            .with_capacity(3)
+            .with_initial_tokens(3)
+            .with_refill_rate(RefillRate::PerMinute(6.0))
+            .starting_at(start);
+
+        let sequence = [
+            (0, 1, Decision::Approved),
+            (0, 1, Decision::Approved),
+            (0, 1, Decision::Approved),
+            (0, 1, Decision::Denied),     // denials dig below empty
+            (9, 1, Decision::Denied),     // no whole token yet
+            (19, 1, Decision::Denied),    // one token lands, still in debt
+            (39, 1, Decision::Denied),    // two more, back to empty
+            (59, 1, Decision::Approved),  // two more pay for one
+            (120, 2, Decision::Approved), // a quiet window tops the bucket up
+            (120, 2, Decision::Denied)    // without counting the quiet time twice
+        ];
+
+        for (step, (secs, cost, decision)) in sequence.into_iter().enumerate() {
+            assert_eq!(bucket.acquire(cost, at(secs)), decision, "step {step}");
+        }
+    }
+}